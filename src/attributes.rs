@@ -0,0 +1,306 @@
+// Named, typed attributes attached to the nodes or edges of a graph.
+//
+// Each attribute name has a single type and an optional default value, which is
+// returned for any key that hasn't had the attribute set explicitly. Names are
+// made of ASCII letters, digits and '_', so that they can be written to a file.
+
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+use std::str::FromStr;
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum AttributeValue {
+    Bool  (bool),
+    Int   (i64),
+    Float (f64),
+    Str   (String),
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum AttributeType {
+    Bool,
+    Int,
+    Float,
+    Str,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum AttributeError {
+    InvalidName      (String),
+    UnknownAttribute (String),
+    TypeMismatch     { name : String, expected : AttributeType, found : AttributeType },
+    InvalidValue     { name : String, expected : AttributeType, text : String },
+}
+
+pub struct AttributeStore<K> where K : Copy + Eq + Hash + Ord {
+    // A BTreeMap so that attributes are always listed in the same order.
+    columns : BTreeMap<String, AttributeColumn<K>>,
+}
+
+struct AttributeColumn<K> where K : Copy + Eq + Hash + Ord {
+    attribute_type : AttributeType,
+    default        : Option<AttributeValue>,
+    values         : HashMap<K, AttributeValue>,
+}
+
+impl AttributeValue {
+    pub fn attribute_type(&self) -> AttributeType {
+        match *self {
+            AttributeValue::Bool(_)  => AttributeType::Bool,
+            AttributeValue::Int(_)   => AttributeType::Int,
+            AttributeValue::Float(_) => AttributeType::Float,
+            AttributeValue::Str(_)   => AttributeType::Str,
+        }
+    }
+
+    // Parses the textual form written by Display.
+    pub fn parse(text : &str, attribute_type : AttributeType) -> Option<AttributeValue> {
+        match attribute_type {
+            AttributeType::Bool  => text.parse::<bool>().ok().map(AttributeValue::Bool),
+            AttributeType::Int   => text.parse::<i64>().ok().map(AttributeValue::Int),
+            AttributeType::Float => text.parse::<f64>().ok().map(AttributeValue::Float),
+            AttributeType::Str   => Some(AttributeValue::Str(String::from(text))),
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self { AttributeValue::Bool(b) => Some(b), _ => None }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match *self { AttributeValue::Int(i) => Some(i), _ => None }
+    }
+
+    // Integers are widened to floats, so that weights can be given either way.
+    pub fn as_float(&self) -> Option<f64> {
+        match *self {
+            AttributeValue::Float(x) => Some(x),
+            AttributeValue::Int(i)   => Some(i as f64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match *self { AttributeValue::Str(ref s) => Some(s), _ => None }
+    }
+}
+
+impl fmt::Display for AttributeValue {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AttributeValue::Bool(b)      => write!(f, "{}", b),
+            AttributeValue::Int(i)       => write!(f, "{}", i),
+            AttributeValue::Float(x)     => write!(f, "{}", x),
+            AttributeValue::Str(ref s)   => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<bool> for AttributeValue {
+    fn from(b : bool) -> AttributeValue { AttributeValue::Bool(b) }
+}
+
+impl From<i64> for AttributeValue {
+    fn from(i : i64) -> AttributeValue { AttributeValue::Int(i) }
+}
+
+impl From<f64> for AttributeValue {
+    fn from(x : f64) -> AttributeValue { AttributeValue::Float(x) }
+}
+
+impl From<String> for AttributeValue {
+    fn from(s : String) -> AttributeValue { AttributeValue::Str(s) }
+}
+
+impl<'a> From<&'a str> for AttributeValue {
+    fn from(s : &'a str) -> AttributeValue { AttributeValue::Str(String::from(s)) }
+}
+
+impl AttributeType {
+    pub fn name(self) -> &'static str {
+        match self {
+            AttributeType::Bool  => "bool",
+            AttributeType::Int   => "int",
+            AttributeType::Float => "float",
+            AttributeType::Str   => "str",
+        }
+    }
+}
+
+impl fmt::Display for AttributeType {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for AttributeType {
+    type Err = ();
+
+    fn from_str(s : &str) -> Result<AttributeType, ()> {
+        match s {
+            "bool"  => Ok(AttributeType::Bool),
+            "int"   => Ok(AttributeType::Int),
+            "float" => Ok(AttributeType::Float),
+            "str"   => Ok(AttributeType::Str),
+            _ => Err(()),
+        }
+    }
+}
+
+pub fn is_attribute_name(name : &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl fmt::Display for AttributeError {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AttributeError::InvalidName(ref name) =>
+                write!(f, "Attribute name \"{}\" must be made of letters, digits and '_'.", name),
+            AttributeError::UnknownAttribute(ref name) =>
+                write!(f, "Attribute {} has not been declared.", name),
+            AttributeError::TypeMismatch { ref name, expected, found } =>
+                write!(f, "Attribute {} has type {}, but was given a value of type {}.", name, expected, found),
+            AttributeError::InvalidValue { ref name, expected, ref text } =>
+                write!(f, "Attribute {} has type {}, but {} is not a valid value.", name, expected, text),
+        }
+    }
+}
+
+impl Error for AttributeError {}
+
+impl<K> AttributeStore<K> where K : Copy + Eq + Hash + Ord {
+
+    pub fn new() -> AttributeStore<K> {
+        AttributeStore { columns : BTreeMap::new() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    // Declares an attribute, or changes the default of an existing one.
+    // Fails if the name isn't a valid attribute name, if the attribute already
+    // exists with a different type, or if the default doesn't match the type.
+    pub fn declare(&mut self, name : &str, attribute_type : AttributeType, default : Option<AttributeValue>) -> Result<(), AttributeError> {
+        if !is_attribute_name(name) {
+            return Err(AttributeError::InvalidName(String::from(name)))
+        }
+        if let Some(ref d) = default {
+            if d.attribute_type() != attribute_type {
+                return Err(AttributeError::TypeMismatch { name : String::from(name), expected : attribute_type, found : d.attribute_type() })
+            }
+        }
+        if let Some(column) = self.columns.get_mut(name) {
+            if column.attribute_type != attribute_type {
+                return Err(AttributeError::TypeMismatch { name : String::from(name), expected : column.attribute_type, found : attribute_type })
+            }
+            column.default = default;
+            return Ok(())
+        }
+        self.columns.insert(String::from(name), AttributeColumn { attribute_type, default, values : HashMap::new() });
+        Ok(())
+    }
+
+    // Removes an attribute entirely, along with all of its values.
+    pub fn undeclare(&mut self, name : &str) {
+        self.columns.remove(name);
+    }
+
+    // The declared attribute names, in alphabetical order.
+    pub fn names<'a>(&'a self) -> Box<dyn Iterator<Item = &'a str> + 'a> {
+        Box::new(self.columns.keys().map(|name| name.as_str()))
+    }
+
+    pub fn attribute_type(&self, name : &str) -> Option<AttributeType> {
+        self.columns.get(name).map(|column| column.attribute_type)
+    }
+
+    pub fn default_value(&self, name : &str) -> Option<&AttributeValue> {
+        self.columns.get(name).and_then(|column| column.default.as_ref())
+    }
+
+    // The value of the attribute for this key, falling back to the default.
+    pub fn get(&self, key : K, name : &str) -> Option<&AttributeValue> {
+        self.columns.get(name).and_then(|column| column.values.get(&key).or(column.default.as_ref()))
+    }
+
+    // The value explicitly set for this key, ignoring any default.
+    pub fn get_explicit(&self, key : K, name : &str) -> Option<&AttributeValue> {
+        self.columns.get(name).and_then(|column| column.values.get(&key))
+    }
+
+    // Sets the value of an attribute, returning the previous explicitly set value.
+    // Setting an undeclared attribute declares it, with no default.
+    pub fn set<V>(&mut self, key : K, name : &str, value : V) -> Result<Option<AttributeValue>, AttributeError>
+        where V : Into<AttributeValue> {
+        let value = value.into();
+        if !self.columns.contains_key(name) {
+            self.declare(name, value.attribute_type(), None)?;
+        }
+        let column = self.columns.get_mut(name).unwrap();
+        if column.attribute_type != value.attribute_type() {
+            return Err(AttributeError::TypeMismatch { name : String::from(name), expected : column.attribute_type, found : value.attribute_type() })
+        }
+        Ok(column.values.insert(key, value))
+    }
+
+    // Parses the text as a value of the attribute's declared type, and sets it.
+    pub fn set_from_str(&mut self, key : K, name : &str, text : &str) -> Result<Option<AttributeValue>, AttributeError> {
+        let attribute_type = match self.attribute_type(name) {
+            Some(t) => t,
+            None => return Err(AttributeError::UnknownAttribute(String::from(name))),
+        };
+        match AttributeValue::parse(text, attribute_type) {
+            Some(value) => self.set(key, name, value),
+            None => Err(AttributeError::InvalidValue { name : String::from(name), expected : attribute_type, text : String::from(text) }),
+        }
+    }
+
+    // Removes an explicitly set value, so that the key reverts to the default.
+    pub fn remove(&mut self, key : K, name : &str) -> Option<AttributeValue> {
+        self.columns.get_mut(name).and_then(|column| column.values.remove(&key))
+    }
+
+    // Removes every explicitly set value for this key.
+    pub fn remove_all(&mut self, key : K) {
+        for column in self.columns.values_mut() {
+            column.values.remove(&key);
+        }
+    }
+
+    // A copy with the same declarations, and each explicitly set value moved to the
+    // key that f gives for its old key. Values for which f gives None are left out.
+    pub fn map_keys<L, F>(&self, mut f : F) -> AttributeStore<L>
+        where L : Copy + Eq + Hash + Ord, F : FnMut(K) -> Option<L> {
+        let columns = self.columns.iter().map(|(name, column)| {
+            let values = column.values.iter().filter_map(|(k, v)| f(*k).map(|l| (l, v.clone()))).collect();
+            (name.clone(), AttributeColumn { attribute_type : column.attribute_type, default : column.default.clone(), values })
+        }).collect();
+        AttributeStore { columns }
+    }
+
+    // Explicitly set values of one attribute, in order of key. Defaults are not included.
+    pub fn iter(&self, name : &str) -> Vec<(K, &AttributeValue)> {
+        let mut values : Vec<(K, &AttributeValue)> = match self.columns.get(name) {
+            Some(column) => column.values.iter().map(|(k, v)| (*k, v)).collect(),
+            None => Vec::new(),
+        };
+        values.sort_by_key(|&(k, _)| k);
+        values
+    }
+
+    // Every attribute that has a value for this key, including defaults, in order of name.
+    pub fn attributes_of(&self, key : K) -> Vec<(&str, &AttributeValue)> {
+        self.columns.keys()
+                    .filter_map(|name| self.get(key, name).map(|v| (name.as_str(), v)))
+                    .collect()
+    }
+}
+
+impl<K> Default for AttributeStore<K> where K : Copy + Eq + Hash + Ord {
+    fn default() -> AttributeStore<K> {
+        AttributeStore::new()
+    }
+}
@@ -0,0 +1,411 @@
+// A command-line tool for looking at graph files and converting between formats.
+//
+//     graph stats    [--from FORMAT] [FILE]
+//     graph convert  [--from FORMAT] [--to FORMAT] [FILE [OUTPUT]]
+//     graph bfs      [--from FORMAT] START [FILE]
+//     graph dfs      [--from FORMAT] START [FILE]
+//     graph path     [--from FORMAT] SOURCE DEST [FILE]
+//     graph validate [--from FORMAT] [FILE]
+//
+// The graph is read from FILE, or from standard input if there's no FILE or it's
+// "-". Formats are taken from --from and --to, or else from the extensions of
+// the files, and the native format is used for anything else. Nodes are named
+// by their labels, or by their indices in formats without labels. A csv or tsv
+// file has a weight column if its header has one called weight, and one is
+// written if the graph has an int or float weight.
+//
+// The exit status is 0 on success, 1 if the command fails, if validate finds an
+// error or if path finds no path, and 2 for a usage error.
+
+extern crate graph;
+
+use graph::Graph;
+use graph::graph_builders;
+use graph::graph_builders::{adjacency_matrix, binary, dimacs, dot, edge_list, gml, graphml, matrix_market, metis, node_link, pajek};
+use graph::graph_builders::{Diagnostic, ParseError, ParseMode, Severity};
+use graph::graph_builders::adjacency_matrix::Diagonal;
+use graph::attributes::AttributeType;
+use graph::graph_builders::edge_list::{Column, EdgeListOptions};
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::hash::Hash;
+use std::io;
+use std::io::BufWriter;
+use std::io::prelude::*;
+use std::process;
+use std::str;
+use std::str::FromStr;
+
+const USAGE : &str = "usage: graph stats    [--from FORMAT] [FILE]
+       graph convert  [--from FORMAT] [--to FORMAT] [FILE [OUTPUT]]
+       graph bfs      [--from FORMAT] START [FILE]
+       graph dfs      [--from FORMAT] START [FILE]
+       graph path     [--from FORMAT] SOURCE DEST [FILE]
+       graph validate [--from FORMAT] [FILE]
+
+FILE defaults to standard input. FORMAT is one of native, binary, csv, tsv,
+dimacs, dot, gml, graphml, json, matrix, mtx, metis and pajek.";
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Format {
+    Native,
+    Binary,
+    Csv,
+    Tsv,
+    Dimacs,         // Shortest-path problems
+    Dot,
+    Gml,
+    Graphml,
+    Json,           // NetworkX and D3 node-link
+    Matrix,         // Plain-text adjacency matrices
+    MatrixMarket,
+    Metis,
+    Pajek,
+}
+
+const FORMAT_NAMES : [(&str, Format); 13] = [("native", Format::Native), ("binary", Format::Binary), ("csv", Format::Csv),
+                                             ("tsv", Format::Tsv), ("dimacs", Format::Dimacs), ("dot", Format::Dot),
+                                             ("gml", Format::Gml), ("graphml", Format::Graphml), ("json", Format::Json),
+                                             ("matrix", Format::Matrix), ("mtx", Format::MatrixMarket),
+                                             ("metis", Format::Metis), ("pajek", Format::Pajek)];
+
+const EXTENSIONS : [(&str, Format); 14] = [("bin", Format::Binary), ("csv", Format::Csv), ("tsv", Format::Tsv),
+                                           ("gr", Format::Dimacs), ("dot", Format::Dot), ("gv", Format::Dot),
+                                           ("gml", Format::Gml), ("graphml", Format::Graphml), ("json", Format::Json),
+                                           ("adj", Format::Matrix), ("mtx", Format::MatrixMarket),
+                                           ("graph", Format::Metis), ("metis", Format::Metis), ("net", Format::Pajek)];
+
+enum Failure {
+    Usage  (String),
+    Failed (String),
+}
+
+impl From<io::Error> for Failure {
+    fn from(e : io::Error) -> Failure {
+        Failure::Failed(e.to_string())
+    }
+}
+
+// The arguments after the command name.
+struct Arguments {
+    from       : Option<Format>,
+    to         : Option<Format>,
+    positional : Vec<String>,
+}
+
+// A graph as read, with labels if the format has them.
+enum Loaded {
+    Indices (Graph<usize>),
+    Labels  (Graph<String>),
+}
+
+fn main() {
+    let args : Vec<String> = env::args().skip(1).collect();
+    match run(&args) {
+        Ok(true) => (),
+        Ok(false) => process::exit(1),
+        Err(Failure::Usage(message)) => {
+            eprintln!("graph: {}\n{}", message, USAGE);
+            process::exit(2)
+        },
+        Err(Failure::Failed(message)) => {
+            eprintln!("graph: {}", message);
+            process::exit(1)
+        },
+    }
+}
+
+// Returns whether the command succeeded.
+fn run(args : &[String]) -> Result<bool, Failure> {
+    let command = match args.first() {
+        Some(command) => command.as_str(),
+        None => return Err(Failure::Usage(String::from("a command is needed"))),
+    };
+    if command == "help" || command == "--help" || command == "-h" {
+        println!("{}", USAGE);
+        return Ok(true)
+    }
+    let arguments = parse_arguments(&args[1..])?;
+    let (min, max) = match command {
+        "stats" | "validate" => (0, 1),
+        "convert"            => (0, 2),
+        "bfs" | "dfs"        => (1, 2),
+        "path"               => (2, 3),
+        _ => return Err(Failure::Usage(format!("unknown command {}", command))),
+    };
+    let count = arguments.positional.len();
+    if count < min || count > max {
+        return Err(Failure::Usage(format!("{} takes {} to {} arguments, but was given {}", command, min, max, count)))
+    }
+    if arguments.to.is_some() && command != "convert" {
+        return Err(Failure::Usage(String::from("only convert takes --to")))
+    }
+
+    // The input file comes after any node labels, except for convert.
+    let input = if command == "convert" { arguments.positional.first() } else { arguments.positional.get(min) };
+    let input = input.map(|name| name.as_str()).filter(|&name| name != "-");
+    let input_name = input.unwrap_or("<stdin>");
+    let from = arguments.from.unwrap_or_else(|| format_of(input));
+    let bytes = match input {
+        Some(filename) => fs::read(filename).map_err(|e| Failure::Failed(format!("{}: {}", filename, e)))?,
+        None => {
+            let mut bytes = Vec::new();
+            io::stdin().read_to_end(&mut bytes)?;
+            bytes
+        },
+    };
+
+    if command == "validate" {
+        return validate(&bytes, from, input_name)
+    }
+    let loaded = load(&bytes, from, ParseMode::Strict).map_err(|e| Failure::Failed(format!("{}: {}", input_name, e)))?;
+    match loaded {
+        Loaded::Indices(g) => run_command(command, &g, false, &arguments),
+        Loaded::Labels(g)  => run_command(command, &g, true, &arguments),
+    }
+}
+
+fn parse_arguments(args : &[String]) -> Result<Arguments, Failure> {
+    let mut arguments = Arguments { from : None, to : None, positional : Vec::new() };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--from" | "--to" => {
+                let name = match args.next() {
+                    Some(name) => name,
+                    None => return Err(Failure::Usage(format!("{} needs a format", arg))),
+                };
+                let format = match FORMAT_NAMES.iter().find(|&&(n, _)| n == name) {
+                    Some(&(_, format)) => format,
+                    None => return Err(Failure::Usage(format!("unknown format {}", name))),
+                };
+                if arg == "--from" { arguments.from = Some(format) } else { arguments.to = Some(format) }
+            },
+            _ if arg.starts_with("--") => return Err(Failure::Usage(format!("unknown option {}", arg))),
+            _ => arguments.positional.push(arg.clone()),
+        }
+    }
+    Ok(arguments)
+}
+
+// The format given by a file's extension, if it has one that's known.
+fn format_of_extension(filename : Option<&str>) -> Option<Format> {
+    let extension = filename.and_then(|name| name.rsplit_once('.')).map(|(_, extension)| extension.to_lowercase())?;
+    EXTENSIONS.iter().find(|&&(e, _)| e == extension).map(|&(_, format)| format)
+}
+
+fn format_of(filename : Option<&str>) -> Format {
+    format_of_extension(filename).unwrap_or(Format::Native)
+}
+
+fn load(bytes : &[u8], format : Format, mode : ParseMode) -> Result<Loaded, ParseError> {
+    if format == Format::Binary {
+        let graph = binary::GraphView::from_bytes(bytes).and_then(|view| view.to_graph())?;
+        return Ok(Loaded::Labels(graph))
+    }
+    let text = match str::from_utf8(bytes) {
+        Ok(text) => text.as_bytes(),
+        Err(_) => return Err(ParseError::Io(io::Error::new(io::ErrorKind::InvalidData, "the input isn't UTF-8 text"))),
+    };
+    Ok(match format {
+        Format::Native if has_nodes(text) => Loaded::Labels(graph_builders::from_reader_with_nodes_and_mode(text, mode)?),
+        Format::Native       => Loaded::Indices(graph_builders::from_reader_with_mode(text, mode)?),
+        Format::Binary       => unreachable!(),
+        Format::Csv          => Loaded::Labels(edge_list::from_edge_list_reader_with_mode(text, &edge_list_options_for_text(',', text), mode)?),
+        Format::Tsv          => Loaded::Labels(edge_list::from_edge_list_reader_with_mode(text, &edge_list_options_for_text('\t', text), mode)?),
+        Format::Dimacs       => Loaded::Indices(dimacs::from_dimacs_sp_reader_with_mode(text, mode)?),
+        Format::Dot          => Loaded::Labels(dot::from_dot_reader_with_options(text, mode, true)?),
+        Format::Gml          => Loaded::Labels(gml::from_gml_reader_with_mode(text, mode)?),
+        Format::Graphml      => Loaded::Labels(graphml::from_graphml_reader_with_mode(text, mode)?),
+        Format::Json         => Loaded::Labels(node_link::from_node_link_reader_with_mode(text, mode)?),
+        Format::Matrix       => Loaded::Indices(adjacency_matrix::from_adjacency_matrix_reader_with_options(text, mode, Diagonal::SelfLoops)?),
+        Format::MatrixMarket => Loaded::Indices(matrix_market::from_matrix_market_reader_with_mode(text, mode)?),
+        Format::Metis        => Loaded::Indices(metis::from_metis_reader_with_options(text, mode, false)?),
+        Format::Pajek        => Loaded::Labels(pajek::from_pajek_reader_with_mode(text, mode)?),
+    })
+}
+
+// The default options, with a weight column if the header has a column called
+// weight. The header is the first line that isn't blank or a comment.
+fn edge_list_options_for_text(delimiter : char, text : &[u8]) -> EdgeListOptions {
+    let header = str::from_utf8(text).unwrap_or("").lines()
+                     .find(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'));
+    let has_weight = header.is_some_and(|line| line.split(delimiter).any(|field| field.trim().trim_matches('"') == "weight"));
+    edge_list_options(delimiter, has_weight)
+}
+
+// The default options, with a weight column if the graph has an int or float weight.
+fn edge_list_options_for_graph<T>(delimiter : char, graph : &Graph<T>) -> EdgeListOptions
+    where T : Clone + Eq + Hash
+{
+    let has_weight = matches!(graph.edge_attributes().attribute_type("weight"), Some(AttributeType::Int) | Some(AttributeType::Float));
+    edge_list_options(delimiter, has_weight)
+}
+
+fn edge_list_options(delimiter : char, has_weight : bool) -> EdgeListOptions {
+    EdgeListOptions { delimiter,
+                      weight_column : if has_weight { Some(Column::Name(String::from("weight"))) } else { None },
+                      ..EdgeListOptions::csv() }
+}
+
+// Whether a file in the native format has node labels.
+fn has_nodes(text : &[u8]) -> bool {
+    text.split(|&b| b == b'\n').any(|line| line.trim_ascii() == b"nodes:")
+}
+
+// Prints every problem found, and returns whether there were no errors.
+fn validate(bytes : &[u8], format : Format, input_name : &str) -> Result<bool, Failure> {
+    let diagnostics : Vec<Diagnostic> = match format {
+        Format::Native => graph_builders::validate_reader(bytes)?,
+        _ => match load(bytes, format, ParseMode::Lenient) {
+            Ok(_) => Vec::new(),
+            Err(ParseError::Invalid(diagnostics)) => diagnostics,
+            Err(ParseError::Io(e)) => return Err(Failure::Failed(format!("{}: {}", input_name, e))),
+        },
+    };
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    if diagnostics.is_empty() {
+        writeln!(out, "{}: no problems found", input_name)?;
+    }
+    for d in &diagnostics {
+        writeln!(out, "{}: {}", input_name, d)?;
+    }
+    Ok(diagnostics.iter().all(|d| d.severity == Severity::Warning))
+}
+
+fn run_command<T>(command : &str, graph : &Graph<T>, labelled : bool, arguments : &Arguments) -> Result<bool, Failure>
+    where T : Clone + Eq + Hash + Display + FromStr
+{
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    let labels = &arguments.positional;
+    let succeeded = match command {
+        "stats" => {
+            stats(graph, &mut out)?;
+            true
+        },
+        "convert" => {
+            let output = labels.get(1).map(|name| name.as_str()).filter(|&name| name != "-");
+            let to = match arguments.to.or_else(|| format_of_extension(output)) {
+                Some(to) => to,
+                None => return Err(Failure::Usage(String::from("convert needs --to, or an OUTPUT with a known extension"))),
+            };
+            match output {
+                Some(filename) => write_file(graph, labelled, to, filename).map_err(|e| Failure::Failed(format!("{}: {}", filename, e)))?,
+                None => write(graph, labelled, to, &mut out)?,
+            }
+            true
+        },
+        "bfs" => {
+            let start = find_node(graph, &labels[0])?;
+            let mut order = Vec::new();
+            graph.breadth_first_iter_from_index(|node| order.push(node.to_string()), |_, _| (), start);
+            for label in order {
+                writeln!(out, "{}", label)?;
+            }
+            true
+        },
+        "dfs" => {
+            let start = find_node(graph, &labels[0])?;
+            let mut order = Vec::new();
+            graph.depth_first_iter_from_index(|node| order.push(node.to_string()), |_| (), |_, _, _, _| (), start);
+            for label in order {
+                writeln!(out, "{}", label)?;
+            }
+            true
+        },
+        "path" => {
+            let (source, dest) = (find_node(graph, &labels[0])?, find_node(graph, &labels[1])?);
+            match graph.shortest_path_from_index(source, dest) {
+                Some(path) => {
+                    for index in path {
+                        writeln!(out, "{}", graph.node_from_index(index))?;
+                    }
+                    true
+                },
+                None => {
+                    eprintln!("graph: there's no path from {} to {}", labels[0], labels[1]);
+                    false
+                },
+            }
+        },
+        _ => unreachable!(),
+    };
+    out.flush()?;
+    Ok(succeeded)
+}
+
+fn find_node<T>(graph : &Graph<T>, label : &str) -> Result<usize, Failure>
+    where T : Clone + Eq + Hash + FromStr
+{
+    label.parse::<T>().ok()
+         .filter(|node| graph.contains_node(node))
+         .map(|node| graph.index_from_node(node))
+         .ok_or_else(|| Failure::Failed(format!("there's no node {} in the graph", label)))
+}
+
+// Counts, and the number of vertices of each degree. The degree of a vertex of
+// a directed graph is the number of edges out of it.
+fn stats<T, W>(graph : &Graph<T>, out : &mut W) -> io::Result<()>
+    where T : Clone + Eq + Hash, W : Write
+{
+    let mut degrees = BTreeMap::new();
+    for index in 0..graph.number_of_vertices() {
+        *degrees.entry(graph.get_degree_from_index(index)).or_insert(0) += 1;
+    }
+    writeln!(out, "directed: {}", graph.is_directed())?;
+    writeln!(out, "vertices: {}", graph.number_of_vertices())?;
+    writeln!(out, "edges: {}", graph.number_of_edges())?;
+    writeln!(out, "{}:", if graph.is_directed() { "out-degree distribution" } else { "degree distribution" })?;
+    for (degree, count) in degrees {
+        writeln!(out, "  {}: {}", degree, count)?;
+    }
+    Ok(())
+}
+
+// Writes to a temporary file that replaces the output only once it's complete,
+// so that a graph that can't be written leaves the output as it was.
+fn write_file<T>(graph : &Graph<T>, labelled : bool, format : Format, filename : &str) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display
+{
+    match format {
+        Format::Native if labelled => graph_builders::to_file_with_nodes(graph, filename),
+        Format::Native       => graph_builders::to_file(graph, filename),
+        Format::Binary       => binary::to_binary_file(graph, filename),
+        Format::Csv          => edge_list::to_edge_list_file(graph, filename, &edge_list_options_for_graph(',', graph)),
+        Format::Tsv          => edge_list::to_edge_list_file(graph, filename, &edge_list_options_for_graph('\t', graph)),
+        Format::Dimacs       => dimacs::to_dimacs_sp_file(graph, filename),
+        Format::Dot          => dot::to_dot_file(graph, filename),
+        Format::Gml          => gml::to_gml_file(graph, filename),
+        Format::Graphml      => graphml::to_graphml_file(graph, filename),
+        Format::Json         => node_link::to_node_link_file(graph, filename),
+        Format::Matrix       => adjacency_matrix::to_adjacency_matrix_file(graph, filename, Diagonal::SelfLoops),
+        Format::MatrixMarket => matrix_market::to_matrix_market_file(graph, filename),
+        Format::Metis        => metis::to_metis_file(graph, filename),
+        Format::Pajek        => pajek::to_pajek_file(graph, filename),
+    }
+}
+
+fn write<T, W>(graph : &Graph<T>, labelled : bool, format : Format, writer : W) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display, W : Write
+{
+    match format {
+        Format::Native if labelled => graph_builders::write_graph_with_nodes(graph, writer),
+        Format::Native       => graph_builders::write_graph(graph, writer),
+        Format::Binary       => binary::write_binary(graph, writer),
+        Format::Csv          => edge_list::write_edge_list(graph, writer, &edge_list_options_for_graph(',', graph)),
+        Format::Tsv          => edge_list::write_edge_list(graph, writer, &edge_list_options_for_graph('\t', graph)),
+        Format::Dimacs       => dimacs::write_dimacs_sp(graph, writer),
+        Format::Dot          => dot::write_dot(graph, writer),
+        Format::Gml          => gml::write_gml(graph, writer),
+        Format::Graphml      => graphml::write_graphml(graph, writer),
+        Format::Json         => node_link::write_node_link(graph, writer),
+        Format::Matrix       => adjacency_matrix::write_adjacency_matrix(graph, writer, Diagonal::SelfLoops),
+        Format::MatrixMarket => matrix_market::write_matrix_market(graph, writer),
+        Format::Metis        => metis::write_metis(graph, writer),
+        Format::Pajek        => pajek::write_pajek(graph, writer),
+    }
+}
@@ -0,0 +1,182 @@
+// Graphs from classic families, with vertices labelled by their indices.
+//
+// In a directed graph, families with a natural direction have one arc for each
+// edge: along a path or cycle, out from the centre of a star or wheel, from the
+// first part of a bipartite graph to the second and from parent to child in a
+// tree. The others, such as complete graphs and grids, have an arc each way, as
+// in NetworkX.
+//
+// Sizes that would need self-loops or parallel edges, such as a cycle of two
+// vertices, are a panic.
+
+use super::Graph;
+use super::graph_builders::unconnected;
+
+pub mod random;
+
+fn empty(n : usize, directed : bool) -> Graph<usize> {
+    unconnected((0..n).collect(), directed)
+}
+
+// An edge with no direction: an arc each way in a directed graph.
+fn add_symmetric_edge(g : &mut Graph<usize>, a : usize, b : usize) {
+    if g.is_directed() {
+        g.add_directed_edge(a, b);
+        g.add_directed_edge(b, a);
+    } else {
+        g.add_undirected_edge(a, b);
+    }
+}
+
+// An edge from a to b in a directed graph.
+fn add_oriented_edge(g : &mut Graph<usize>, a : usize, b : usize) {
+    if g.is_directed() {
+        g.add_directed_edge(a, b);
+    } else {
+        g.add_undirected_edge(a, b);
+    }
+}
+
+pub fn complete(n : usize, directed : bool) -> Graph<usize> {
+    let mut g = empty(n, directed);
+    for a in 0..n {
+        for b in (a + 1)..n {
+            add_symmetric_edge(&mut g, a, b);
+        }
+    }
+    g
+}
+
+// 0 - 1 - ... - (n - 1)
+pub fn path(n : usize, directed : bool) -> Graph<usize> {
+    let mut g = empty(n, directed);
+    for v in 1..n {
+        add_oriented_edge(&mut g, v - 1, v);
+    }
+    g
+}
+
+// A path with n - 1 joined back to 0.
+pub fn cycle(n : usize, directed : bool) -> Graph<usize> {
+    if n < 3 {
+        panic!("A cycle needs at least 3 vertices, not {}.", n)
+    }
+    let mut g = path(n, directed);
+    add_oriented_edge(&mut g, n - 1, 0);
+    g
+}
+
+// A centre, 0, joined to each of n leaves, 1 to n.
+pub fn star(n : usize, directed : bool) -> Graph<usize> {
+    let mut g = empty(n + 1, directed);
+    for leaf in 1..(n + 1) {
+        add_oriented_edge(&mut g, 0, leaf);
+    }
+    g
+}
+
+// A star of n leaves, with the leaves joined in a cycle 1 - 2 - ... - n - 1.
+pub fn wheel(n : usize, directed : bool) -> Graph<usize> {
+    if n < 3 {
+        panic!("The rim of a wheel needs at least 3 vertices, not {}.", n)
+    }
+    let mut g = star(n, directed);
+    for v in 1..(n + 1) {
+        add_oriented_edge(&mut g, v, v % n + 1);
+    }
+    g
+}
+
+// Every one of 0 to m - 1 joined to every one of m to m + n - 1.
+pub fn complete_bipartite(m : usize, n : usize, directed : bool) -> Graph<usize> {
+    let mut g = empty(m + n, directed);
+    for a in 0..m {
+        for b in m..(m + n) {
+            add_oriented_edge(&mut g, a, b);
+        }
+    }
+    g
+}
+
+// The vertex in row r and column c is r * columns + c, and is joined to the
+// vertices next to it in its row and column.
+pub fn grid_2d(rows : usize, columns : usize, directed : bool) -> Graph<usize> {
+    let mut g = empty(rows * columns, directed);
+    for r in 0..rows {
+        for c in 0..columns {
+            let v = r * columns + c;
+            if c + 1 < columns { add_symmetric_edge(&mut g, v, v + 1) }
+            if r + 1 < rows { add_symmetric_edge(&mut g, v, v + columns) }
+        }
+    }
+    g
+}
+
+// The vertex at (i, j, k) is (i * y + j) * z + k.
+pub fn grid_3d(x : usize, y : usize, z : usize, directed : bool) -> Graph<usize> {
+    let mut g = empty(x * y * z, directed);
+    for i in 0..x {
+        for j in 0..y {
+            for k in 0..z {
+                let v = (i * y + j) * z + k;
+                if k + 1 < z { add_symmetric_edge(&mut g, v, v + 1) }
+                if j + 1 < y { add_symmetric_edge(&mut g, v, v + z) }
+                if i + 1 < x { add_symmetric_edge(&mut g, v, v + y * z) }
+            }
+        }
+    }
+    g
+}
+
+// A 2D grid whose rows and columns wrap around.
+pub fn torus(rows : usize, columns : usize, directed : bool) -> Graph<usize> {
+    if rows < 3 || columns < 3 {
+        panic!("A torus needs at least 3 rows and 3 columns, not {} x {}.", rows, columns)
+    }
+    let mut g = empty(rows * columns, directed);
+    for r in 0..rows {
+        for c in 0..columns {
+            let v = r * columns + c;
+            add_symmetric_edge(&mut g, v, r * columns + (c + 1) % columns);
+            add_symmetric_edge(&mut g, v, ((r + 1) % rows) * columns + c);
+        }
+    }
+    g
+}
+
+// The 2^dimension corners of a cube, joined when they differ in one bit.
+pub fn hypercube(dimension : u32, directed : bool) -> Graph<usize> {
+    if dimension >= usize::BITS {
+        panic!("A hypercube of dimension {} has too many vertices.", dimension)
+    }
+    let n = 1 << dimension;
+    let mut g = empty(n, directed);
+    for v in 0..n {
+        for bit in 0..dimension {
+            let w = v ^ (1 << bit);
+            if w > v { add_symmetric_edge(&mut g, v, w) }
+        }
+    }
+    g
+}
+
+// A complete binary tree of n vertices, numbered as in a binary heap: the
+// children of v are 2v + 1 and 2v + 2.
+pub fn binary_tree(n : usize, directed : bool) -> Graph<usize> {
+    let mut g = empty(n, directed);
+    for v in 1..n {
+        add_oriented_edge(&mut g, (v - 1) / 2, v);
+    }
+    g
+}
+
+// An outer cycle 0 to 4, an inner pentagram 5 to 9, and a spoke from each i to i + 5.
+pub fn petersen(directed : bool) -> Graph<usize> {
+    let mut g = empty(10, directed);
+    for i in 0..5 {
+        add_symmetric_edge(&mut g, i, (i + 1) % 5);
+        add_symmetric_edge(&mut g, i, i + 5);
+        add_symmetric_edge(&mut g, i + 5, (i + 2) % 5 + 5);
+    }
+    g
+}
@@ -0,0 +1,261 @@
+// Random graphs, with vertices labelled by their indices.
+//
+// Each generator takes the random number generator to draw from, and has a
+// _with_seed version that uses ChaCha8 seeded with the given number, so that the
+// same seed gives the same graph on every platform. None of them make self-loops
+// or parallel edges, and parameters that can't be met are a panic.
+
+use super::super::Graph;
+use super::super::graph_builders::unconnected;
+use rand::{Rng, SeedableRng};
+use rand::seq::{IndexedRandom, SliceRandom};
+use rand_chacha::ChaCha8Rng;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+fn seeded(seed : u64) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(seed)
+}
+
+fn check_probability(p : f64) {
+    if !(0.0..=1.0).contains(&p) {
+        panic!("A probability must be between 0 and 1, not {}.", p)
+    }
+}
+
+fn add_edge(g : &mut Graph<usize>, a : usize, b : usize) {
+    if g.is_directed() { g.add_directed_edge(a, b); } else { g.add_undirected_edge(a, b); }
+}
+
+// Erdős-Rényi G(n, p): each of the possible edges, or arcs if directed, is
+// present with probability p.
+pub fn gnp<R>(n : usize, p : f64, directed : bool, rng : &mut R) -> Graph<usize>
+    where R : Rng + ?Sized
+{
+    check_probability(p);
+    let mut g = unconnected((0..n).collect(), directed);
+    for a in 0..n {
+        for b in (if directed { 0 } else { a + 1 })..n {
+            if a != b && rng.random_bool(p) { add_edge(&mut g, a, b) }
+        }
+    }
+    g
+}
+
+pub fn gnp_with_seed(n : usize, p : f64, directed : bool, seed : u64) -> Graph<usize> {
+    gnp(n, p, directed, &mut seeded(seed))
+}
+
+// Erdős-Rényi G(n, m): m edges, or arcs if directed, chosen uniformly from all
+// the possible ones.
+pub fn gnm<R>(n : usize, m : usize, directed : bool, rng : &mut R) -> Graph<usize>
+    where R : Rng + ?Sized
+{
+    let pairs = n * n.saturating_sub(1);
+    let possible = if directed { pairs } else { pairs / 2 };
+    if m > possible {
+        panic!("A graph of {} vertices has room for {} edges, not {}.", n, possible, m)
+    }
+    let edges = if m <= possible / 2 {
+        // Sparse: draw edges until there are enough different ones.
+        let mut chosen = HashSet::new();
+        let mut edges = Vec::with_capacity(m);
+        while edges.len() < m {
+            let (a, b) = (rng.random_range(0..n), rng.random_range(0..n));
+            let edge = if directed || a < b { (a, b) } else { (b, a) };
+            if a != b && chosen.insert(edge) { edges.push(edge) }
+        }
+        edges
+    } else {
+        // Dense: the possible edges are few enough to list.
+        let mut all : Vec<(usize, usize)> = (0..n).flat_map(|a| (0..n).map(move |b| (a, b)))
+                                                  .filter(|&(a, b)| if directed { a != b } else { a < b })
+                                                  .collect();
+        all.partial_shuffle(rng, m);
+        all.truncate(m);
+        all
+    };
+    let mut g = unconnected((0..n).collect(), directed);
+    for (a, b) in edges {
+        add_edge(&mut g, a, b);
+    }
+    g
+}
+
+pub fn gnm_with_seed(n : usize, m : usize, directed : bool, seed : u64) -> Graph<usize> {
+    gnm(n, m, directed, &mut seeded(seed))
+}
+
+// Barabási-Albert preferential attachment, undirected: vertices m to n - 1 are
+// added in turn, each joined to m different earlier vertices chosen with
+// probability proportional to their degree. Vertex m is joined to all of 0 to
+// m - 1, so there are (n - m) * m edges.
+pub fn barabasi_albert<R>(n : usize, m : usize, rng : &mut R) -> Graph<usize>
+    where R : Rng + ?Sized
+{
+    if m < 1 || m >= n {
+        panic!("Each new vertex must be joined to at least 1 and fewer than {} vertices, not {}.", n, m)
+    }
+    let mut g = unconnected((0..n).collect(), false);
+    let mut targets : Vec<usize> = (0..m).collect();
+    // Each vertex appears once for every edge it's on.
+    let mut repeated = Vec::with_capacity(2 * (n - m) * m);
+    for source in m..n {
+        for &target in &targets {
+            g.add_undirected_edge(source, target);
+        }
+        repeated.extend_from_slice(&targets);
+        repeated.extend(::std::iter::repeat_n(source, m));
+        targets.clear();
+        while targets.len() < m {
+            let target = *repeated.choose(rng).unwrap();
+            if !targets.contains(&target) { targets.push(target) }
+        }
+    }
+    g
+}
+
+pub fn barabasi_albert_with_seed(n : usize, m : usize, seed : u64) -> Graph<usize> {
+    barabasi_albert(n, m, &mut seeded(seed))
+}
+
+// Watts-Strogatz small world, undirected: a ring of n vertices each joined to
+// its k nearest neighbours, k / 2 on each side, then each edge (u, u + j) has its
+// far end moved to a vertex chosen uniformly with probability beta. The number
+// of edges stays n * k / 2.
+pub fn watts_strogatz<R>(n : usize, k : usize, beta : f64, rng : &mut R) -> Graph<usize>
+    where R : Rng + ?Sized
+{
+    if !k.is_multiple_of(2) || k >= n {
+        panic!("Each of {} vertices must have an even number of neighbours less than {}, not {}.", n, n, k)
+    }
+    check_probability(beta);
+    // Sets rather than hash sets, so that the edges come out in the same order each time.
+    let mut neighbours : Vec<BTreeSet<usize>> = vec![BTreeSet::new(); n];
+    for u in 0..n {
+        for j in 1..(k / 2 + 1) {
+            let v = (u + j) % n;
+            neighbours[u].insert(v);
+            neighbours[v].insert(u);
+        }
+    }
+    for j in 1..(k / 2 + 1) {
+        for u in 0..n {
+            let v = (u + j) % n;
+            // There's nowhere to move the edge to if u is joined to everything.
+            if !neighbours[u].contains(&v) || neighbours[u].len() >= n - 1 || !rng.random_bool(beta) { continue }
+            let mut w = rng.random_range(0..n);
+            while w == u || neighbours[u].contains(&w) {
+                w = rng.random_range(0..n);
+            }
+            neighbours[u].remove(&v);
+            neighbours[v].remove(&u);
+            neighbours[u].insert(w);
+            neighbours[w].insert(u);
+        }
+    }
+    let mut g = unconnected((0..n).collect(), false);
+    for (u, vs) in neighbours.iter().enumerate() {
+        for &v in vs.range((u + 1)..) {
+            g.add_undirected_edge(u, v);
+        }
+    }
+    g
+}
+
+pub fn watts_strogatz_with_seed(n : usize, k : usize, beta : f64, seed : u64) -> Graph<usize> {
+    watts_strogatz(n, k, beta, &mut seeded(seed))
+}
+
+// A graph chosen at random, undirected, in which every vertex has degree d. The
+// half-edges are paired at random, and those that would make a self-loop or a
+// parallel edge are paired again among themselves, starting over if they can't
+// be; this is close to uniform for small d. Pairing rarely succeeds near the
+// complete graph, so for d > (n - 1) / 2 this makes an (n - 1 - d)-regular
+// graph and takes its complement.
+pub fn random_regular<R>(n : usize, d : usize, rng : &mut R) -> Graph<usize>
+    where R : Rng + ?Sized
+{
+    if !(n * d).is_multiple_of(2) || (d >= n && d > 0) {
+        panic!("There is no {}-regular graph of {} vertices.", d, n)
+    }
+    // n - 1 - d is also valid: n * (n - 1) is even, so n * (n - 1 - d) is too.
+    let complement = d > n.saturating_sub(1) / 2;
+    let sparse_d = if complement { n - 1 - d } else { d };
+    let sparse = loop {
+        if let Some(edges) = try_random_regular(n, sparse_d, rng) { break edges }
+    };
+    let edges : Vec<(usize, usize)> = if complement {
+        (0..n).flat_map(|a| ((a + 1)..n).map(move |b| (a, b))).filter(|edge| !sparse.contains(edge)).collect()
+    } else {
+        sparse.into_iter().collect()
+    };
+    let mut g = unconnected((0..n).collect(), false);
+    for (a, b) in edges {
+        g.add_undirected_edge(a, b);
+    }
+    g
+}
+
+fn try_random_regular<R>(n : usize, d : usize, rng : &mut R) -> Option<BTreeSet<(usize, usize)>>
+    where R : Rng + ?Sized
+{
+    let mut edges = BTreeSet::new();
+    let mut stubs : Vec<usize> = (0..n).flat_map(|v| ::std::iter::repeat_n(v, d)).collect();
+    while !stubs.is_empty() {
+        stubs.shuffle(rng);
+        // The number of half-edges at each vertex that couldn't be paired.
+        let mut unpaired : BTreeMap<usize, usize> = BTreeMap::new();
+        for pair in stubs.chunks(2) {
+            let (a, b) = (pair[0].min(pair[1]), pair[0].max(pair[1]));
+            if a == b || !edges.insert((a, b)) {
+                *unpaired.entry(a).or_insert(0) += 1;
+                *unpaired.entry(b).or_insert(0) += 1;
+            }
+        }
+        // Give up if no two of what's left can be joined.
+        let left : Vec<usize> = unpaired.keys().cloned().collect();
+        let joinable = left.iter().enumerate().any(|(i, &a)| left[(i + 1)..].iter().any(|&b| !edges.contains(&(a, b))));
+        if !left.is_empty() && !joinable { return None }
+        stubs = unpaired.iter().flat_map(|(&v, &count)| ::std::iter::repeat_n(v, count)).collect();
+    }
+    Some(edges)
+}
+
+pub fn random_regular_with_seed(n : usize, d : usize, seed : u64) -> Graph<usize> {
+    random_regular(n, d, &mut seeded(seed))
+}
+
+// Stochastic block model: the vertices are split into blocks of the given sizes,
+// the first sizes[0] in block 0 and so on, and an edge, or arc if directed, from a
+// vertex in block i to one in block j is present with probability
+// probabilities[i][j]. An undirected graph needs the probabilities to be symmetric.
+pub fn stochastic_block_model<R>(sizes : &[usize], probabilities : &[Vec<f64>], directed : bool, rng : &mut R) -> Graph<usize>
+    where R : Rng + ?Sized
+{
+    let blocks = sizes.len();
+    if probabilities.len() != blocks || probabilities.iter().any(|row| row.len() != blocks) {
+        panic!("The probabilities must be a {} x {} matrix, one row and column for each block.", blocks, blocks)
+    }
+    for (i, row) in probabilities.iter().enumerate() {
+        for (j, &p) in row.iter().enumerate() {
+            check_probability(p);
+            if !directed && p != probabilities[j][i] {
+                panic!("The probabilities for an undirected graph must be symmetric, but {} and {} differ for blocks {} and {}.",
+                       p, probabilities[j][i], i, j)
+            }
+        }
+    }
+    let block_of : Vec<usize> = sizes.iter().enumerate().flat_map(|(block, &size)| ::std::iter::repeat_n(block, size)).collect();
+    let n = block_of.len();
+    let mut g = unconnected((0..n).collect(), directed);
+    for a in 0..n {
+        for b in (if directed { 0 } else { a + 1 })..n {
+            if a != b && rng.random_bool(probabilities[block_of[a]][block_of[b]]) { add_edge(&mut g, a, b) }
+        }
+    }
+    g
+}
+
+pub fn stochastic_block_model_with_seed(sizes : &[usize], probabilities : &[Vec<f64>], directed : bool, seed : u64) -> Graph<usize> {
+    stochastic_block_model(sizes, probabilities, directed, &mut seeded(seed))
+}
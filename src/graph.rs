@@ -1,17 +1,116 @@
+extern crate memmap2;
+extern crate rand;
+extern crate rand_chacha;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+
 use std::collections::HashMap;
-use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
 use std::hash::Hash;
 
+pub mod attributes;
+pub mod generators;
 pub mod graph_builders;
+pub mod matrix_graph;
+#[cfg(feature = "serde")]
+mod serialization;
+mod traversal;
+
+use attributes::AttributeStore;
+use traversal::Traversable;
 
 pub struct Graph<T> where T : Clone + Eq + Hash {
     directed       : bool,
     nodes          : Vec<T>,
     node_indices   : HashMap<T, usize>,
+    config         : GraphConfig,
     adjacency_list : Vec<Vec<usize>>,
+    // The EdgeIndex of each entry in adjacency_list.
+    adjacency_edges : Vec<Vec<EdgeIndex>>,
+    // The (source, dest) pair of each edge, indexed by EdgeIndex.
+    edge_endpoints : Vec<(usize, usize)>,
+    // Optional per-vertex maps from destination to the first edge to it, mirroring
+    // adjacency_list. When present, edge existence tests are O(1) rather than O(degree).
+    edge_index     : Option<Vec<HashMap<usize, EdgeIndex>>>,
+    node_attributes : AttributeStore<usize>,
+    edge_attributes : AttributeStore<EdgeIndex>,
+    metadata       : Metadata,
+}
+
+// Identifies a single edge, so that parallel edges can be told apart.
+// Edges are numbered from zero in the order that they are added.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub struct EdgeIndex(usize);
+
+impl EdgeIndex {
+    pub fn new(index : usize) -> EdgeIndex {
+        EdgeIndex(index)
+    }
+
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+// What to do when an edge is added between two vertices that are already joined.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ParallelEdgePolicy {
+    Reject,
+    Merge,   // Keep the existing edge and discard the new one
+    Keep,    // Allow a multigraph
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SelfLoopPolicy {
+    Reject,
+    Allow,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GraphConfig {
+    pub parallel_edges : ParallelEdgePolicy,
+    pub self_loops     : SelfLoopPolicy,
+}
+
+// Free-form information about the graph as a whole, kept in the header of the
+// text format.
+#[derive(PartialEq, Eq, Clone, Default, Debug)]
+pub struct Metadata {
+    pub name        : Option<String>,
+    pub description : Option<String>,
 }
 
+// The default merges parallel edges and allows self-loops.
+impl Default for GraphConfig {
+    fn default() -> GraphConfig {
+        GraphConfig { parallel_edges : ParallelEdgePolicy::Merge, self_loops : SelfLoopPolicy::Allow }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum EdgeError {
+    ParallelEdge (usize, usize),
+    SelfLoop     (usize),
+}
+
+impl fmt::Display for EdgeError {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EdgeError::ParallelEdge(source, dest) => write!(f, "Edge {} -> {} is already present and parallel edges are rejected.", source, dest),
+            EdgeError::SelfLoop(vertex) => write!(f, "Self-loop on vertex {} is rejected.", vertex),
+        }
+    }
+}
+
+impl Error for EdgeError {}
+
 #[derive(PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum BFSTraversalState {
     Undiscovered,
     Discovered,
@@ -19,6 +118,7 @@ pub enum BFSTraversalState {
 }
 
 #[derive(PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DFSTraversalState {
     Undiscovered,
     Processing (usize),
@@ -26,6 +126,7 @@ pub enum DFSTraversalState {
 }
 
 #[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DFSEdgeType {
     Tree,
     Back,
@@ -37,7 +138,9 @@ pub enum DFSEdgeType {
 impl<T> Graph<T> where T : Clone + Eq + Hash {
 
     pub fn new() -> Graph<T> {
-        Graph { nodes : Vec::new(), directed : false, node_indices : HashMap::new(), adjacency_list : Vec::new() }
+        Graph { nodes : Vec::new(), directed : false, node_indices : HashMap::new(), config : GraphConfig::default(),
+                adjacency_list : Vec::new(), adjacency_edges : Vec::new(), edge_endpoints : Vec::new(), edge_index : None,
+                node_attributes : AttributeStore::new(), edge_attributes : AttributeStore::new(), metadata : Metadata::default() }
     }
 
     pub fn number_of_vertices(&self) -> usize {
@@ -56,59 +159,249 @@ impl<T> Graph<T> where T : Clone + Eq + Hash {
         }
     }
 
+    pub fn contains_node(&self, node : &T) -> bool {
+        self.node_indices.contains_key(node)
+    }
+
     pub fn index_from_node(&self, node : T) -> usize {
         match self.node_indices.get(&node) {
-            Some(i) => i.clone(),
+            Some(i) => *i,
             None => panic!("Node was not present in the graph."),
         }
     }
 
+    // For an undirected graph a self-loop contributes two to the degree of its vertex.
     pub fn get_degree_from_index(&self, index : usize) -> usize {
-        self.adjacency_list[index].len()
+        let degree = self.adjacency_list[index].len();
+        if self.directed {
+            degree
+        } else {
+            degree + self.adjacency_list[index].iter().filter(|&&dest| dest == index).count()
+        }
     }
 
-    pub fn add_directed_edge(&mut self, source_index : usize, dest_index : usize) {
+    pub fn config(&self) -> GraphConfig {
+        self.config
+    }
+
+    // The number of distinct edges. An undirected edge counts once, even though it
+    // appears in the adjacency lists of both of its endpoints.
+    pub fn number_of_edges(&self) -> usize {
+        self.edge_endpoints.len()
+    }
+
+    // The (source, dest) pair that the edge was added with.
+    pub fn edge_endpoints(&self, edge : EdgeIndex) -> (usize, usize) {
+        if edge.index() >= self.number_of_edges() {
+            panic!("Called edge_endpoints({}) on a graph with only {} edges!", edge.index(), self.number_of_edges())
+        }
+        self.edge_endpoints[edge.index()]
+    }
+
+    // Attributes of the nodes, keyed by node index.
+    pub fn node_attributes(&self) -> &AttributeStore<usize> {
+        &self.node_attributes
+    }
+
+    pub fn node_attributes_mut(&mut self) -> &mut AttributeStore<usize> {
+        &mut self.node_attributes
+    }
+
+    pub fn edge_attributes(&self) -> &AttributeStore<EdgeIndex> {
+        &self.edge_attributes
+    }
+
+    pub fn edge_attributes_mut(&mut self) -> &mut AttributeStore<EdgeIndex> {
+        &mut self.edge_attributes
+    }
+
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    pub fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+
+    pub fn has_edge(&self, source_index : usize, dest_index : usize) -> bool {
+        self.find_edge(source_index, dest_index).is_some()
+    }
+
+    // The first edge from source to dest, if there is one.
+    pub fn find_edge(&self, source_index : usize, dest_index : usize) -> Option<EdgeIndex> {
+        self.check_indices(source_index, dest_index);
+        match self.edge_index {
+            Some(ref index) => index[source_index].get(&dest_index).cloned(),
+            None => self.adjacency_list[source_index].iter()
+                                                     .position(|&d| d == dest_index)
+                                                     .map(|i| self.adjacency_edges[source_index][i]),
+        }
+    }
+
+    // All of the (parallel) edges from source to dest, in the order they were added.
+    pub fn edges_between(&self, source_index : usize, dest_index : usize) -> Vec<EdgeIndex> {
+        self.check_indices(source_index, dest_index);
+        self.adjacency_list[source_index].iter()
+                                         .zip(self.adjacency_edges[source_index].iter())
+                                         .filter(|&(&d, _)| d == dest_index)
+                                         .map(|(_, &e)| e)
+                                         .collect()
+    }
+
+    pub fn has_edge_index(&self) -> bool {
+        self.edge_index.is_some()
+    }
+
+    // Builds the hashed edge index, after which has_edge and the duplicate check
+    // when adding edges no longer need to scan the adjacency list.
+    // Costs one hash map entry per edge.
+    pub fn build_edge_index(&mut self) {
+        let mut index = vec![HashMap::new(); self.number_of_vertices()];
+        for (source, dests) in self.adjacency_list.iter().enumerate() {
+            for (dest, edge) in dests.iter().zip(self.adjacency_edges[source].iter()) {
+                index[source].entry(*dest).or_insert(*edge);
+            }
+        }
+        self.edge_index = Some(index);
+    }
+
+    pub fn drop_edge_index(&mut self) {
+        self.edge_index = None;
+    }
+
+    // Adds an edge, subject to the graph's GraphConfig, and returns its index.
+    // Panics if the configuration rejects the edge. If an existing edge is merged
+    // with this one then the index of the existing edge is returned.
+    pub fn add_directed_edge(&mut self, source_index : usize, dest_index : usize) -> EdgeIndex {
+        match self.try_add_directed_edge(source_index, dest_index) {
+            Ok(edge) => edge,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    pub fn add_undirected_edge(&mut self, source_index : usize, dest_index : usize) -> EdgeIndex {
+        match self.try_add_undirected_edge(source_index, dest_index) {
+            Ok(edge) => edge,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    pub fn try_add_directed_edge(&mut self, source_index : usize, dest_index : usize) -> Result<EdgeIndex, EdgeError> {
+        self.check_self_loop(source_index, dest_index)?;
+        if let Some(existing) = self.find_edge(source_index, dest_index) {
+            match self.config.parallel_edges {
+                ParallelEdgePolicy::Reject => return Err(EdgeError::ParallelEdge(source_index, dest_index)),
+                ParallelEdgePolicy::Merge  => return Ok(existing),
+                ParallelEdgePolicy::Keep   => (),
+            }
+        }
+        let edge = self.new_edge(source_index, dest_index);
+        self.push_arc(source_index, dest_index, edge);
+        Ok(edge)
+    }
+
+    // Adds an arc in each direction, both with the same EdgeIndex.
+    // A self-loop is only entered into the adjacency list once.
+    pub fn try_add_undirected_edge(&mut self, source_index : usize, dest_index : usize) -> Result<EdgeIndex, EdgeError> {
+        self.check_self_loop(source_index, dest_index)?;
+        let forward  = self.find_edge(source_index, dest_index);
+        let backward = self.find_edge(dest_index, source_index);
+        let edge = match self.config.parallel_edges {
+            ParallelEdgePolicy::Reject if forward.is_some() || backward.is_some() =>
+                return Err(EdgeError::ParallelEdge(source_index, dest_index)),
+            ParallelEdgePolicy::Merge => {
+                // Complete whichever direction is missing, re-using the existing edge's index.
+                let edge = match forward.or(backward) {
+                    Some(edge) => edge,
+                    None => self.new_edge(source_index, dest_index),
+                };
+                if forward.is_none() { self.push_arc(source_index, dest_index, edge) }
+                if backward.is_none() && source_index != dest_index { self.push_arc(dest_index, source_index, edge) }
+                return Ok(edge)
+            },
+            _ => self.new_edge(source_index, dest_index),
+        };
+        self.push_arc(source_index, dest_index, edge);
+        if source_index != dest_index {
+            self.push_arc(dest_index, source_index, edge);
+        }
+        Ok(edge)
+    }
+
+    // Adds many edges at once. Each pair is added as by add_directed_edge for a
+    // directed graph, or add_undirected_edge for an undirected one, and so is
+    // subject to the graph's GraphConfig.
+    // The new edges are sorted (and, when merging parallel edges, deduplicated) in
+    // a single pass and checked against the hashed edge index, which is built for
+    // the duration of the call if it isn't already present. New edges are added
+    // in increasing order of (source, dest).
+    pub fn extend_edges<I>(&mut self, edges : I) where I : IntoIterator<Item = (usize, usize)> {
+        let mut new_edges = Vec::new();
+        for (source_index, dest_index) in edges {
+            self.check_indices(source_index, dest_index);
+            if self.directed || source_index <= dest_index {
+                new_edges.push((source_index, dest_index));
+            } else {
+                new_edges.push((dest_index, source_index));
+            }
+        }
+        new_edges.sort_unstable();
+        if self.config.parallel_edges == ParallelEdgePolicy::Merge {
+            new_edges.dedup();
+        }
+
+        let had_edge_index = self.has_edge_index();
+        if !had_edge_index {
+            self.build_edge_index();
+        }
+        for (source_index, dest_index) in new_edges {
+            if self.directed {
+                self.add_directed_edge(source_index, dest_index);
+            } else {
+                self.add_undirected_edge(source_index, dest_index);
+            }
+        }
+        if !had_edge_index {
+            self.drop_edge_index();
+        }
+    }
+
+    fn new_edge(&mut self, source_index : usize, dest_index : usize) -> EdgeIndex {
+        self.edge_endpoints.push((source_index, dest_index));
+        EdgeIndex::new(self.edge_endpoints.len() - 1)
+    }
+
+    fn push_arc(&mut self, source_index : usize, dest_index : usize, edge : EdgeIndex) {
+        self.adjacency_list[source_index].push(dest_index);
+        self.adjacency_edges[source_index].push(edge);
+        if let Some(ref mut index) = self.edge_index {
+            index[source_index].entry(dest_index).or_insert(edge);
+        }
+    }
+
+    fn check_self_loop(&self, source_index : usize, dest_index : usize) -> Result<(), EdgeError> {
+        if source_index == dest_index && self.config.self_loops == SelfLoopPolicy::Reject {
+            Err(EdgeError::SelfLoop(source_index))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_indices(&self, source_index : usize, dest_index : usize) {
         if dest_index >= self.number_of_vertices() {
             panic!("dest_index {} was >= {}, the number of vertices in the graph.", dest_index, self.number_of_vertices())
         }
         if source_index >= self.number_of_vertices() {
             panic!("source_index {} was >= {}, the number of vertices in the graph.", source_index, self.number_of_vertices())
         }
-        if !self.adjacency_list[source_index].contains(&dest_index) {
-            self.adjacency_list[source_index].push(dest_index);
-        }
-    }
-
-    pub fn add_undirected_edge(&mut self, source_index : usize, dest_index : usize) {
-        self.add_directed_edge(source_index, dest_index);
-        self.add_directed_edge(dest_index, source_index);
     }
 
     pub fn breadth_first_iter_from_index<F, G> (&self,
                                                 mut process_vertex : F,
                                                 mut process_edge : G,
                                                 root_index : usize)
-        where F : FnMut(&T) -> (), G : FnMut(&T, &T) -> () {
-
-        let mut node_states = vec![BFSTraversalState::Undiscovered; self.number_of_vertices()];
-        node_states[root_index] = BFSTraversalState::Discovered;
-
-        let mut nodes_to_process = VecDeque::<usize>::new();
-        nodes_to_process.push_back(root_index);
-
-        while let Some(current_node) = nodes_to_process.pop_front() {
-            for dest_node in &self.adjacency_list[current_node] {
-                // Note - this does both edges in both directions for an undirected graph.
-                process_edge(&self.nodes[current_node], &self.nodes[*dest_node]);
-                if node_states[*dest_node] == BFSTraversalState::Undiscovered {
-                    node_states[*dest_node] = BFSTraversalState::Discovered;
-                    nodes_to_process.push_back(*dest_node);
-                }
-            }
-
-            process_vertex(&self.nodes[current_node]);
-            node_states[current_node] = BFSTraversalState::Processed;
-        }
+        where F : FnMut(&T), G : FnMut(&T, &T) {
+        traversal::breadth_first(self, &mut process_vertex, &mut process_edge, root_index)
     }
 
     // FIXME - I don't understand why process_edge and process_vertex don't need
@@ -117,102 +410,77 @@ impl<T> Graph<T> where T : Clone + Eq + Hash {
                                                process_vertex : F,
                                                process_edge : G,
                                                root_node : T)
-        where F : FnMut(&T) -> (), G : FnMut(&T, &T) -> () {
+        where F : FnMut(&T), G : FnMut(&T, &T) {
         let root_index = self.index_from_node(root_node);
         self.breadth_first_iter_from_index(process_vertex, process_edge, root_index)
     }
 
+    // The indices of the vertices on a path with the fewest edges from source to
+    // dest, including both, found by a breadth-first search. Edges are followed
+    // in their direction in a directed graph.
+    pub fn shortest_path_from_index(&self, source_index : usize, dest_index : usize) -> Option<Vec<usize>> {
+        self.check_indices(source_index, dest_index);
+        traversal::shortest_path(self, source_index, dest_index)
+    }
+
+    pub fn shortest_path_from_node(&self, source_node : T, dest_node : T) -> Option<Vec<T>> {
+        let source_index = self.index_from_node(source_node);
+        let dest_index = self.index_from_node(dest_node);
+        self.shortest_path_from_index(source_index, dest_index)
+            .map(|path| path.into_iter().map(|index| self.nodes[index].clone()).collect())
+    }
+
     pub fn depth_first_iter_from_index<F, G, H> (&self,
                                                  mut process_vertex_early : F,
                                                  mut process_vertex_late  : G,
                                                  mut process_edge         : H,
                                                  root_node : usize)
-        where F : FnMut(&T) -> (), G : FnMut(&T) -> (), H : FnMut(&T, &T, DFSEdgeType, &Vec<Option<usize>>) -> () {
-        
-        let mut discovery_state = vec![DFSTraversalState::Undiscovered; self.number_of_vertices()];
-        let mut parent = vec![None; self.number_of_vertices()];
-        // Call into a recursive function
-        self.inner_dfs(&mut process_vertex_early,
-                       &mut process_vertex_late,
-                       &mut process_edge,
-                       &mut discovery_state,
-                       &mut parent,
-                       0usize,
-                       root_node);
+        where F : FnMut(&T), G : FnMut(&T), H : FnMut(&T, &T, DFSEdgeType, &Vec<Option<usize>>) {
+        traversal::depth_first_from_index(self,
+                                          &mut process_vertex_early,
+                                          &mut process_vertex_late,
+                                          &mut process_edge,
+                                          root_node)
     }
 
     pub fn depth_first_iter<F, G, H> (&self,
                                       mut process_vertex_early : F,
                                       mut process_vertex_late  : G,
                                       mut process_edge         : H)
-        where F : FnMut(&T) -> (), G : FnMut(&T) -> (), H : FnMut(&T, &T, DFSEdgeType, &Vec<Option<usize>>) -> () {
-        
-        let mut discovery_state = vec![DFSTraversalState::Undiscovered; self.number_of_vertices()];
-        let mut parent = vec![None; self.number_of_vertices()];
-        for root_node in 0..self.number_of_vertices() {
-            if discovery_state[root_node] == DFSTraversalState::Undiscovered {
-                // Call into a recursive function
-                self.inner_dfs(&mut process_vertex_early,
+        where F : FnMut(&T), G : FnMut(&T), H : FnMut(&T, &T, DFSEdgeType, &Vec<Option<usize>>) {
+        traversal::depth_first(self,
+                               &mut process_vertex_early,
                                &mut process_vertex_late,
-                               &mut process_edge,
-                               &mut discovery_state,
-                               &mut parent,
-                               0usize,
-                               root_node);
-            }
-        };
+                               &mut process_edge)
     }
 
-    // Recursive part of DFS
-    fn inner_dfs<F, G, H>(&self,
-                          process_vertex_early     : &mut F,
-                          process_vertex_late      : &mut G,
-                          process_edge             : &mut H,
-                          discovery_state          : &mut Vec<DFSTraversalState>,
-                          parent                   : &mut Vec<Option<usize>>,
-                          time                     : usize,
-                          current_node             : usize) -> usize    // Returns the exit time + 1
-        where F : FnMut(&T) -> (), G : FnMut(&T) -> (),
-              H : FnMut(&T, &T, DFSEdgeType, &Vec<Option<usize>>) -> () {
-            process_vertex_early(&self.nodes[current_node]);
-            let entry_time : usize = time;
-            let mut running_time = time + 1;
-            discovery_state[current_node] = DFSTraversalState::Processing(entry_time);
-            running_time += 1;
-            for dest_node in &self.adjacency_list[current_node] {
-                match discovery_state[*dest_node] {
-                    DFSTraversalState::Undiscovered => {
-                        parent[*dest_node] = Some(current_node);
-                        process_edge(&self.nodes[current_node], &self.nodes[*dest_node], DFSEdgeType::Tree, &parent);
-                        running_time = self.inner_dfs(process_vertex_early,
-                                                      process_vertex_late,
-                                                      process_edge,
-                                                      discovery_state,
-                                                      parent,
-                                                      running_time,
-                                                      *dest_node);
-                    },
-                    DFSTraversalState::Processing(_) => {
-                        if parent[current_node] != Some(*dest_node) || self.is_directed() {
-                            process_edge(&self.nodes[current_node], &self.nodes[*dest_node], DFSEdgeType::Back, &parent);
-                        }
-                    },
-                    DFSTraversalState::Processed(dest_entry_time, _) => {
-                        if self.is_directed() {
-                            if dest_entry_time > entry_time {
-                                process_edge(&self.nodes[current_node], &self.nodes[*dest_node], DFSEdgeType::Forward, &parent);
-                            } else {
-                                process_edge(&self.nodes[current_node], &self.nodes[*dest_node], DFSEdgeType::Cross, &parent);
-                            }
-                        }
-                    },
-                }
-            }
-            discovery_state[current_node] = DFSTraversalState::Processed(entry_time, running_time);
-            running_time += 1;
-            process_vertex_late(&self.nodes[current_node]);
-            // Return the next time available for use
-            running_time
-        }
+    // The type of every edge, as found by depth_first_iter. An undirected edge has
+    // a single type, from the direction in which it was first followed.
+    pub fn depth_first_edge_types(&self) -> HashMap<EdgeIndex, DFSEdgeType> {
+        traversal::depth_first_edge_types(self).into_iter()
+                                                .map(|(edge, edge_type)| (EdgeIndex(edge), edge_type))
+                                                .collect()
+    }
+}
+
+impl<T> Default for Graph<T> where T : Clone + Eq + Hash {
+    fn default() -> Graph<T> {
+        Graph::new()
+    }
 }
 
+impl<T> Traversable<T> for Graph<T> where T : Clone + Eq + Hash {
+    fn nodes(&self) -> &[T] {
+        &self.nodes
+    }
+
+    fn directed(&self) -> bool {
+        self.directed
+    }
+
+    fn neighbours<'a>(&'a self, index : usize) -> Box<dyn Iterator<Item = (usize, usize)> + 'a> {
+        Box::new(self.adjacency_list[index].iter()
+                                           .zip(self.adjacency_edges[index].iter())
+                                           .map(|(&dest, &edge)| (dest, edge.index())))
+    }
+}
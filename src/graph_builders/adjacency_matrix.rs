@@ -0,0 +1,185 @@
+// Adjacency matrices as plain text, one whitespace-separated row per line.
+//
+//     # A comment
+//     0 1 1
+//     1 0 0
+//     1 0 0
+//
+// The entry in row i and column j is the edge from vertex i to vertex j, and 0
+// means no edge. A symmetric matrix gives an undirected graph and any other a
+// directed graph. If every entry is 0 or 1 the graph is unweighted; otherwise
+// the entries are kept as the edge attribute "weight", as an int if they're all
+// whole numbers and as a float if not. Blank lines and lines starting with '#',
+// after any spaces, are skipped.
+
+use super::super::{Graph, GraphConfig, ParallelEdgePolicy, SelfLoopPolicy};
+use super::super::attributes::{AttributeType, AttributeValue};
+use super::{unconnected_with_config, write_atomically, Diagnostic, ParseError, ParseMode, Section, Severity};
+use std::fs::File;
+use std::hash::Hash;
+use std::io;
+use std::io::BufReader;
+use std::io::prelude::*;
+
+const SECTION : Section = Section::Other("adjacency matrix");
+
+// What the entries on the diagonal mean.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Diagonal {
+    Zero,       // The diagonal holds no edges: it's ignored when read, and self-loops are written as 0
+    SelfLoops,  // An entry on the diagonal is a self-loop, just as any other entry is an edge
+}
+
+pub fn from_adjacency_matrix_file(filename : &str) -> Result<Graph<usize>, ParseError> {
+    from_adjacency_matrix_reader(BufReader::new(File::open(filename)?))
+}
+
+pub fn from_adjacency_matrix_str(text : &str) -> Result<Graph<usize>, ParseError> {
+    from_adjacency_matrix_reader(text.as_bytes())
+}
+
+pub fn from_adjacency_matrix_reader<R : BufRead>(reader : R) -> Result<Graph<usize>, ParseError> {
+    from_adjacency_matrix_reader_with_options(reader, ParseMode::Strict, Diagonal::SelfLoops)
+}
+
+fn diagnostic(line : usize, column : usize, expected : &str, found : String) -> Diagnostic {
+    Diagnostic { line, column, section : SECTION, expected : String::from(expected), found, severity : Severity::Error }
+}
+
+fn is_zero(value : &AttributeValue) -> bool {
+    value.as_float() == Some(0.0)
+}
+
+pub fn from_adjacency_matrix_reader_with_options<R : BufRead>(reader : R, mode : ParseMode, diagonal : Diagonal) -> Result<Graph<usize>, ParseError> {
+    let mut diagnostics = Vec::new();
+    let mut rows : Vec<Vec<AttributeValue>> = Vec::new();
+    let mut last_line = 0;
+
+    for (line_index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = line_index + 1;
+        last_line = line_number;
+        if mode == ParseMode::Strict && !diagnostics.is_empty() { break }
+        if line.trim_start().starts_with('#') || line.trim().is_empty() { continue }
+
+        let fields : Vec<&str> = line.split_whitespace().collect();
+        let column_of = |index : usize| {
+            match fields.get(index) {
+                Some(field) => line[..(field.as_ptr() as usize - line.as_ptr() as usize)].chars().count() + 1,
+                None => line.chars().count() + 1,
+            }
+        };
+        let found_at = |index : usize| fields.get(index).map(|f| format!("\"{}\"", f)).unwrap_or_else(|| String::from("end of line"));
+
+        // The first row gives the size of the matrix.
+        let n = rows.first().map(|row| row.len()).unwrap_or(fields.len());
+        if fields.len() != n {
+            diagnostics.push(diagnostic(line_number, column_of(n), &format!("{} entries, as in the first row", n), found_at(n)));
+            continue
+        }
+        let mut row = Vec::with_capacity(n);
+        for (i, field) in fields.iter().enumerate() {
+            let value = AttributeValue::parse(field, AttributeType::Int)
+                            .or_else(|| AttributeValue::parse(field, AttributeType::Float).filter(|x| x.as_float().unwrap().is_finite()));
+            match value {
+                Some(value) => row.push(value),
+                None => {
+                    diagnostics.push(diagnostic(line_number, column_of(i), "a number", found_at(i)));
+                    break
+                },
+            }
+        }
+        rows.push(row);
+    }
+
+    let n = rows.first().map(|row| row.len()).unwrap_or(0);
+    if rows.len() != n && diagnostics.is_empty() {
+        diagnostics.push(diagnostic(last_line + 1, 1, &format!("{} rows, for a square matrix", n), format!("{}", rows.len())));
+    }
+    if !diagnostics.is_empty() {
+        if mode == ParseMode::Strict {
+            diagnostics.truncate(1);
+        }
+        return Err(ParseError::Invalid(diagnostics))
+    }
+
+    let counts = |i : usize, j : usize| (i != j || diagonal == Diagonal::SelfLoops) && !is_zero(&rows[i][j]);
+    let directed = (0..n).any(|i| (0..i).any(|j| rows[i][j].as_float() != rows[j][i].as_float()));
+    let entries : Vec<(usize, usize)> = (0..n).flat_map(|i| (0..n).map(move |j| (i, j)))
+                                              .filter(|&(i, j)| (directed || j >= i) && counts(i, j))
+                                              .collect();
+    let weighted = entries.iter().any(|&(i, j)| rows[i][j].as_float() != Some(1.0));
+    let all_ints = entries.iter().all(|&(i, j)| rows[i][j].as_int().is_some());
+
+    let config = GraphConfig { parallel_edges : ParallelEdgePolicy::Keep, self_loops : SelfLoopPolicy::Allow };
+    let mut g = unconnected_with_config((0..n).collect(), directed, config);
+    if weighted {
+        g.edge_attributes.declare("weight", if all_ints { AttributeType::Int } else { AttributeType::Float }, None).unwrap();
+    }
+    for (i, j) in entries {
+        let edge = if directed { g.add_directed_edge(i, j) } else { g.add_undirected_edge(i, j) };
+        if weighted {
+            let weight = if all_ints { rows[i][j].clone() } else { AttributeValue::Float(rows[i][j].as_float().unwrap()) };
+            g.edge_attributes.set(edge, "weight", weight).unwrap();
+        }
+    }
+    Ok(g)
+}
+
+// Panics if the graph can't be written as a matrix; see write_adjacency_matrix.
+pub fn make_adjacency_matrix_string<T>(graph : &Graph<T>, diagonal : Diagonal) -> String
+    where T : Clone + Eq + Hash
+{
+    let mut matrix = Vec::new();
+    write_adjacency_matrix(graph, &mut matrix, diagonal).unwrap();
+    String::from_utf8(matrix).unwrap()
+}
+
+pub fn to_adjacency_matrix_file<T>(graph : &Graph<T>, filename : &str, diagonal : Diagonal) -> io::Result<()>
+    where T : Clone + Eq + Hash
+{
+    write_atomically(filename, |writer| write_adjacency_matrix(graph, writer, diagonal))
+}
+
+// The entries are the int or float "weight" attribute if the graph has one, with
+// 1 for an edge without a weight, and 0 or 1 otherwise. Float weights are
+// written with a decimal point or exponent, so they're read back as floats. A
+// matrix can't hold parallel edges, a weight of 0 or an infinite or NaN weight,
+// so a graph with any of them is an InvalidInput error. A directed graph whose
+// matrix is symmetric reads back as undirected.
+pub fn write_adjacency_matrix<T, W>(graph : &Graph<T>, mut writer : W, diagonal : Diagonal) -> io::Result<()>
+    where T : Clone + Eq + Hash, W : Write
+{
+    let n = graph.number_of_vertices();
+    let weighted = matches!(graph.edge_attributes.attribute_type("weight"), Some(AttributeType::Int) | Some(AttributeType::Float));
+    let invalid = |message : String| io::Error::new(io::ErrorKind::InvalidInput, message);
+
+    for source in 0..n {
+        let mut row = vec![String::from("0"); n];
+        let mut seen = vec![false; n];
+        for (&dest, &edge) in graph.adjacency_list[source].iter().zip(graph.adjacency_edges[source].iter()) {
+            if seen[dest] {
+                return Err(invalid(format!("an adjacency matrix can't hold the parallel edges from {} to {}", source, dest)))
+            }
+            seen[dest] = true;
+            if dest == source && diagonal == Diagonal::Zero { continue }
+            row[dest] = match graph.edge_attributes.get(edge, "weight") {
+                Some(weight) if weighted => {
+                    match *weight {
+                        _ if is_zero(weight) => {
+                            return Err(invalid(format!("an adjacency matrix can't hold the edge from {} to {}, with a weight of 0", source, dest)))
+                        },
+                        AttributeValue::Float(x) if !x.is_finite() => {
+                            return Err(invalid(format!("an adjacency matrix can't hold the edge from {} to {}, with a weight of {}", source, dest, x)))
+                        },
+                        AttributeValue::Float(x) => format!("{:?}", x),
+                        _ => weight.to_string(),
+                    }
+                },
+                _ => String::from("1"),
+            };
+        }
+        writeln!(writer, "{}", row.join(" "))?;
+    }
+    Ok(())
+}
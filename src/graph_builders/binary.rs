@@ -0,0 +1,273 @@
+// A compact binary format, laid out so that a file can be memory-mapped and
+// used in place. All integers are little-endian u64s unless noted.
+//
+//     magic            8 bytes, "GRAPHCSR"
+//     version          u32, currently 1
+//     flags            u32, bit 0 set for a directed graph
+//     n                number of vertices
+//     m                number of arcs
+//     label bytes      length of the label text
+//     offsets          n + 1 entries; the arcs of vertex v are targets[offsets[v]..offsets[v + 1]]
+//     targets          m entries
+//     label offsets    n + 1 entries into the label text
+//     label text       UTF-8, the Display form of each node label in turn
+//
+// As in adjacency_list, an undirected edge is stored as an arc in each
+// direction, except for a self-loop, which is stored once. Attributes, the
+// config and which arcs of a directed graph share an EdgeIndex aren't stored.
+
+use super::super::{Graph, GraphConfig, ParallelEdgePolicy, SelfLoopPolicy};
+use super::{unconnected_with_config, write_atomically};
+use memmap2::Mmap;
+use std::fmt::Display;
+use std::fs::File;
+use std::hash::Hash;
+use std::io;
+use std::io::prelude::*;
+use std::str;
+use std::str::FromStr;
+
+pub const MAGIC : &[u8; 8] = b"GRAPHCSR";
+pub const VERSION : u32 = 1;
+
+const HEADER_SIZE : usize = 40;
+
+fn invalid(message : String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+pub fn make_binary_vec<T>(graph : &Graph<T>) -> Vec<u8>
+    where T : Clone + Eq + Hash + Display
+{
+    let mut binary = Vec::new();
+    write_binary(graph, &mut binary).unwrap();  // Writing to a Vec can't fail
+    binary
+}
+
+pub fn to_binary_file<T>(graph : &Graph<T>, filename : &str) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display
+{
+    write_atomically(filename, |writer| write_binary(graph, writer))
+}
+
+pub fn write_binary<T, W>(graph : &Graph<T>, mut writer : W) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display, W : Write
+{
+    let labels : Vec<String> = graph.nodes.iter().map(|label| label.to_string()).collect();
+    let label_bytes : usize = labels.iter().map(|label| label.len()).sum();
+    let m : usize = graph.adjacency_list.iter().map(|dests| dests.len()).sum();
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    writer.write_all(&(if graph.is_directed() { 1u32 } else { 0u32 }).to_le_bytes())?;
+    for &count in &[graph.number_of_vertices(), m, label_bytes] {
+        writer.write_all(&(count as u64).to_le_bytes())?;
+    }
+
+    let mut offset = 0;
+    writer.write_all(&0u64.to_le_bytes())?;
+    for dests in &graph.adjacency_list {
+        offset += dests.len();
+        writer.write_all(&(offset as u64).to_le_bytes())?;
+    }
+    for dests in &graph.adjacency_list {
+        for &dest in dests {
+            writer.write_all(&(dest as u64).to_le_bytes())?;
+        }
+    }
+
+    let mut offset = 0;
+    writer.write_all(&0u64.to_le_bytes())?;
+    for label in &labels {
+        offset += label.len();
+        writer.write_all(&(offset as u64).to_le_bytes())?;
+    }
+    for label in &labels {
+        writer.write_all(label.as_bytes())?;
+    }
+    Ok(())
+}
+
+// A file in the binary format, mapped into memory.
+pub struct MappedGraph {
+    map : Mmap,
+}
+
+// Maps and checks the file. The file mustn't be changed while it's mapped.
+pub fn map_binary_file(filename : &str) -> io::Result<MappedGraph> {
+    let file = File::open(filename)?;
+    let map = unsafe { Mmap::map(&file)? };
+    GraphView::from_bytes(&map)?;
+    Ok(MappedGraph { map })
+}
+
+impl MappedGraph {
+    pub fn view(&self) -> GraphView<'_> {
+        GraphView::from_checked_bytes(&self.map)
+    }
+}
+
+// Reads the whole file into a graph, via a mapping.
+pub fn from_binary_file<T>(filename : &str) -> io::Result<Graph<T>>
+    where T : Clone + Eq + Hash + FromStr
+{
+    map_binary_file(filename)?.view().to_graph()
+}
+
+// A read-only graph backed by bytes in the binary format, which are used in
+// place rather than copied.
+#[derive(Clone, Copy)]
+pub struct GraphView<'a> {
+    directed      : bool,
+    n             : usize,
+    m             : usize,
+    offsets       : &'a [u8],
+    targets       : &'a [u8],
+    label_offsets : &'a [u8],
+    labels        : &'a [u8],
+}
+
+// The i'th u64 of a section.
+fn entry(section : &[u8], i : usize) -> usize {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&section[(8 * i)..(8 * i + 8)]);
+    u64::from_le_bytes(bytes) as usize
+}
+
+impl<'a> GraphView<'a> {
+
+    // Checks the header and the contents: the offsets must be in order and in
+    // range, every target must be a vertex and every label valid UTF-8.
+    pub fn from_bytes(bytes : &'a [u8]) -> io::Result<GraphView<'a>> {
+        if bytes.len() < HEADER_SIZE || &bytes[0..8] != MAGIC {
+            return Err(invalid(String::from("not a binary graph file: the magic number is missing")))
+        }
+        let mut word = [0; 4];
+        word.copy_from_slice(&bytes[8..12]);
+        let version = u32::from_le_bytes(word);
+        if version != VERSION {
+            return Err(invalid(format!("binary graph version {} is not supported; expected version {}", version, VERSION)))
+        }
+        word.copy_from_slice(&bytes[12..16]);
+        let flags = u32::from_le_bytes(word);
+        if flags > 1 {
+            return Err(invalid(format!("unknown flags {:#x} in the binary graph header", flags)))
+        }
+        let (n, m, label_bytes) = (entry(&bytes[16..], 0), entry(&bytes[16..], 1), entry(&bytes[16..], 2));
+        let expected = (n.checked_add(1).and_then(|v| v.checked_mul(2)))
+                       .and_then(|entries| entries.checked_add(m))
+                       .and_then(|entries| entries.checked_mul(8))
+                       .and_then(|size| size.checked_add(HEADER_SIZE))
+                       .and_then(|size| size.checked_add(label_bytes));
+        if expected != Some(bytes.len()) {
+            return Err(invalid(format!("a binary graph with {} vertices, {} arcs and {} bytes of labels can't be {} bytes long",
+                                       n, m, label_bytes, bytes.len())))
+        }
+
+        let view = GraphView::from_checked_bytes(bytes);
+        for (section, count, kind) in [(view.offsets, m, "arc"), (view.label_offsets, label_bytes, "label")].iter() {
+            if entry(section, 0) != 0 || entry(section, n) != *count {
+                return Err(invalid(format!("the {} offsets must run from 0 to {}", kind, count)))
+            }
+            if let Some(v) = (0..n).find(|&v| entry(section, v) > entry(section, v + 1)) {
+                return Err(invalid(format!("the {} offsets of vertex {} are out of order", kind, v)))
+            }
+        }
+        if let Some(i) = (0..m).find(|&i| entry(view.targets, i) >= n) {
+            return Err(invalid(format!("arc {} goes to vertex {}, but there are only {} vertices", i, entry(view.targets, i), n)))
+        }
+        if let Some(v) = (0..n).find(|&v| str::from_utf8(view.label_bytes(v)).is_err()) {
+            return Err(invalid(format!("the label of vertex {} is not valid UTF-8", v)))
+        }
+        Ok(view)
+    }
+
+    // For bytes that from_bytes has already accepted.
+    fn from_checked_bytes(bytes : &'a [u8]) -> GraphView<'a> {
+        let (n, m) = (entry(&bytes[16..], 0), entry(&bytes[16..], 1));
+        let targets_start = HEADER_SIZE + 8 * (n + 1);
+        let label_offsets_start = targets_start + 8 * m;
+        let labels_start = label_offsets_start + 8 * (n + 1);
+        GraphView { directed : bytes[12] & 1 == 1, n, m,
+                    offsets : &bytes[HEADER_SIZE..targets_start],
+                    targets : &bytes[targets_start..label_offsets_start],
+                    label_offsets : &bytes[label_offsets_start..labels_start],
+                    labels : &bytes[labels_start..] }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn number_of_vertices(&self) -> usize {
+        self.n
+    }
+
+    // Each undirected edge counts twice, except for self-loops.
+    pub fn number_of_arcs(&self) -> usize {
+        self.m
+    }
+
+    pub fn get_degree_from_index(&self, index : usize) -> usize {
+        entry(self.offsets, index + 1) - entry(self.offsets, index)
+    }
+
+    pub fn neighbours(&self, index : usize) -> Neighbours<'a> {
+        Neighbours { targets : self.targets, next : entry(self.offsets, index), end : entry(self.offsets, index + 1) }
+    }
+
+    fn label_bytes(&self, index : usize) -> &'a [u8] {
+        &self.labels[entry(self.label_offsets, index)..entry(self.label_offsets, index + 1)]
+    }
+
+    pub fn label(&self, index : usize) -> &'a str {
+        str::from_utf8(self.label_bytes(index)).unwrap()  // Checked by from_bytes
+    }
+
+    // Copies the view into a graph, parsing the labels. The graph keeps parallel
+    // edges and allows self-loops.
+    pub fn to_graph<T>(&self) -> io::Result<Graph<T>>
+        where T : Clone + Eq + Hash + FromStr
+    {
+        let mut nodes = Vec::with_capacity(self.n);
+        for v in 0..self.n {
+            match self.label(v).parse::<T>() {
+                Ok(label) => nodes.push(label),
+                Err(_) => return Err(invalid(format!("the label \"{}\" of vertex {} can't be parsed", self.label(v), v))),
+            }
+        }
+        let config = GraphConfig { parallel_edges : ParallelEdgePolicy::Keep, self_loops : SelfLoopPolicy::Allow };
+        let mut g = unconnected_with_config(nodes, self.directed, config);
+        if g.node_indices.len() != self.n {
+            return Err(invalid(String::from("the node labels must be distinct")))
+        }
+        for source in 0..self.n {
+            for dest in self.neighbours(source) {
+                if self.directed {
+                    g.add_directed_edge(source, dest);
+                } else if source <= dest {
+                    g.add_undirected_edge(source, dest);
+                }
+            }
+        }
+        Ok(g)
+    }
+}
+
+pub struct Neighbours<'a> {
+    targets : &'a [u8],
+    next    : usize,
+    end     : usize,
+}
+
+impl<'a> Iterator for Neighbours<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.next == self.end {
+            return None
+        }
+        self.next += 1;
+        Some(entry(self.targets, self.next - 1))
+    }
+}
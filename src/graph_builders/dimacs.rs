@@ -0,0 +1,344 @@
+// DIMACS shortest-path and maximum-flow problem files.
+//
+//     c A comment
+//     p sp 3 2          p max 3 2
+//                       n 1 s
+//                       n 3 t
+//     a 1 2 7           a 1 2 7
+//     a 2 3 -1          a 2 3 4
+//
+// Vertices are numbered from 1 in the files but from 0 in the graph, which is
+// always directed. Arc lengths are kept as the int edge attribute "weight", and
+// capacities as the int edge attribute "capacity". Parallel arcs are kept.
+
+use super::super::{EdgeIndex, Graph, GraphConfig, ParallelEdgePolicy, SelfLoopPolicy};
+use super::super::attributes::AttributeType;
+use super::{unconnected_with_config, whole_number, write_atomically, Diagnostic, ParseError, ParseMode, Section, Severity};
+use std::fs::File;
+use std::hash::Hash;
+use std::io;
+use std::io::BufReader;
+use std::io::prelude::*;
+
+const SECTION : Section = Section::Other("DIMACS");
+
+// A maximum-flow problem: the network, and its source and sink vertices.
+pub struct MaxFlowProblem {
+    pub graph  : Graph<usize>,
+    pub source : usize,
+    pub sink   : usize,
+}
+
+pub fn from_dimacs_sp_file(filename : &str) -> Result<Graph<usize>, ParseError> {
+    from_dimacs_sp_reader(BufReader::new(File::open(filename)?))
+}
+
+pub fn from_dimacs_sp_str(text : &str) -> Result<Graph<usize>, ParseError> {
+    from_dimacs_sp_reader(text.as_bytes())
+}
+
+pub fn from_dimacs_sp_reader<R : BufRead>(reader : R) -> Result<Graph<usize>, ParseError> {
+    from_dimacs_sp_reader_with_mode(reader, ParseMode::Strict)
+}
+
+pub fn from_dimacs_sp_reader_with_mode<R : BufRead>(reader : R, mode : ParseMode) -> Result<Graph<usize>, ParseError> {
+    read_problem(reader, "sp", mode).map(|(graph, _)| graph)
+}
+
+pub fn from_dimacs_max_flow_file(filename : &str) -> Result<MaxFlowProblem, ParseError> {
+    from_dimacs_max_flow_reader(BufReader::new(File::open(filename)?))
+}
+
+pub fn from_dimacs_max_flow_str(text : &str) -> Result<MaxFlowProblem, ParseError> {
+    from_dimacs_max_flow_reader(text.as_bytes())
+}
+
+pub fn from_dimacs_max_flow_reader<R : BufRead>(reader : R) -> Result<MaxFlowProblem, ParseError> {
+    from_dimacs_max_flow_reader_with_mode(reader, ParseMode::Strict)
+}
+
+pub fn from_dimacs_max_flow_reader_with_mode<R : BufRead>(reader : R, mode : ParseMode) -> Result<MaxFlowProblem, ParseError> {
+    read_problem(reader, "max", mode).map(|(graph, terminals)| {
+        MaxFlowProblem { graph, source : terminals[0].unwrap(), sink : terminals[1].unwrap() }
+    })
+}
+
+fn diagnostic(line : usize, column : usize, expected : &str, found : String) -> Diagnostic {
+    Diagnostic { line, column, section : SECTION, expected : String::from(expected), found, severity : Severity::Error }
+}
+
+// Reads a problem of the given type. For max-flow problems the source and sink
+// are also returned.
+fn read_problem<R : BufRead>(reader : R, problem : &str, mode : ParseMode) -> Result<(Graph<usize>, [Option<usize>; 2]), ParseError> {
+    let weight_name = if problem == "sp" { "weight" } else { "capacity" };
+    let mut diagnostics = Vec::new();
+    let mut graph : Option<Graph<usize>> = None;
+    let mut problem_line = 0;
+    let mut expected_arcs = 0;
+    let mut terminals = [None, None];
+    let mut last_line = 0;
+
+    for (line_index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = line_index + 1;
+        last_line = line_number;
+        if mode == ParseMode::Strict && !diagnostics.is_empty() { break }
+
+        let tokens = tokenize(&line);
+        let (kind, kind_column) = match tokens.first() {
+            None => continue,
+            Some(&(column, text)) => (text, column),
+        };
+        let mut error = |index : usize, expected : &str| {
+            let (column, found) = match tokens.get(index) {
+                Some(&(column, text)) => (column, format!("\"{}\"", text)),
+                None => (line.chars().count() + 1, String::from("end of line")),
+            };
+            diagnostics.push(diagnostic(line_number, column, expected, found));
+        };
+
+        match kind {
+            "c" => (),
+            "p" => {
+                if graph.is_some() {
+                    error(0, "only one problem line");
+                    continue
+                }
+                if tokens.get(1).map(|t| t.1) != Some(problem) {
+                    error(1, &format!("the problem type {}", problem));
+                    continue
+                }
+                let counts : Vec<Option<usize>> = (2..4).map(|i| tokens.get(i).and_then(|t| t.1.parse().ok())).collect();
+                match (counts[0], counts[1]) {
+                    (Some(n), Some(m)) => {
+                        if tokens.len() > 4 {
+                            error(4, "end of line");
+                            continue
+                        }
+                        let config = GraphConfig { parallel_edges : ParallelEdgePolicy::Keep, self_loops : SelfLoopPolicy::Allow };
+                        let mut g = unconnected_with_config((0..n).collect(), true, config);
+                        g.edge_attributes.declare(weight_name, AttributeType::Int, None).unwrap();
+                        graph = Some(g);
+                        problem_line = line_number;
+                        expected_arcs = m;
+                    },
+                    (None, _) => error(2, "a number of vertices"),
+                    (_, None) => error(3, "a number of arcs"),
+                }
+            },
+            "n" if problem == "max" => {
+                let n = match graph {
+                    Some(ref g) => g.number_of_vertices(),
+                    None => {
+                        error(0, "a problem line before any node lines");
+                        continue
+                    },
+                };
+                let vertex = match parse_vertex(&tokens, 1, n) {
+                    Ok(vertex) => vertex,
+                    Err(expected) => {
+                        error(1, &expected);
+                        continue
+                    },
+                };
+                let which = match tokens.get(2).map(|t| t.1) {
+                    Some("s") => 0,
+                    Some("t") => 1,
+                    _ => {
+                        error(2, "s or t");
+                        continue
+                    },
+                };
+                if tokens.len() > 3 {
+                    error(3, "end of line");
+                } else if terminals[which].is_some() {
+                    error(2, &format!("only one {} line", if which == 0 { "source" } else { "sink" }));
+                } else {
+                    terminals[which] = Some(vertex);
+                }
+            },
+            "a" => {
+                let g = match graph {
+                    Some(ref mut g) => g,
+                    None => {
+                        error(0, "a problem line before any arcs");
+                        continue
+                    },
+                };
+                let n = g.number_of_vertices();
+                let mut endpoints = [0; 2];
+                let mut valid = true;
+                for (i, endpoint) in endpoints.iter_mut().enumerate() {
+                    match parse_vertex(&tokens, i + 1, n) {
+                        Ok(vertex) => *endpoint = vertex,
+                        Err(expected) => {
+                            error(i + 1, &expected);
+                            valid = false;
+                            break
+                        },
+                    }
+                }
+                if !valid { continue }
+                let weight = match tokens.get(3).and_then(|t| t.1.parse::<i64>().ok()) {
+                    Some(weight) => weight,
+                    None => {
+                        error(3, &format!("an integer {}", if problem == "sp" { "length" } else { "capacity" }));
+                        continue
+                    },
+                };
+                if tokens.len() > 4 {
+                    error(4, "end of line");
+                    continue
+                }
+                let edge = g.add_directed_edge(endpoints[0], endpoints[1]);
+                g.edge_attributes.set(edge, weight_name, weight).unwrap();
+            },
+            _ => diagnostics.push(diagnostic(line_number, kind_column,
+                                             if problem == "sp" { "c, p or a" } else { "c, p, n or a" },
+                                             format!("\"{}\"", kind))),
+        }
+    }
+
+    let end_of_file = last_line + 1;
+    let graph = match graph {
+        Some(g) => {
+            if g.number_of_edges() != expected_arcs && diagnostics.is_empty() {
+                diagnostics.push(diagnostic(problem_line, 1, &format!("{} arcs, as given on the problem line", expected_arcs),
+                                            format!("{}", g.number_of_edges())));
+            }
+            Some(g)
+        },
+        None => {
+            diagnostics.push(diagnostic(end_of_file, 1, &format!("a problem line, p {}", problem), String::from("end of file")));
+            None
+        },
+    };
+    if problem == "max" && graph.is_some() {
+        if terminals[0].is_none() { diagnostics.push(diagnostic(end_of_file, 1, "a source, given by n <vertex> s", String::from("end of file"))) }
+        if terminals[1].is_none() { diagnostics.push(diagnostic(end_of_file, 1, "a sink, given by n <vertex> t", String::from("end of file"))) }
+    }
+
+    if diagnostics.is_empty() {
+        return Ok((graph.unwrap(), terminals))
+    }
+    if mode == ParseMode::Strict {
+        diagnostics.truncate(1);
+    }
+    Err(ParseError::Invalid(diagnostics))
+}
+
+// Whitespace-separated words, with their columns.
+fn tokenize(line : &str) -> Vec<(usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (i, c) in line.char_indices().chain(Some((line.len(), ' '))) {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                tokens.push((line[..s].chars().count() + 1, &line[s..i]));
+                start = None;
+            },
+            (false, None) => start = Some(i),
+            _ => (),
+        }
+    }
+    tokens
+}
+
+// A 1-indexed vertex number, as a 0-indexed vertex.
+fn parse_vertex(tokens : &[(usize, &str)], index : usize, n : usize) -> Result<usize, String> {
+    match tokens.get(index).and_then(|t| t.1.parse::<usize>().ok()) {
+        Some(v) if v >= 1 && v <= n => Ok(v - 1),
+        _ => Err(format!("a vertex number from 1 to {}", n)),
+    }
+}
+
+// Panics if the graph can't be written; see write_dimacs_sp.
+pub fn make_dimacs_sp_string<T>(graph : &Graph<T>) -> String
+    where T : Clone + Eq + Hash
+{
+    let mut dimacs = Vec::new();
+    write_dimacs_sp(graph, &mut dimacs).unwrap();
+    String::from_utf8(dimacs).unwrap()
+}
+
+// Panics if the graph can't be written; see write_dimacs_max_flow.
+pub fn make_dimacs_max_flow_string<T>(graph : &Graph<T>, source : usize, sink : usize) -> String
+    where T : Clone + Eq + Hash
+{
+    let mut dimacs = Vec::new();
+    write_dimacs_max_flow(graph, source, sink, &mut dimacs).unwrap();
+    String::from_utf8(dimacs).unwrap()
+}
+
+pub fn to_dimacs_sp_file<T>(graph : &Graph<T>, filename : &str) -> io::Result<()>
+    where T : Clone + Eq + Hash
+{
+    write_atomically(filename, |writer| write_dimacs_sp(graph, writer))
+}
+
+pub fn to_dimacs_max_flow_file<T>(graph : &Graph<T>, source : usize, sink : usize, filename : &str) -> io::Result<()>
+    where T : Clone + Eq + Hash
+{
+    write_atomically(filename, |writer| write_dimacs_max_flow(graph, source, sink, writer))
+}
+
+// Arcs without a "weight" get length 1. An undirected edge is written as a pair
+// of arcs, one in each direction. Lengths can only be whole numbers, so a
+// "weight" that isn't an int or a whole float is an InvalidInput error, before
+// anything is written.
+pub fn write_dimacs_sp<T, W>(graph : &Graph<T>, mut writer : W) -> io::Result<()>
+    where T : Clone + Eq + Hash, W : Write
+{
+    check_weights(graph, "weight")?;
+    writeln!(writer, "p sp {} {}", graph.number_of_vertices(), number_of_arcs(graph))?;
+    write_arcs(graph, &mut writer, "weight")
+}
+
+// Arcs without a "capacity" get capacity 1. As with lengths, a "capacity" that
+// isn't a whole number is an InvalidInput error.
+pub fn write_dimacs_max_flow<T, W>(graph : &Graph<T>, source : usize, sink : usize, mut writer : W) -> io::Result<()>
+    where T : Clone + Eq + Hash, W : Write
+{
+    check_weights(graph, "capacity")?;
+    writeln!(writer, "p max {} {}", graph.number_of_vertices(), number_of_arcs(graph))?;
+    writeln!(writer, "n {} s", source + 1)?;
+    writeln!(writer, "n {} t", sink + 1)?;
+    write_arcs(graph, &mut writer, "capacity")
+}
+
+fn number_of_arcs<T>(graph : &Graph<T>) -> usize
+    where T : Clone + Eq + Hash
+{
+    graph.adjacency_list.iter().map(|dests| dests.len()).sum()
+}
+
+fn check_weights<T>(graph : &Graph<T>, weight_name : &str) -> io::Result<()>
+    where T : Clone + Eq + Hash
+{
+    let not_int = (0..graph.number_of_edges()).map(EdgeIndex::new)
+                                               .filter_map(|edge| graph.edge_attributes.get(edge, weight_name).map(|w| (edge, w)))
+                                               .find(|&(_, w)| whole_number(w).is_none());
+    match not_int {
+        Some((edge, w)) => {
+            let (source, dest) = graph.edge_endpoints(edge);
+            Err(io::Error::new(io::ErrorKind::InvalidInput,
+                               format!("DIMACS can only hold whole numbers, not {} for the {} of the edge from {} to {}", w, weight_name, source, dest)))
+        },
+        None => Ok(()),
+    }
+}
+
+fn write_arcs<T, W>(graph : &Graph<T>, writer : &mut W, weight_name : &str) -> io::Result<()>
+    where T : Clone + Eq + Hash, W : Write
+{
+    for source in 0..graph.number_of_vertices() {
+        for (&dest, &edge) in graph.adjacency_list[source].iter().zip(graph.adjacency_edges[source].iter()) {
+            match graph.edge_attributes.get(edge, weight_name) {
+                // Already checked by check_weights
+                Some(weight) => writeln!(writer, "a {} {} {}", source + 1, dest + 1, whole_number(weight).unwrap())?,
+                None => writeln!(writer, "a {} {} 1", source + 1, dest + 1)?,
+            }
+        }
+    }
+    Ok(())
+}
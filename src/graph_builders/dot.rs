@@ -0,0 +1,622 @@
+// Graphviz DOT output, for looking at graphs, and input.
+//
+// Nodes are identified by their index, and labelled with their Display form.
+// Styling is added by closures that return extra attributes for each node and
+// edge, as (name, value) pairs. These take precedence over anything the writer
+// adds itself, such as the label.
+
+use super::super::{DFSEdgeType, EdgeIndex, Graph, GraphConfig, ParallelEdgePolicy, SelfLoopPolicy};
+use super::super::attributes::is_attribute_name;
+use super::{unconnected_with_config, write_atomically, Diagnostic, ParseError, ParseMode, Section, Severity};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs::File;
+use std::hash::Hash;
+use std::io;
+use std::io::BufReader;
+use std::io::prelude::*;
+
+pub fn make_dot_string<T>(graph : &Graph<T>) -> String
+    where T : Clone + Eq + Hash + Display
+{
+    make_dot_string_with_style(graph, |_, _| Vec::new(), |_, _, _| Vec::new(), false)
+}
+
+pub fn make_dot_string_with_style<T, F, G>(graph : &Graph<T>, node_style : F, edge_style : G, annotate_dfs : bool) -> String
+    where T : Clone + Eq + Hash + Display,
+          F : FnMut(usize, &T) -> Vec<(String, String)>,
+          G : FnMut(EdgeIndex, usize, usize) -> Vec<(String, String)>
+{
+    let mut dot = Vec::new();
+    write_dot_with_style(graph, &mut dot, node_style, edge_style, annotate_dfs).unwrap();  // Writing to a Vec can't fail
+    String::from_utf8(dot).unwrap()
+}
+
+pub fn to_dot_file<T>(graph : &Graph<T>, filename : &str) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display
+{
+    write_atomically(filename, |writer| write_dot(graph, writer))
+}
+
+pub fn write_dot<T, W>(graph : &Graph<T>, writer : W) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display, W : Write
+{
+    write_dot_with_style(graph, writer, |_, _| Vec::new(), |_, _, _| Vec::new(), false)
+}
+
+// node_style is given each node's index and label, and edge_style each edge and
+// its endpoints. If annotate_dfs is set then each edge is labelled and styled
+// with its type in a depth-first search: tree edges are bold, back edges dashed,
+// forward edges dotted and cross edges grey.
+pub fn write_dot_with_style<T, W, F, G>(graph : &Graph<T>, mut writer : W, mut node_style : F, mut edge_style : G, annotate_dfs : bool) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display, W : Write,
+          F : FnMut(usize, &T) -> Vec<(String, String)>,
+          G : FnMut(EdgeIndex, usize, usize) -> Vec<(String, String)>
+{
+    let (keyword, edge_op) = if graph.is_directed() { ("digraph", "->") } else { ("graph", "--") };
+    writeln!(writer, "{} {{", keyword)?;
+
+    for (index, node) in graph.nodes.iter().enumerate() {
+        let mut attributes = vec![(String::from("label"), node.to_string())];
+        merge(&mut attributes, node_style(index, node));
+        writeln!(writer, "    {} {};", index, attribute_list(&attributes))?;
+    }
+
+    let edge_types = if annotate_dfs { Some(graph.depth_first_edge_types()) } else { None };
+    for source in 0..graph.number_of_vertices() {
+        for (&dest, &edge) in graph.adjacency_list[source].iter().zip(graph.adjacency_edges[source].iter()) {
+            // Each undirected edge is only written once, from its lower endpoint.
+            if !graph.is_directed() && dest < source { continue }
+
+            let mut attributes = Vec::new();
+            if let Some(edge_type) = edge_types.as_ref().and_then(|types| types.get(&edge)) {
+                let (name, style) = match *edge_type {
+                    DFSEdgeType::Tree    => ("tree", ("style", "bold")),
+                    DFSEdgeType::Back    => ("back", ("style", "dashed")),
+                    DFSEdgeType::Forward => ("forward", ("style", "dotted")),
+                    DFSEdgeType::Cross   => ("cross", ("color", "gray")),
+                };
+                attributes.push((String::from("label"), String::from(name)));
+                attributes.push((String::from(style.0), String::from(style.1)));
+            }
+            merge(&mut attributes, edge_style(edge, source, dest));
+            if attributes.is_empty() {
+                writeln!(writer, "    {} {} {};", source, edge_op, dest)?;
+            } else {
+                writeln!(writer, "    {} {} {} {};", source, edge_op, dest, attribute_list(&attributes))?;
+            }
+        }
+    }
+
+    writeln!(writer, "}}")
+}
+
+// Adds the extra attributes, replacing any existing ones with the same name.
+fn merge(attributes : &mut Vec<(String, String)>, extra : Vec<(String, String)>) {
+    for (name, value) in extra {
+        match attributes.iter_mut().find(|a| a.0 == name) {
+            Some(existing) => existing.1 = value,
+            None => attributes.push((name, value)),
+        }
+    }
+}
+
+fn attribute_list(attributes : &[(String, String)]) -> String {
+    let pairs : Vec<String> = attributes.iter().map(|(name, value)| format!("{}={}", id(name), quote(value))).collect();
+    format!("[{}]", pairs.join(", "))
+}
+
+// Attribute names are left bare when they're plain identifiers, such as color.
+fn id(text : &str) -> String {
+    let is_plain = text.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                   && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if is_plain { String::from(text) } else { quote(text) }
+}
+
+// A quoted string is always a valid ID, whatever it contains.
+fn quote(text : &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"'  => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => (),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+// Graphviz DOT input.
+//
+// Node IDs become the labels of a Graph<String>, in the order in which they're
+// first mentioned. Subgraphs are flattened into the graph, and an edge to or
+// from a subgraph connects to every node in it. Attributes given to nodes and
+// edges, including those from node [...] and edge [...] defaults, are kept as
+// string attributes unless keep_attributes is false. Graph attributes, ports and
+// attributes whose names aren't made of letters, digits and '_' are ignored.
+//
+// A DOT statement can span several lines, so there's no good way to carry on
+// after a syntax error. Lenient mode only affects problems such as using -> in
+// an undirected graph, which don't stop the rest of the file being read.
+
+pub fn from_dot_file(filename : &str) -> Result<Graph<String>, ParseError> {
+    from_dot_reader(BufReader::new(File::open(filename)?))
+}
+
+pub fn from_dot_str(text : &str) -> Result<Graph<String>, ParseError> {
+    from_dot_reader(text.as_bytes())
+}
+
+pub fn from_dot_reader<R : Read>(reader : R) -> Result<Graph<String>, ParseError> {
+    from_dot_reader_with_options(reader, ParseMode::Strict, true)
+}
+
+pub fn from_dot_reader_with_options<R : Read>(mut reader : R, mode : ParseMode, keep_attributes : bool) -> Result<Graph<String>, ParseError> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+
+    let tokens = tokenize(&text).map_err(|d| ParseError::Invalid(vec![d]))?;
+    let mut parser = DotParser::new(tokens, mode);
+    let result = parser.parse_graph();
+    if result.is_err() || !parser.diagnostics.is_empty() {
+        if mode == ParseMode::Strict { parser.diagnostics.truncate(1) }
+        return Err(ParseError::Invalid(parser.diagnostics))
+    }
+
+    let config = GraphConfig { parallel_edges : if parser.strict { ParallelEdgePolicy::Merge } else { ParallelEdgePolicy::Keep },
+                               self_loops     : SelfLoopPolicy::Allow };
+    let mut g = unconnected_with_config(parser.nodes, parser.directed, config);
+    for (source, dest, attributes) in parser.edges {
+        let edge = if parser.directed { g.add_directed_edge(source, dest) } else { g.add_undirected_edge(source, dest) };
+        if keep_attributes {
+            for (name, value) in attributes.into_iter().filter(|(name, _)| is_attribute_name(name)) {
+                g.edge_attributes.set(edge, &name, value).unwrap();    // Every attribute is a string
+            }
+        }
+    }
+    if keep_attributes {
+        for (node, name, value) in parser.node_attributes.into_iter().filter(|(_, name, _)| is_attribute_name(name)) {
+            g.node_attributes.set(node, &name, value).unwrap();
+        }
+    }
+    Ok(g)
+}
+
+const SECTION : Section = Section::Other("DOT graph");
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+enum Keyword {
+    Strict,
+    Graph,
+    Digraph,
+    Node,
+    Edge,
+    Subgraph,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+enum TokenKind {
+    Id       (String),
+    Keyword  (Keyword),
+    Punct    (char),      // { } [ ] ; , = :
+    EdgeOp   (bool),      // Whether it's ->, rather than --
+    End,
+}
+
+struct Token {
+    kind   : TokenKind,
+    line   : usize,
+    column : usize,
+    text   : String,      // As it appeared in the file, for diagnostics
+}
+
+// Splits the whole file into tokens, with comments removed.
+fn tokenize(text : &str) -> Result<Vec<Token>, Diagnostic> {
+    let chars : Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let (mut i, mut line, mut column) = (0, 1, 1);
+    let mut at_line_start = true;
+
+    // Moves past n characters, keeping track of the line and column.
+    let advance = |i : &mut usize, line : &mut usize, column : &mut usize, n : usize| {
+        for _ in 0..n {
+            if chars[*i] == '\n' { *line += 1; *column = 1 } else { *column += 1 }
+            *i += 1;
+        }
+    };
+    let error = |line : usize, column : usize, expected : &str, found : String| {
+        Diagnostic { line, column, section : SECTION, expected : String::from(expected), found, severity : Severity::Error }
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).cloned();
+        let (start_line, start_column, start) = (line, column, i);
+
+        if c == '\n' {
+            at_line_start = true;
+            advance(&mut i, &mut line, &mut column, 1);
+            continue
+        }
+        if c.is_whitespace() {
+            advance(&mut i, &mut line, &mut column, 1);
+            continue
+        }
+        let line_start = at_line_start;
+        at_line_start = false;
+
+        // Comments, including lines of C preprocessor output
+        if (c == '#' && line_start) || (c == '/' && next == Some('/')) {
+            while i < chars.len() && chars[i] != '\n' { advance(&mut i, &mut line, &mut column, 1) }
+            continue
+        }
+        if c == '/' && next == Some('*') {
+            advance(&mut i, &mut line, &mut column, 2);
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                advance(&mut i, &mut line, &mut column, 1);
+            }
+            if i >= chars.len() {
+                return Err(error(start_line, start_column, "the end of the comment, */", String::from("end of file")))
+            }
+            advance(&mut i, &mut line, &mut column, 2);
+            continue
+        }
+
+        let kind = if c == '-' && (next == Some('>') || next == Some('-')) {
+            advance(&mut i, &mut line, &mut column, 2);
+            TokenKind::EdgeOp(next == Some('>'))
+        } else if "{}[];,=:".contains(c) {
+            advance(&mut i, &mut line, &mut column, 1);
+            TokenKind::Punct(c)
+        } else if c == '"' {
+            // Quoted strings can be joined with +
+            let mut id = String::new();
+            loop {
+                let (quote_line, quote_column) = (line, column);
+                advance(&mut i, &mut line, &mut column, 1);
+                loop {
+                    match (chars.get(i).cloned(), chars.get(i + 1).cloned()) {
+                        (None, _) => return Err(error(quote_line, quote_column, "a closing '\"'", String::from("end of file"))),
+                        (Some('"'), _) => break,
+                        (Some('\\'), Some('"')) => { id.push('"'); advance(&mut i, &mut line, &mut column, 2) },
+                        (Some('\\'), Some('\n')) => advance(&mut i, &mut line, &mut column, 2),
+                        (Some(c), _) => { id.push(c); advance(&mut i, &mut line, &mut column, 1) },
+                    }
+                }
+                advance(&mut i, &mut line, &mut column, 1);
+
+                let mut j = i;
+                while j < chars.len() && chars[j].is_whitespace() { j += 1 }
+                if chars.get(j) != Some(&'+') { break }
+                j += 1;
+                while j < chars.len() && chars[j].is_whitespace() { j += 1 }
+                if chars.get(j) != Some(&'"') { break }
+                let skip = j - i;
+                advance(&mut i, &mut line, &mut column, skip);
+            }
+            TokenKind::Id(id)
+        } else if c == '<' {
+            // An HTML string, which runs to the matching '>'
+            let mut depth = 0;
+            let mut id = String::new();
+            loop {
+                match chars.get(i).cloned() {
+                    None => return Err(error(start_line, start_column, "a closing '>'", String::from("end of file"))),
+                    Some(c) => {
+                        if c == '<' { depth += 1 }
+                        if c == '>' { depth -= 1 }
+                        advance(&mut i, &mut line, &mut column, 1);
+                        if depth == 0 { break }
+                        if depth > 1 || c != '<' { id.push(c) }
+                    },
+                }
+            }
+            TokenKind::Id(id)
+        } else if c == '-' || c == '.' || c.is_ascii_digit() {
+            // A numeral, such as -1.5
+            let mut end = i + 1;
+            while end < chars.len() && (chars[end].is_ascii_digit() || chars[end] == '.') { end += 1 }
+            let numeral : String = chars[i..end].iter().collect();
+            let digits = numeral.trim_start_matches('-');
+            if digits.is_empty() || digits.matches('.').count() > 1 || digits == "." {
+                return Err(error(start_line, start_column, "a number", format!("\"{}\"", numeral)))
+            }
+            advance(&mut i, &mut line, &mut column, end - start);
+            TokenKind::Id(numeral)
+        } else if c.is_alphabetic() || c == '_' || !c.is_ascii() {
+            let mut end = i + 1;
+            while end < chars.len() && (chars[end].is_alphanumeric() || chars[end] == '_' || !chars[end].is_ascii()) { end += 1 }
+            let id : String = chars[i..end].iter().collect();
+            advance(&mut i, &mut line, &mut column, end - start);
+            match id.to_lowercase().as_str() {
+                "strict"   => TokenKind::Keyword(Keyword::Strict),
+                "graph"    => TokenKind::Keyword(Keyword::Graph),
+                "digraph"  => TokenKind::Keyword(Keyword::Digraph),
+                "node"     => TokenKind::Keyword(Keyword::Node),
+                "edge"     => TokenKind::Keyword(Keyword::Edge),
+                "subgraph" => TokenKind::Keyword(Keyword::Subgraph),
+                _ => TokenKind::Id(id),
+            }
+        } else {
+            return Err(error(start_line, start_column, "an ID, an edge operator or punctuation", format!("'{}'", c)))
+        };
+        let text = chars[start..i].iter().collect();
+        tokens.push(Token { kind, line : start_line, column : start_column, text });
+    }
+    tokens.push(Token { kind : TokenKind::End, line, column, text : String::from("end of file") });
+    Ok(tokens)
+}
+
+// (name, value) pairs, in the order given.
+type Attributes = Vec<(String, String)>;
+
+// Attribute defaults set by node [...] and edge [...] within a graph or subgraph.
+#[derive(Clone, Default)]
+struct Defaults {
+    node : Attributes,
+    edge : Attributes,
+}
+
+struct DotParser {
+    tokens          : Vec<Token>,
+    position        : usize,
+    mode            : ParseMode,
+    diagnostics     : Vec<Diagnostic>,
+    strict          : bool,
+    directed        : bool,
+    nodes           : Vec<String>,
+    node_indices    : HashMap<String, usize>,
+    node_attributes : Vec<(usize, String, String)>,
+    edges           : Vec<(usize, usize, Attributes)>,
+    defaults        : Vec<Defaults>,
+}
+
+// Err means that a syntax error has been reported and parsing should stop.
+type DotResult<T> = Result<T, ()>;
+
+// Subgraphs can't be nested any deeper than this, so that a hostile file can't
+// overflow the stack.
+const MAX_DEPTH : usize = 256;
+
+impl DotParser {
+
+    #[allow(clippy::redundant_field_names)]
+    fn new(tokens : Vec<Token>, mode : ParseMode) -> DotParser {
+        DotParser { tokens          : tokens,
+                    position        : 0,
+                    mode            : mode,
+                    diagnostics     : Vec::new(),
+                    strict          : false,
+                    directed        : false,
+                    nodes           : Vec::new(),
+                    node_indices    : HashMap::new(),
+                    node_attributes : Vec::new(),
+                    edges           : Vec::new(),
+                    defaults        : vec![Defaults::default()] }
+    }
+
+    fn peek(&self) -> &TokenKind {
+        &self.tokens[self.position].kind
+    }
+
+    fn peek_next(&self) -> &TokenKind {
+        &self.tokens[(self.position + 1).min(self.tokens.len() - 1)].kind
+    }
+
+    fn bump(&mut self) -> TokenKind {
+        let kind = self.tokens[self.position].kind.clone();
+        if kind != TokenKind::End { self.position += 1 }
+        kind
+    }
+
+    // Reports a problem with the current token, and returns Err.
+    fn error<X>(&mut self, expected : &str) -> DotResult<X> {
+        let t = &self.tokens[self.position];
+        let found = if t.kind == TokenKind::End { t.text.clone() } else { format!("\"{}\"", t.text) };
+        self.diagnostics.push(Diagnostic { line : t.line, column : t.column, section : SECTION,
+                                           expected : String::from(expected), found, severity : Severity::Error });
+        Err(())
+    }
+
+    fn expect_punct(&mut self, c : char, expected : &str) -> DotResult<()> {
+        if *self.peek() == TokenKind::Punct(c) {
+            self.bump();
+            Ok(())
+        } else {
+            self.error(expected)
+        }
+    }
+
+    fn expect_id(&mut self, expected : &str) -> DotResult<String> {
+        match self.peek().clone() {
+            TokenKind::Id(id) => {
+                self.bump();
+                Ok(id)
+            },
+            _ => self.error(expected),
+        }
+    }
+
+    // [strict] (graph | digraph) [ID] { stmt_list }
+    fn parse_graph(&mut self) -> DotResult<()> {
+        if *self.peek() == TokenKind::Keyword(Keyword::Strict) {
+            self.bump();
+            self.strict = true;
+        }
+        match *self.peek() {
+            TokenKind::Keyword(Keyword::Graph)   => self.directed = false,
+            TokenKind::Keyword(Keyword::Digraph) => self.directed = true,
+            _ => return self.error("graph or digraph"),
+        }
+        self.bump();
+        if let TokenKind::Id(_) = *self.peek() { self.bump(); }
+        self.expect_punct('{', "'{'")?;
+        self.parse_statements(&mut Vec::new())?;
+        self.expect_punct('}', "'}'")?;
+        if *self.peek() != TokenKind::End {
+            return self.error("end of file after the graph")
+        }
+        Ok(())
+    }
+
+    // Statements up to the closing '}'. Every node mentioned is added to members.
+    fn parse_statements(&mut self, members : &mut Vec<usize>) -> DotResult<()> {
+        loop {
+            match *self.peek() {
+                TokenKind::Punct('}') => return Ok(()),
+                TokenKind::Punct(';') => { self.bump(); },
+                _ => self.parse_statement(members)?,
+            }
+        }
+    }
+
+    fn parse_statement(&mut self, members : &mut Vec<usize>) -> DotResult<()> {
+        match self.peek().clone() {
+            TokenKind::Keyword(Keyword::Graph) => {
+                self.bump();
+                self.parse_attribute_list()?;
+                Ok(())
+            },
+            TokenKind::Keyword(keyword @ Keyword::Node) | TokenKind::Keyword(keyword @ Keyword::Edge) => {
+                self.bump();
+                if *self.peek() != TokenKind::Punct('[') {
+                    return self.error("'[' after node or edge")
+                }
+                let attributes = self.parse_attribute_list()?;
+                let defaults = self.defaults.last_mut().unwrap();
+                let target = if keyword == Keyword::Node { &mut defaults.node } else { &mut defaults.edge };
+                merge(target, attributes);
+                Ok(())
+            },
+            TokenKind::Id(_) if *self.peek_next() == TokenKind::Punct('=') => {
+                // A graph attribute, such as rankdir=LR
+                self.bump();
+                self.bump();
+                self.expect_id("an attribute value")?;
+                Ok(())
+            },
+            TokenKind::Id(_) | TokenKind::Keyword(Keyword::Subgraph) | TokenKind::Punct('{') => {
+                let first = self.parse_endpoint(members)?;
+                if let TokenKind::EdgeOp(_) = *self.peek() {
+                    self.parse_edges(first, members)
+                } else {
+                    let attributes = if *self.peek() == TokenKind::Punct('[') { self.parse_attribute_list()? } else { Vec::new() };
+                    // A lone node ID can have attributes; a lone subgraph can't.
+                    for node in first {
+                        for (name, value) in attributes.iter().cloned() {
+                            self.node_attributes.push((node, name, value));
+                        }
+                    }
+                    Ok(())
+                }
+            },
+            _ => self.error("a statement, such as a node ID or edge"),
+        }
+    }
+
+    // A node ID or subgraph at one end of an edge. Returns the nodes it refers to.
+    fn parse_endpoint(&mut self, members : &mut Vec<usize>) -> DotResult<Vec<usize>> {
+        match *self.peek() {
+            TokenKind::Keyword(Keyword::Subgraph) | TokenKind::Punct('{') => {
+                // The graph itself has the first set of defaults, and each subgraph one more.
+                if self.defaults.len() > MAX_DEPTH {
+                    return self.error(&format!("subgraphs nested no more than {} deep", MAX_DEPTH))
+                }
+                if *self.peek() == TokenKind::Keyword(Keyword::Subgraph) {
+                    self.bump();
+                    if let TokenKind::Id(_) = *self.peek() { self.bump(); }
+                }
+                self.expect_punct('{', "'{' to start the subgraph")?;
+                let scope = self.defaults.last().unwrap().clone();
+                self.defaults.push(scope);
+                let mut subgraph_members = Vec::new();
+                self.parse_statements(&mut subgraph_members)?;
+                self.defaults.pop();
+                self.expect_punct('}', "'}'")?;
+                members.extend(subgraph_members.iter().cloned());
+                Ok(subgraph_members)
+            },
+            _ => {
+                let id = self.expect_id("a node ID")?;
+                // Ports, as in a:port:n, are ignored.
+                while *self.peek() == TokenKind::Punct(':') {
+                    self.bump();
+                    self.expect_id("a port name")?;
+                }
+                let node = self.node(id);
+                members.push(node);
+                Ok(vec![node])
+            },
+        }
+    }
+
+    // The rest of an edge statement such as a -> b -> c [colour=red], given its first endpoint.
+    fn parse_edges(&mut self, first : Vec<usize>, members : &mut Vec<usize>) -> DotResult<()> {
+        let mut endpoints = vec![first];
+        while let TokenKind::EdgeOp(directed) = *self.peek() {
+            if directed != self.directed {
+                let expected = if self.directed { "-> in a digraph" } else { "-- in an undirected graph" };
+                let _ = self.error::<()>(expected);
+                if self.mode == ParseMode::Strict { return Err(()) }
+            }
+            self.bump();
+            endpoints.push(self.parse_endpoint(members)?);
+        }
+        let mut attributes = self.defaults.last().unwrap().edge.clone();
+        if *self.peek() == TokenKind::Punct('[') {
+            let explicit = self.parse_attribute_list()?;
+            merge(&mut attributes, explicit);
+        }
+        for pair in endpoints.windows(2) {
+            for &source in &pair[0] {
+                for &dest in &pair[1] {
+                    self.edges.push((source, dest, attributes.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // One or more [name=value, ...] lists.
+    fn parse_attribute_list(&mut self) -> DotResult<Attributes> {
+        let mut attributes = Vec::new();
+        if *self.peek() != TokenKind::Punct('[') {
+            return self.error("'['")
+        }
+        while *self.peek() == TokenKind::Punct('[') {
+            self.bump();
+            loop {
+                if *self.peek() == TokenKind::Punct(']') {
+                    self.bump();
+                    break
+                }
+                let name = self.expect_id("an attribute name or ']'")?;
+                self.expect_punct('=', "'=' after the attribute name")?;
+                let value = self.expect_id("an attribute value")?;
+                attributes.push((name, value));
+                match *self.peek() {
+                    TokenKind::Punct(',') | TokenKind::Punct(';') => { self.bump(); },
+                    _ => (),
+                }
+            }
+        }
+        Ok(attributes)
+    }
+
+    // The index of the node with this ID, adding it if it's new.
+    fn node(&mut self, id : String) -> usize {
+        if let Some(&index) = self.node_indices.get(&id) {
+            return index
+        }
+        let index = self.nodes.len();
+        self.node_indices.insert(id.clone(), index);
+        self.nodes.push(id);
+        for (name, value) in self.defaults.last().unwrap().node.iter().cloned() {
+            self.node_attributes.push((index, name, value));
+        }
+        index
+    }
+}
@@ -0,0 +1,369 @@
+// Delimited edge lists, such as CSV and TSV files with a source,target[,weight]
+// row for each edge.
+//
+// Nodes are numbered in the order in which they're first seen. Fields can be
+// quoted as in CSV, with "" standing for a quote inside a quoted field, but a
+// field can't span more than one line. Weights are kept as the float edge
+// attribute "weight".
+
+use super::super::{EdgeError, Graph, GraphConfig};
+use super::super::attributes::AttributeType;
+use super::{unconnected_with_config, write_atomically, Diagnostic, ParseError, ParseMode, Section, Severity};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs::File;
+use std::hash::Hash;
+use std::io;
+use std::io::BufReader;
+use std::io::prelude::*;
+use std::str::FromStr;
+
+const SECTION : Section = Section::Other("edge list");
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Column {
+    Index (usize),      // Counting from 0
+    Name  (String),     // As given in the header row
+}
+
+#[derive(Clone, Debug)]
+pub struct EdgeListOptions {
+    pub delimiter      : char,
+    pub has_header     : bool,
+    pub source_column  : Column,
+    pub target_column  : Column,
+    pub weight_column  : Option<Column>,
+    pub comment_prefix : Option<String>,  // Lines starting with this are skipped
+    pub directed       : bool,
+    pub config         : GraphConfig,
+}
+
+impl EdgeListOptions {
+    pub fn csv() -> EdgeListOptions {
+        EdgeListOptions { delimiter      : ',',
+                          has_header     : true,
+                          source_column  : Column::Index(0),
+                          target_column  : Column::Index(1),
+                          weight_column  : None,
+                          comment_prefix : Some(String::from("#")),
+                          directed       : true,
+                          config         : GraphConfig::default() }
+    }
+
+    pub fn tsv() -> EdgeListOptions {
+        EdgeListOptions { delimiter : '\t', ..EdgeListOptions::csv() }
+    }
+}
+
+impl Default for EdgeListOptions {
+    fn default() -> EdgeListOptions {
+        EdgeListOptions::csv()
+    }
+}
+
+pub fn from_edge_list_file<T>(filename : &str, options : &EdgeListOptions) -> Result<Graph<T>, ParseError>
+    where T : Clone + Eq + Hash + FromStr
+{
+    from_edge_list_reader(BufReader::new(File::open(filename)?), options)
+}
+
+pub fn from_edge_list_str<T>(text : &str, options : &EdgeListOptions) -> Result<Graph<T>, ParseError>
+    where T : Clone + Eq + Hash + FromStr
+{
+    from_edge_list_reader(text.as_bytes(), options)
+}
+
+pub fn from_edge_list_reader<T, R>(reader : R, options : &EdgeListOptions) -> Result<Graph<T>, ParseError>
+    where T : Clone + Eq + Hash + FromStr, R : BufRead
+{
+    from_edge_list_reader_with_mode(reader, options, ParseMode::Strict)
+}
+
+pub fn from_edge_list_reader_with_mode<T, R>(reader : R, options : &EdgeListOptions, mode : ParseMode) -> Result<Graph<T>, ParseError>
+    where T : Clone + Eq + Hash + FromStr, R : BufRead
+{
+    let mut diagnostics = Vec::new();
+
+    let mut g = unconnected_with_config(Vec::new(), options.directed, options.config);
+    if options.weight_column.is_some() {
+        g.edge_attributes.declare("weight", AttributeType::Float, None).unwrap();
+    }
+    // The positions of the source, target and weight columns, once known.
+    let mut positions : Option<[Option<usize>; 3]> = None;
+    let columns = [Some(&options.source_column), Some(&options.target_column), options.weight_column.as_ref()];
+    let column_names = ["source", "target", "weight"];
+
+    for (line_index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = line_index + 1;
+        if mode == ParseMode::Strict && !diagnostics.is_empty() { break }
+        if line.trim().is_empty() { continue }
+        if let Some(ref prefix) = options.comment_prefix {
+            if line.trim_start().starts_with(prefix.as_str()) { continue }
+        }
+        let fields = match split_fields(&line, options.delimiter) {
+            Ok(fields) => fields,
+            Err((column, expected)) => {
+                diagnostics.push(diagnostic(line_number, column, String::from(expected), String::from("end of line")));
+                continue
+            },
+        };
+
+        let positions = match positions {
+            Some(ref positions) => positions,
+            None => {
+                // The first row is either the header, or the first edge.
+                let mut found = [None; 3];
+                for (i, column) in columns.iter().enumerate() {
+                    found[i] = match *column {
+                        None => None,
+                        Some(&Column::Index(index)) => Some(index),
+                        Some(Column::Name(name)) if options.has_header => {
+                            match fields.iter().position(|f| f.text == *name) {
+                                Some(index) => Some(index),
+                                None => {
+                                    diagnostics.push(diagnostic(line_number, 1, format!("a {} column named \"{}\" in the header", column_names[i], name), String::from("no such column")));
+                                    None
+                                },
+                            }
+                        },
+                        Some(&Column::Name(_)) => {
+                            diagnostics.push(diagnostic(line_number, 1, format!("a header row, since the {} column is given by name", column_names[i]), String::from("has_header false")));
+                            None
+                        },
+                    };
+                }
+                if found[0].is_none() || found[1].is_none() {
+                    // Without both endpoints none of the edges can be read.
+                    return Err(ParseError::Invalid(diagnostics))
+                }
+                positions = Some(found);
+                if options.has_header { continue }
+                positions.as_ref().unwrap()
+            },
+        };
+
+        let mut values = Vec::with_capacity(3);
+        for (i, position) in positions.iter().enumerate() {
+            match *position {
+                None => values.push(None),
+                Some(p) => match fields.get(p) {
+                    Some(field) => values.push(Some(field)),
+                    None => {
+                        let column = fields.last().map(|f| f.end_column).unwrap_or(1);
+                        diagnostics.push(diagnostic(line_number, column, format!("a {} in column {}", column_names[i], p + 1), format!("only {} columns", fields.len())));
+                        break
+                    },
+                },
+            }
+        }
+        if values.len() < 3 { continue }
+
+        let mut endpoints = [0; 2];
+        let mut valid = true;
+        for i in 0..2 {
+            let field = values[i].unwrap();
+            match field.text.parse::<T>() {
+                Ok(node) => endpoints[i] = node_index(&mut g, node),
+                Err(_) => {
+                    diagnostics.push(diagnostic(line_number, field.column, String::from("a valid node label"), format!("\"{}\"", field.text)));
+                    valid = false;
+                },
+            }
+        }
+        let weight = match values[2] {
+            Some(field) if field.text.trim().is_empty() => None,
+            Some(field) => match field.text.trim().parse::<f64>() {
+                Ok(weight) => Some(weight),
+                Err(_) => {
+                    diagnostics.push(diagnostic(line_number, field.column, String::from("a weight"), format!("\"{}\"", field.text)));
+                    valid = false;
+                    None
+                },
+            },
+            None => None,
+        };
+        if !valid { continue }
+
+        let added = if options.directed { g.try_add_directed_edge(endpoints[0], endpoints[1]) }
+                    else { g.try_add_undirected_edge(endpoints[0], endpoints[1]) };
+        match added {
+            Ok(edge) => {
+                if let Some(weight) = weight {
+                    g.edge_attributes.set(edge, "weight", weight).unwrap();
+                }
+            },
+            Err(e) => {
+                let expected = match e {
+                    EdgeError::ParallelEdge(_, _) => "no parallel edges, since parallel_edges is reject",
+                    EdgeError::SelfLoop(_) => "no self-loops, since self_loops is reject",
+                };
+                diagnostics.push(diagnostic(line_number, values[0].unwrap().column, String::from(expected), format!("{}", e)));
+            },
+        }
+    }
+
+    if diagnostics.is_empty() {
+        return Ok(g)
+    }
+    if mode == ParseMode::Strict {
+        diagnostics.truncate(1);
+    }
+    Err(ParseError::Invalid(diagnostics))
+}
+
+fn diagnostic(line : usize, column : usize, expected : String, found : String) -> Diagnostic {
+    Diagnostic { line, column, section : SECTION, expected, found, severity : Severity::Error }
+}
+
+// The index of the node, adding it to the graph if it's new.
+fn node_index<T>(g : &mut Graph<T>, node : T) -> usize
+    where T : Clone + Eq + Hash
+{
+    if let Some(&index) = g.node_indices.get(&node) {
+        return index
+    }
+    let index = g.nodes.len();
+    g.node_indices.insert(node.clone(), index);
+    g.nodes.push(node);
+    g.adjacency_list.push(Vec::new());
+    g.adjacency_edges.push(Vec::new());
+    if let Some(ref mut edge_index) = g.edge_index {
+        edge_index.push(HashMap::new());
+    }
+    index
+}
+
+struct Field {
+    text       : String,
+    column     : usize,    // Of the start of the field
+    end_column : usize,    // Just after the end of the field
+}
+
+// Splits a line into fields, removing any quotes. On failure, returns the column
+// and a description of what was expected.
+fn split_fields(line : &str, delimiter : char) -> Result<Vec<Field>, (usize, &'static str)> {
+    let chars : Vec<char> = line.chars().collect();
+    let mut fields = Vec::new();
+    let mut i = 0;
+    loop {
+        let start = i;
+        let mut text = String::new();
+        if chars.get(i) == Some(&'"') {
+            i += 1;
+            loop {
+                match (chars.get(i), chars.get(i + 1)) {
+                    (None, _) => return Err((start + 1, "a closing '\"'")),
+                    (Some(&'"'), Some(&'"')) => { text.push('"'); i += 2 },
+                    (Some(&'"'), _) => { i += 1; break },
+                    (Some(&c), _) => { text.push(c); i += 1 },
+                }
+            }
+            if i < chars.len() && chars[i] != delimiter {
+                return Err((i + 1, "a delimiter after the closing '\"'"))
+            }
+        } else {
+            while i < chars.len() && chars[i] != delimiter {
+                text.push(chars[i]);
+                i += 1;
+            }
+            // Spaces around unquoted fields are ignored, unless they're the delimiter.
+            if delimiter != ' ' { text = String::from(text.trim()) }
+        }
+        fields.push(Field { text, column : start + 1, end_column : i + 1 });
+        if i >= chars.len() { break }
+        i += 1;     // Past the delimiter
+    }
+    Ok(fields)
+}
+
+// Panics if two columns are given the same index; see write_edge_list.
+pub fn make_edge_list_string<T>(graph : &Graph<T>, options : &EdgeListOptions) -> String
+    where T : Clone + Eq + Hash + Display
+{
+    let mut edge_list = Vec::new();
+    write_edge_list(graph, &mut edge_list, options).unwrap();
+    String::from_utf8(edge_list).unwrap()
+}
+
+pub fn to_edge_list_file<T>(graph : &Graph<T>, filename : &str, options : &EdgeListOptions) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display
+{
+    write_atomically(filename, |writer| write_edge_list(graph, writer, options))
+}
+
+// Writes the source, target and, if there's a weight column, weight of each
+// edge. Columns given by index are written at that index, with empty fields in
+// any gaps, and columns given by name fill the first free places, in that order.
+// The header uses the column names from the options, or source, target and
+// weight for columns given by index. Nodes without edges aren't written.
+pub fn write_edge_list<T, W>(graph : &Graph<T>, mut writer : W, options : &EdgeListOptions) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display, W : Write
+{
+    let delimiter = options.delimiter.to_string();
+    let field = |text : &str| quote_field(text, options.delimiter, options.comment_prefix.as_deref());
+    let columns = [Some(&options.source_column), Some(&options.target_column), options.weight_column.as_ref()];
+    let positions = column_positions(&columns)?;
+    let width = positions.iter().filter_map(|&p| p).max().unwrap() + 1;
+    let row = |values : [Option<String>; 3]| {
+        let mut row = vec![String::new(); width];
+        for (value, position) in values.iter().zip(positions.iter()) {
+            if let (Some(value), Some(position)) = (value.as_ref(), *position) { row[position] = value.clone() }
+        }
+        row.join(&delimiter)
+    };
+    if options.has_header {
+        let name = |column : Option<&Column>, default : &str| match column {
+            Some(Column::Name(name)) => field(name),
+            _ => String::from(default),
+        };
+        let header = [Some(name(columns[0], "source")), Some(name(columns[1], "target")),
+                      columns[2].map(|column| name(Some(column), "weight"))];
+        writeln!(writer, "{}", row(header))?;
+    }
+
+    let labels : Vec<String> = graph.nodes.iter().map(|node| field(&node.to_string())).collect();
+    for source in 0..graph.number_of_vertices() {
+        for (&dest, &edge) in graph.adjacency_list[source].iter().zip(graph.adjacency_edges[source].iter()) {
+            // Each undirected edge is only written once, from its lower endpoint.
+            if !graph.is_directed() && dest < source { continue }
+            let weight = columns[2].map(|_| {
+                graph.edge_attributes.get(edge, "weight").and_then(|v| v.as_float()).map(|w| w.to_string()).unwrap_or_default()
+            });
+            writeln!(writer, "{}", row([Some(labels[source].clone()), Some(labels[dest].clone()), weight]))?;
+        }
+    }
+    Ok(())
+}
+
+// Where the source, target and weight columns go when writing: columns given by
+// index at their index, and those given by name in the first free places.
+fn column_positions(columns : &[Option<&Column>; 3]) -> io::Result<[Option<usize>; 3]> {
+    let mut positions = [None; 3];
+    for (i, column) in columns.iter().enumerate() {
+        if let Some(&Column::Index(index)) = *column {
+            if positions.contains(&Some(index)) {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Two columns can't both be written at index {}.", index)))
+            }
+            positions[i] = Some(index);
+        }
+    }
+    for (i, column) in columns.iter().enumerate() {
+        if let Some(&Column::Name(_)) = *column {
+            let free = (0..).find(|index| !positions.contains(&Some(*index))).unwrap();
+            positions[i] = Some(free);
+        }
+    }
+    Ok(positions)
+}
+
+// Quotes a field if it would otherwise be read back differently.
+fn quote_field(text : &str, delimiter : char, comment_prefix : Option<&str>) -> String {
+    let needs_quotes = text.is_empty() || text.contains(delimiter) || text.contains('"')
+                       || text.trim() != text || comment_prefix.is_some_and(|prefix| text.starts_with(prefix));
+    if needs_quotes {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        String::from(text)
+    }
+}
@@ -0,0 +1,517 @@
+// GML (Graph Modelling Language) input and output.
+//
+//     graph [
+//       directed 1
+//       node [ id 1 label "a" ]
+//       node [ id 2 label "b" weight 0.5 ]
+//       edge [ source 1 target 2 ]
+//     ]
+//
+// Nodes are labelled by their label, or by their id if they have none. Other
+// integer, real and string values on nodes and edges become attributes; an
+// attribute given as both integers and reals is read as floats, and one given
+// as strings and numbers as strings. Nested lists, such as graphics, are
+// ignored, as is everything outside the first graph.
+//
+// When writing, nodes are given ids from 0, booleans are written as 0 or 1, and
+// attributes whose names aren't GML keys, or clash with id, label, source and
+// target, are left out, as are infinite and NaN reals.
+
+use super::super::{EdgeIndex, Graph, GraphConfig, ParallelEdgePolicy, SelfLoopPolicy};
+use super::super::attributes::{AttributeType, AttributeValue};
+use super::parser::Location;
+use super::{unconnected_with_config, write_atomically, Diagnostic, ParseError, ParseMode, Section, Severity};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs::File;
+use std::hash::Hash;
+use std::io;
+use std::io::BufReader;
+use std::io::prelude::*;
+
+const SECTION : Section = Section::Other("GML");
+
+pub fn from_gml_file(filename : &str) -> Result<Graph<String>, ParseError> {
+    from_gml_reader(BufReader::new(File::open(filename)?))
+}
+
+pub fn from_gml_str(text : &str) -> Result<Graph<String>, ParseError> {
+    from_gml_reader(text.as_bytes())
+}
+
+pub fn from_gml_reader<R : Read>(reader : R) -> Result<Graph<String>, ParseError> {
+    from_gml_reader_with_mode(reader, ParseMode::Strict)
+}
+
+// In lenient mode every problem with the graph is reported, but badly formed
+// GML still stops at the first problem.
+pub fn from_gml_reader_with_mode<R : Read>(mut reader : R, mode : ParseMode) -> Result<Graph<String>, ParseError> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let pairs = parse(&text).map_err(|d| ParseError::Invalid(vec![d]))?;
+
+    let mut diagnostics = Vec::new();
+    let graph = build_graph(pairs, &mut diagnostics);
+    if diagnostics.is_empty() {
+        return Ok(graph.unwrap())
+    }
+    if mode == ParseMode::Strict {
+        diagnostics.truncate(1);
+    }
+    Err(ParseError::Invalid(diagnostics))
+}
+
+enum Value {
+    Int  (i64),
+    Real (f64),
+    Str  (String),
+    List (Vec<Pair>),
+}
+
+struct Pair {
+    key      : String,
+    value    : Value,
+    location : Location,
+}
+
+impl Value {
+    fn describe(&self) -> String {
+        match *self {
+            Value::Int(i)      => format!("{}", i),
+            Value::Real(x)     => format!("{}", x),
+            Value::Str(ref s)  => format!("\"{}\"", s),
+            Value::List(_)     => String::from("a list"),
+        }
+    }
+
+    // Scalars as attribute values; lists have none.
+    fn attribute_value(&self) -> Option<AttributeValue> {
+        match *self {
+            Value::Int(i)      => Some(AttributeValue::Int(i)),
+            Value::Real(x)     => Some(AttributeValue::Float(x)),
+            Value::Str(ref s)  => Some(AttributeValue::Str(s.clone())),
+            Value::List(_)     => None,
+        }
+    }
+}
+
+fn error(location : Location, expected : &str, found : String) -> Diagnostic {
+    Diagnostic { line : location.line, column : location.column, section : SECTION,
+                 expected : String::from(expected), found, severity : Severity::Error }
+}
+
+// Lists can't be nested any deeper than this, so that a hostile file can't
+// overflow the stack when the nested values are walked or dropped.
+const MAX_DEPTH : usize = 256;
+
+// The pairs of an open list, and the key of the list, which the top level has none of.
+type OpenList = (Vec<Pair>, Option<(String, Location)>);
+
+// The key-value pairs of the whole file.
+fn parse(text : &str) -> Result<Vec<Pair>, Diagnostic> {
+    let mut lexer = Lexer { chars : text.chars().collect(), position : 0, line : 1, column : 1 };
+    let mut stack : Vec<OpenList> = vec![(Vec::new(), None)];
+    loop {
+        lexer.skip_whitespace_and_comments();
+        let location = lexer.location();
+        let c = match lexer.peek() {
+            None => break,
+            Some(c) => c,
+        };
+        if c == ']' {
+            lexer.advance();
+            if stack.len() == 1 {
+                return Err(error(location, "a key", String::from("']'")))
+            }
+            let (pairs, key) = stack.pop().unwrap();
+            let (key, key_location) = key.unwrap();
+            stack.last_mut().unwrap().0.push(Pair { key, value : Value::List(pairs), location : key_location });
+            continue
+        }
+        let key = lexer.read_key()?;
+        lexer.skip_whitespace_and_comments();
+        match lexer.peek() {
+            Some('[') => {
+                // The top level is on the stack too.
+                if stack.len() > MAX_DEPTH {
+                    return Err(error(lexer.location(), &format!("lists nested no more than {} deep", MAX_DEPTH), String::from("'['")))
+                }
+                lexer.advance();
+                stack.push((Vec::new(), Some((key, location))));
+            },
+            _ => {
+                let value = lexer.read_value()?;
+                stack.last_mut().unwrap().0.push(Pair { key, value, location });
+            },
+        }
+    }
+    if stack.len() > 1 {
+        let (_, key) = stack.pop().unwrap();
+        let (key, location) = key.unwrap();
+        return Err(error(location, &format!("a closing ']' for {}", key), String::from("end of file")))
+    }
+    Ok(stack.pop().unwrap().0)
+}
+
+struct Lexer {
+    chars    : Vec<char>,
+    position : usize,
+    line     : usize,
+    column   : usize,
+}
+
+impl Lexer {
+
+    fn location(&self) -> Location {
+        Location { line : self.line, column : self.column }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).cloned()
+    }
+
+    fn advance(&mut self) {
+        if let Some(c) = self.peek() {
+            if c == '\n' { self.line += 1; self.column = 1 } else { self.column += 1 }
+            self.position += 1;
+        }
+    }
+
+    fn found(&self) -> String {
+        match self.peek() {
+            Some(c) => format!("'{}'", c),
+            None => String::from("end of file"),
+        }
+    }
+
+    // Comments run from a '#' at the start of a line to the end of the line.
+    fn skip_whitespace_and_comments(&mut self) {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => self.advance(),
+                Some('#') if self.column == 1 => {
+                    while self.peek().is_some_and(|c| c != '\n') { self.advance() }
+                },
+                _ => return,
+            }
+        }
+    }
+
+    fn read_word(&mut self) -> String {
+        let mut word = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || c == '[' || c == ']' || c == '"' { break }
+            word.push(c);
+            self.advance();
+        }
+        word
+    }
+
+    fn read_key(&mut self) -> Result<String, Diagnostic> {
+        let location = self.location();
+        if !self.peek().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') {
+            return Err(error(location, "a key", self.found()))
+        }
+        let key = self.read_word();
+        if !is_key(&key) {
+            return Err(error(location, "a key made of letters, digits and underscores", format!("\"{}\"", key)))
+        }
+        Ok(key)
+    }
+
+    fn read_value(&mut self) -> Result<Value, Diagnostic> {
+        let location = self.location();
+        if self.peek() == Some('"') {
+            self.advance();
+            let mut text = String::new();
+            loop {
+                match self.peek() {
+                    None => return Err(error(location, "a closing '\"'", String::from("end of file"))),
+                    Some('"') => break,
+                    Some(c) => {
+                        text.push(c);
+                        self.advance();
+                    },
+                }
+            }
+            self.advance();
+            return Ok(Value::Str(unescape(&text)))
+        }
+        let word = self.read_word();
+        if let Ok(i) = word.parse::<i64>() {
+            return Ok(Value::Int(i))
+        }
+        match word.parse::<f64>() {
+            Ok(x) if word.contains(|c : char| c.is_ascii_digit()) => Ok(Value::Real(x)),
+            _ if word.is_empty() => Err(error(location, "a value or '['", self.found())),
+            _ => Err(error(location, "an integer, real, string or '['", format!("\"{}\"", word))),
+        }
+    }
+}
+
+fn is_key(text : &str) -> bool {
+    text.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// Decodes character entities such as &quot; and &#233;. Anything else is left as it is.
+fn unescape(text : &str) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').and_then(|end| {
+            let name = &rest[1..end];
+            let c = match name {
+                "quot" => Some('"'),
+                "amp"  => Some('&'),
+                "lt"   => Some('<'),
+                "gt"   => Some('>'),
+                "apos" => Some('\''),
+                _ if name.starts_with("#x") => u32::from_str_radix(&name[2..], 16).ok().and_then(::std::char::from_u32),
+                _ if name.starts_with('#') => name[1..].parse::<u32>().ok().and_then(::std::char::from_u32),
+                _ => None,
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                result.push(c);
+                rest = &rest[(end + 1)..];
+            },
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            },
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+fn escape(text : &str) -> String {
+    text.replace('&', "&amp;").replace('"', "&quot;")
+}
+
+// Attribute values by name, in the order the names were first seen.
+type AttributeValues<K> = Vec<(String, Vec<(K, AttributeValue)>)>;
+
+fn add_value<K>(values : &mut AttributeValues<K>, name : &str, key : K, value : AttributeValue) {
+    match values.iter_mut().find(|(n, _)| n == name) {
+        Some((_, list)) => list.push((key, value)),
+        None => values.push((String::from(name), vec![(key, value)])),
+    }
+}
+
+// Checks the pairs and builds the graph from the first graph list, adding any
+// problems to diagnostics.
+fn build_graph(pairs : Vec<Pair>, diagnostics : &mut Vec<Diagnostic>) -> Option<Graph<String>> {
+    let graph_pairs = match pairs.into_iter().find(|p| p.key == "graph") {
+        Some(Pair { value : Value::List(pairs), .. }) => pairs,
+        Some(p) => {
+            diagnostics.push(error(p.location, "a list for the graph", p.value.describe()));
+            return None
+        },
+        None => {
+            diagnostics.push(error(Location { line : 1, column : 1 }, "a graph [ ... ] list", String::from("none")));
+            return None
+        },
+    };
+
+    let mut directed = false;
+    let mut labels : Vec<String> = Vec::new();
+    let mut ids : HashMap<i64, usize> = HashMap::new();
+    let mut label_lines : HashMap<String, usize> = HashMap::new();
+    let mut node_values : AttributeValues<usize> = Vec::new();
+    let mut edges = Vec::new();
+    let mut edge_values : AttributeValues<EdgeIndex> = Vec::new();
+
+    for pair in graph_pairs {
+        match (pair.key.as_str(), pair.value) {
+            ("directed", Value::Int(d)) if d == 0 || d == 1 => directed = d == 1,
+            ("directed", value) => diagnostics.push(error(pair.location, "directed 0 or 1", value.describe())),
+            ("node", Value::List(fields)) => {
+                let index = labels.len();
+                let mut id = None;
+                let mut label = None;
+                let mut values = Vec::new();
+                for field in fields {
+                    match (field.key.as_str(), field.value) {
+                        ("id", Value::Int(i)) => id = Some((i, field.location)),
+                        ("id", value) => diagnostics.push(error(field.location, "an integer id", value.describe())),
+                        ("label", Value::Str(s)) => label = Some(s),
+                        ("label", value) => label = value.attribute_value().map(|v| v.to_string()),
+                        (key, value) => if let Some(v) = value.attribute_value() { values.push((String::from(key), v)) },
+                    }
+                }
+                let id = match id {
+                    Some((id, location)) => match ids.get(&id) {
+                        Some(_) => {
+                            diagnostics.push(error(location, "unique node ids", format!("a second node with id {}", id)));
+                            continue
+                        },
+                        None => id,
+                    },
+                    None => {
+                        diagnostics.push(error(pair.location, "an id for the node", String::from("none")));
+                        continue
+                    },
+                };
+                let label = label.unwrap_or_else(|| id.to_string());
+                if let Some(line) = label_lines.get(&label) {
+                    diagnostics.push(error(pair.location, "unique node labels", format!("a repeat of the label on line {}", line)));
+                    continue
+                }
+                label_lines.insert(label.clone(), pair.location.line);
+                ids.insert(id, index);
+                labels.push(label);
+                for (name, value) in values {
+                    add_value(&mut node_values, &name, index, value);
+                }
+            },
+            ("edge", Value::List(fields)) => {
+                let mut endpoints = [None, None];
+                let mut values = Vec::new();
+                for field in fields {
+                    match (field.key.as_str(), field.value) {
+                        ("source", Value::Int(i)) => endpoints[0] = Some((i, field.location)),
+                        ("target", Value::Int(i)) => endpoints[1] = Some((i, field.location)),
+                        ("source", value) | ("target", value) =>
+                            diagnostics.push(error(field.location, "an integer node id", value.describe())),
+                        (key, value) => if let Some(v) = value.attribute_value() { values.push((String::from(key), v)) },
+                    }
+                }
+                edges.push((pair.location, endpoints, values));
+            },
+            ("node", value) | ("edge", value) =>
+                diagnostics.push(error(pair.location, &format!("a list for the {}", pair.key), value.describe())),
+            _ => (),
+        }
+    }
+
+    let config = GraphConfig { parallel_edges : ParallelEdgePolicy::Keep, self_loops : SelfLoopPolicy::Allow };
+    let mut g = unconnected_with_config(labels, directed, config);
+    for (location, endpoints, values) in edges {
+        let mut indices = [0; 2];
+        let mut found = true;
+        for (i, (endpoint, which)) in endpoints.iter().zip(["source", "target"].iter()).enumerate() {
+            match *endpoint {
+                Some((id, location)) => match ids.get(&id) {
+                    Some(&index) => indices[i] = index,
+                    None => {
+                        diagnostics.push(error(location, "the id of a node in the graph", format!("{}", id)));
+                        found = false;
+                    },
+                },
+                None => {
+                    diagnostics.push(error(location, &format!("a {} for the edge", which), String::from("none")));
+                    found = false;
+                },
+            }
+        }
+        if !found { continue }
+        let edge = if directed { g.add_directed_edge(indices[0], indices[1]) }
+                   else { g.add_undirected_edge(indices[0], indices[1]) };
+        for (name, value) in values {
+            add_value(&mut edge_values, &name, edge, value);
+        }
+    }
+
+    for (name, values) in node_values {
+        let attribute_type = common_type(&values);
+        g.node_attributes.declare(&name, attribute_type, None).unwrap();
+        for (index, value) in values {
+            g.node_attributes.set(index, &name, convert(value, attribute_type)).unwrap();
+        }
+    }
+    for (name, values) in edge_values {
+        let attribute_type = common_type(&values);
+        g.edge_attributes.declare(&name, attribute_type, None).unwrap();
+        for (index, value) in values {
+            g.edge_attributes.set(index, &name, convert(value, attribute_type)).unwrap();
+        }
+    }
+    Some(g)
+}
+
+// The narrowest of int, float and string that holds all the values.
+fn common_type<K>(values : &[(K, AttributeValue)]) -> AttributeType {
+    let types : Vec<AttributeType> = values.iter().map(|(_, v)| v.attribute_type()).collect();
+    if types.contains(&AttributeType::Str) {
+        AttributeType::Str
+    } else if types.contains(&AttributeType::Float) {
+        AttributeType::Float
+    } else {
+        AttributeType::Int
+    }
+}
+
+fn convert(value : AttributeValue, attribute_type : AttributeType) -> AttributeValue {
+    match attribute_type {
+        AttributeType::Float => AttributeValue::Float(value.as_float().unwrap()),
+        AttributeType::Str   => AttributeValue::Str(value.to_string()),
+        _ => value,
+    }
+}
+
+pub fn make_gml_string<T>(graph : &Graph<T>) -> String
+    where T : Clone + Eq + Hash + Display
+{
+    let mut gml = Vec::new();
+    write_gml(graph, &mut gml).unwrap();  // Writing to a Vec can't fail
+    String::from_utf8(gml).unwrap()
+}
+
+pub fn to_gml_file<T>(graph : &Graph<T>, filename : &str) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display
+{
+    write_atomically(filename, |writer| write_gml(graph, writer))
+}
+
+pub fn write_gml<T, W>(graph : &Graph<T>, mut writer : W) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display, W : Write
+{
+    writeln!(writer, "graph [")?;
+    writeln!(writer, "  directed {}", if graph.is_directed() { 1 } else { 0 })?;
+    for (index, label) in graph.nodes.iter().enumerate() {
+        writeln!(writer, "  node [")?;
+        writeln!(writer, "    id {}", index)?;
+        writeln!(writer, "    label \"{}\"", escape(&label.to_string()))?;
+        for (name, value) in graph.node_attributes.attributes_of(index) {
+            match gml_value(value) {
+                Some(text) if is_key(name) && name != "id" && name != "label" => writeln!(writer, "    {} {}", name, text)?,
+                _ => (),
+            }
+        }
+        writeln!(writer, "  ]")?;
+    }
+    for source in 0..graph.number_of_vertices() {
+        for (&dest, &edge) in graph.adjacency_list[source].iter().zip(graph.adjacency_edges[source].iter()) {
+            if !graph.is_directed() && dest < source { continue }
+            writeln!(writer, "  edge [")?;
+            writeln!(writer, "    source {}", source)?;
+            writeln!(writer, "    target {}", dest)?;
+            for (name, value) in graph.edge_attributes.attributes_of(edge) {
+                match gml_value(value) {
+                    Some(text) if is_key(name) && name != "source" && name != "target" => writeln!(writer, "    {} {}", name, text)?,
+                    _ => (),
+                }
+            }
+            writeln!(writer, "  ]")?;
+        }
+    }
+    writeln!(writer, "]")
+}
+
+// Reals are written with a decimal point or exponent, so they're read back as
+// reals. GML has no infinities or NaN, so there's nothing to write for them.
+fn gml_value(value : &AttributeValue) -> Option<String> {
+    match *value {
+        AttributeValue::Bool(b)    => Some(String::from(if b { "1" } else { "0" })),
+        AttributeValue::Int(i)     => Some(format!("{}", i)),
+        AttributeValue::Float(x) if x.is_finite() => Some(format!("{:?}", x)),
+        AttributeValue::Float(_)   => None,
+        AttributeValue::Str(ref s) => Some(format!("\"{}\"", escape(s))),
+    }
+}
@@ -0,0 +1,512 @@
+// GraphML input and output.
+//
+// Node IDs are the Display form of the node labels, and attributes are declared
+// with <key> elements and given with <data> elements. Booleans, integers, floats
+// and strings map onto the GraphML boolean, int/long, float/double and string
+// types.
+//
+// When reading, only the first top-level <graph> is used, and any nested graphs
+// are flattened into it. An undirected edge in a directed graph becomes a pair
+// of arcs, but a directed edge in an undirected graph is an error. Data with
+// child elements rather than text, or for keys with a yfiles.type or with an
+// attr.name that isn't made of letters, digits and '_', is ignored, as are
+// graph-level data and ports.
+
+use super::super::{Graph, GraphConfig, ParallelEdgePolicy, SelfLoopPolicy};
+use super::super::attributes::{is_attribute_name, AttributeStore, AttributeType, AttributeValue};
+use super::parser::Location;
+use super::xml::{self, Event};
+use super::{unconnected_with_config, write_atomically, Diagnostic, ParseError, ParseMode, Section, Severity};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::fs::File;
+use std::hash::Hash;
+use std::io;
+use std::io::BufReader;
+use std::io::prelude::*;
+use std::str::FromStr;
+
+const SECTION : Section = Section::Other("GraphML");
+
+pub fn from_graphml_file<T>(filename : &str) -> Result<Graph<T>, ParseError>
+    where T : Clone + Eq + Hash + FromStr
+{
+    from_graphml_reader(BufReader::new(File::open(filename)?))
+}
+
+pub fn from_graphml_str<T>(text : &str) -> Result<Graph<T>, ParseError>
+    where T : Clone + Eq + Hash + FromStr
+{
+    from_graphml_reader(text.as_bytes())
+}
+
+pub fn from_graphml_reader<T, R>(reader : R) -> Result<Graph<T>, ParseError>
+    where T : Clone + Eq + Hash + FromStr, R : Read
+{
+    from_graphml_reader_with_mode(reader, ParseMode::Strict)
+}
+
+// In lenient mode every problem with the graph is reported, but a badly formed
+// XML document still stops at the first problem.
+pub fn from_graphml_reader_with_mode<T, R>(mut reader : R, mode : ParseMode) -> Result<Graph<T>, ParseError>
+    where T : Clone + Eq + Hash + FromStr, R : Read
+{
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let events = xml::parse(&text, SECTION).map_err(|d| ParseError::Invalid(vec![d]))?;
+
+    let document = read_document(events)?;
+    let mut diagnostics = Vec::new();
+    let graph = build_graph(document, &mut diagnostics);
+    if diagnostics.is_empty() {
+        return Ok(graph)
+    }
+    diagnostics.sort_by_key(|d| (d.line, d.column));
+    if mode == ParseMode::Strict {
+        diagnostics.truncate(1);
+    }
+    Err(ParseError::Invalid(diagnostics))
+}
+
+// Which elements a key applies to.
+#[derive(PartialEq, Clone, Copy)]
+enum Domain {
+    Node,
+    Edge,
+    All,
+    Other,      // graph, port and so on, whose data is ignored
+}
+
+struct Key {
+    domain         : Domain,
+    name           : String,
+    attribute_type : AttributeType,
+    default        : Option<(String, Location)>,
+    location       : Location,
+}
+
+struct Data {
+    key      : String,
+    value    : String,
+    location : Location,
+}
+
+struct Edge {
+    source   : (String, Location),
+    target   : (String, Location),
+    directed : Option<(bool, Location)>,
+    data     : Vec<Data>,
+}
+
+// Everything read from the document, before it's checked and turned into a graph.
+struct Document {
+    keys     : Vec<(String, Key)>,
+    directed : bool,
+    nodes    : Vec<(String, Location, Vec<Data>)>,
+    edges    : Vec<Edge>,
+}
+
+fn error(location : Location, expected : &str, found : String) -> Diagnostic {
+    Diagnostic { line : location.line, column : location.column, section : SECTION,
+                 expected : String::from(expected), found, severity : Severity::Error }
+}
+
+// The element name without any namespace prefix.
+fn local_name(name : &str) -> &str {
+    name.rsplit(':').next().unwrap()
+}
+
+fn attribute<'a>(attributes : &'a [xml::Attribute], name : &str) -> Option<&'a xml::Attribute> {
+    attributes.iter().find(|a| local_name(&a.name) == name)
+}
+
+// Picks out the parts of the document that matter, reporting structural
+// problems such as a missing attribute. These stop the document being read.
+fn read_document(events : Vec<(Event, Location)>) -> Result<Document, ParseError> {
+    let invalid = |d : Diagnostic| ParseError::Invalid(vec![d]);
+    let mut document = Document { keys : Vec::new(), directed : true, nodes : Vec::new(), edges : Vec::new() };
+
+    // The local names of the open elements.
+    let mut open : Vec<String> = Vec::new();
+    let mut seen_graph = false;
+    let mut in_graph = false;         // Within the first top-level graph
+    let mut key : Option<Key> = None;
+    let mut key_id = String::new();
+    let mut default_text : Option<(String, Location)> = None;
+    // The data being read, and whether it has child elements.
+    let mut data : Option<(Data, bool)> = None;
+    // What the data belongs to: nodes and edges can be nested, via nested graphs.
+    let mut owners : Vec<Option<Edge>> = Vec::new();
+    let mut owner_nodes : Vec<(String, Location, Vec<Data>)> = Vec::new();
+
+    for (event, location) in events {
+        match event {
+            Event::Start { name, attributes } => {
+                let name = String::from(local_name(&name));
+                if open.is_empty() && name != "graphml" {
+                    return Err(invalid(error(location, "a <graphml> root element", format!("<{}>", name))))
+                }
+                if let Some((_, ref mut has_children)) = data {
+                    *has_children = true;
+                }
+                let parent = open.last().map(|s| s.as_str()).unwrap_or("");
+                match name.as_str() {
+                    "key" if parent == "graphml" => {
+                        let id = attribute(&attributes, "id").ok_or_else(|| invalid(error(location, "an id for the key", String::from("none"))))?;
+                        let domain = match attribute(&attributes, "for").map(|a| a.value.as_str()) {
+                            // Graphics and the like, such as yEd's, rather than attributes
+                            _ if attribute(&attributes, "yfiles.type").is_some() => Domain::Other,
+                            Some("node") => Domain::Node,
+                            Some("edge") => Domain::Edge,
+                            Some("all") | None => Domain::All,
+                            Some(_) => Domain::Other,
+                        };
+                        let attribute_type = match attribute(&attributes, "attr.type") {
+                            None => AttributeType::Str,
+                            Some(a) => match a.value.as_str() {
+                                "boolean"         => AttributeType::Bool,
+                                "int" | "long"    => AttributeType::Int,
+                                "float" | "double" => AttributeType::Float,
+                                "string"          => AttributeType::Str,
+                                _ => return Err(invalid(error(a.location, "boolean, int, long, float, double or string", format!("\"{}\"", a.value)))),
+                            },
+                        };
+                        key_id = id.value.clone();
+                        let name = attribute(&attributes, "attr.name").map(|a| a.value.clone()).unwrap_or_else(|| key_id.clone());
+                        // A name that can't be an attribute name is ignored like graphics are.
+                        let domain = if is_attribute_name(&name) { domain } else { Domain::Other };
+                        key = Some(Key { domain, name, attribute_type, default : None, location });
+                    },
+                    "default" if parent == "key" => {
+                        default_text = Some((String::new(), location));
+                    },
+                    // Any later top-level graphs are skipped
+                    "graph" if parent == "graphml" && !seen_graph => {
+                        seen_graph = true;
+                        in_graph = true;
+                        document.directed = match attribute(&attributes, "edgedefault") {
+                            None => true,
+                            Some(a) if a.value == "directed" => true,
+                            Some(a) if a.value == "undirected" => false,
+                            Some(a) => return Err(invalid(error(a.location, "directed or undirected", format!("\"{}\"", a.value)))),
+                        };
+                    },
+                    "node" if in_graph => {
+                        let id = attribute(&attributes, "id").ok_or_else(|| invalid(error(location, "an id for the node", String::from("none"))))?;
+                        owner_nodes.push((id.value.clone(), id.location, Vec::new()));
+                        owners.push(None);
+                    },
+                    "edge" if in_graph => {
+                        let endpoint = |which : &str| -> Result<(String, Location), ParseError> {
+                            attribute(&attributes, which).map(|a| (a.value.clone(), a.location))
+                                                         .ok_or_else(|| invalid(error(location, &format!("a {} for the edge", which), String::from("none"))))
+                        };
+                        let directed = match attribute(&attributes, "directed") {
+                            None => None,
+                            Some(a) if a.value == "true" => Some((true, a.location)),
+                            Some(a) if a.value == "false" => Some((false, a.location)),
+                            Some(a) => return Err(invalid(error(a.location, "true or false", format!("\"{}\"", a.value)))),
+                        };
+                        owners.push(Some(Edge { source : endpoint("source")?, target : endpoint("target")?, directed,
+                                                data : Vec::new() }));
+                    },
+                    "hyperedge" if in_graph => {
+                        return Err(invalid(error(location, "edges with two endpoints", String::from("a <hyperedge>"))))
+                    },
+                    "data" if in_graph && (parent == "node" || parent == "edge") => {
+                        let key = attribute(&attributes, "key").ok_or_else(|| invalid(error(location, "a key for the data", String::from("none"))))?;
+                        data = Some((Data { key : key.value.clone(), value : String::new(), location : key.location }, false));
+                    },
+                    _ => (),
+                }
+                open.push(name);
+            },
+            Event::Text(text) => {
+                if let Some((ref mut d, false)) = data {
+                    d.value.push_str(&text);
+                } else if let Some((ref mut default, _)) = default_text {
+                    default.push_str(&text);
+                }
+            },
+            Event::End => {
+                let name = open.pop().unwrap();
+                let parent = open.last().map(|s| s.as_str()).unwrap_or("");
+                match name.as_str() {
+                    "key" if parent == "graphml" => {
+                        if let Some(k) = key.take() {
+                            document.keys.push((key_id.clone(), k));
+                        }
+                    },
+                    "default" if parent == "key" => {
+                        if let Some(ref mut k) = key {
+                            k.default = default_text.take();
+                        }
+                    },
+                    "graph" if parent == "graphml" => in_graph = false,
+                    "node" if in_graph => {
+                        owners.pop();
+                        document.nodes.push(owner_nodes.pop().unwrap());
+                    },
+                    "edge" if in_graph => {
+                        document.edges.push(owners.pop().unwrap().unwrap());
+                    },
+                    "data" if data.is_some() && (parent == "node" || parent == "edge") => {
+                        let (d, has_children) = data.take().unwrap();
+                        if !has_children {
+                            match owners.last_mut() {
+                                Some(&mut Some(ref mut edge)) => edge.data.push(d),
+                                Some(&mut None) => owner_nodes.last_mut().unwrap().2.push(d),
+                                None => (),
+                            }
+                        }
+                    },
+                    _ => (),
+                }
+            },
+        }
+    }
+    Ok(document)
+}
+
+// Checks the document and builds the graph, adding any problems to diagnostics.
+fn build_graph<T>(document : Document, diagnostics : &mut Vec<Diagnostic>) -> Graph<T>
+    where T : Clone + Eq + Hash + FromStr
+{
+    let mut labels = Vec::new();
+    let mut indices : HashMap<String, usize> = HashMap::new();
+    let mut first_ids : HashMap<&str, Location> = HashMap::new();
+    let mut first_seen : HashMap<&T, Location> = HashMap::new();
+    let mut parsed_labels = Vec::new();
+    for &(ref id, location, _) in &document.nodes {
+        if let Some(first) = first_ids.get(id.as_str()) {
+            diagnostics.push(error(location, "unique node IDs", format!("a repeat of the node on line {}", first.line)));
+            continue
+        }
+        first_ids.insert(id, location);
+        match id.parse::<T>() {
+            Ok(label) => parsed_labels.push((label, location)),
+            Err(_) => {
+                diagnostics.push(error(location, "a valid node label", format!("\"{}\"", id)));
+                continue
+            },
+        }
+        indices.insert(id.clone(), labels.len());
+        labels.push(id.clone());
+    }
+    for &(ref label, location) in &parsed_labels {
+        // Different IDs can still give the same label, such as 1 and 01 for integers.
+        if let Some(first) = first_seen.get(label) {
+            diagnostics.push(error(location, "unique node labels", format!("a repeat of the label on line {}", first.line)));
+        } else {
+            first_seen.insert(label, location);
+        }
+    }
+
+    let config = GraphConfig { parallel_edges : ParallelEdgePolicy::Keep, self_loops : SelfLoopPolicy::Allow };
+    let nodes = parsed_labels.iter().map(|(label, _)| label.clone()).collect();
+    let mut g = unconnected_with_config(nodes, document.directed, config);
+
+    // Declare the attributes, with their defaults. Keys whose names are already
+    // declared with another type have been reported, and their data is skipped.
+    let mut keys : HashMap<&str, &Key> = HashMap::new();
+    let mut clashing : HashSet<&str> = HashSet::new();
+    for (id, key) in &document.keys {
+        if keys.contains_key(id.as_str()) {
+            diagnostics.push(error(key.location, "unique key IDs", format!("a second key {}", id)));
+            continue
+        }
+        keys.insert(id, key);
+        let default = match key.default {
+            None => None,
+            Some((ref text, location)) => match parse_value(text, key.attribute_type) {
+                Some(value) => Some(value),
+                None => {
+                    diagnostics.push(error(location, &format!("a default of type {}", key.attribute_type), format!("\"{}\"", text)));
+                    continue
+                },
+            },
+        };
+        if (key.domain == Domain::Node || key.domain == Domain::All) && !declare(&mut g.node_attributes, key, default.clone(), diagnostics) {
+            clashing.insert(id);
+        }
+        if (key.domain == Domain::Edge || key.domain == Domain::All) && !declare(&mut g.edge_attributes, key, default, diagnostics) {
+            clashing.insert(id);
+        }
+    }
+
+    for (id, _, data) in document.nodes {
+        if let Some(&index) = indices.get(&id) {
+            for d in data.iter().filter(|d| !clashing.contains(d.key.as_str())) {
+                if let Some(value) = data_value(&keys, d, Domain::Node, diagnostics) {
+                    g.node_attributes.set(index, &keys[d.key.as_str()].name, value).unwrap();
+                }
+            }
+        }
+    }
+
+    for edge in document.edges {
+        let mut endpoints = [0; 2];
+        let mut found = true;
+        for (i, endpoint) in [&edge.source, &edge.target].iter().enumerate() {
+            let (id, location) = (&endpoint.0, endpoint.1);
+            match indices.get(id) {
+                Some(&index) => endpoints[i] = index,
+                None => {
+                    diagnostics.push(error(location, "the ID of a node in the graph", format!("\"{}\"", id)));
+                    found = false;
+                },
+            }
+        }
+        let directed = match edge.directed {
+            Some((true, location)) if !document.directed => {
+                diagnostics.push(error(location, "only undirected edges, since edgedefault is undirected", String::from("a directed edge")));
+                continue
+            },
+            Some((directed, _)) => directed,
+            None => document.directed,
+        };
+        if !found { continue }
+        let index = if directed { g.add_directed_edge(endpoints[0], endpoints[1]) }
+                    else { g.add_undirected_edge(endpoints[0], endpoints[1]) };
+        for d in edge.data.iter().filter(|d| !clashing.contains(d.key.as_str())) {
+            if let Some(value) = data_value(&keys, d, Domain::Edge, diagnostics) {
+                g.edge_attributes.set(index, &keys[d.key.as_str()].name, value).unwrap();
+            }
+        }
+    }
+
+    g
+}
+
+// Declares the key's attribute, returning false if it's already declared with
+// another type.
+fn declare<K>(store : &mut AttributeStore<K>, key : &Key, default : Option<AttributeValue>, diagnostics : &mut Vec<Diagnostic>) -> bool
+    where K : Copy + Eq + Hash + Ord
+{
+    if let Some(existing) = store.attribute_type(&key.name) {
+        if existing != key.attribute_type {
+            diagnostics.push(error(key.location, &format!("{} to have a single type", key.name),
+                                   format!("types {} and {}", existing, key.attribute_type)));
+            return false
+        }
+        return true
+    }
+    store.declare(&key.name, key.attribute_type, default).unwrap();
+    true
+}
+
+// The value of some data, if its key exists, applies to this kind of element and
+// the value has the right type. Data for ignored keys is skipped silently.
+fn data_value(keys : &HashMap<&str, &Key>, data : &Data, domain : Domain, diagnostics : &mut Vec<Diagnostic>) -> Option<AttributeValue> {
+    let kind = if domain == Domain::Node { "node" } else { "edge" };
+    let key = match keys.get(data.key.as_str()) {
+        Some(key) if key.domain == domain || key.domain == Domain::All => key,
+        Some(key) if key.domain == Domain::Other => return None,
+        Some(_) => {
+            diagnostics.push(error(data.location, &format!("a key for {} data", kind), format!("\"{}\"", data.key)));
+            return None
+        },
+        None => {
+            diagnostics.push(error(data.location, "a declared key", format!("\"{}\"", data.key)));
+            return None
+        },
+    };
+    let value = parse_value(&data.value, key.attribute_type);
+    if value.is_none() {
+        diagnostics.push(error(data.location, &format!("a value of type {} for {}", key.attribute_type, key.name), format!("\"{}\"", data.value)));
+    }
+    value
+}
+
+// Surrounding whitespace is only kept for strings, and booleans can be in any case.
+fn parse_value(text : &str, attribute_type : AttributeType) -> Option<AttributeValue> {
+    match attribute_type {
+        AttributeType::Str  => AttributeValue::parse(text, attribute_type),
+        AttributeType::Bool => AttributeValue::parse(&text.trim().to_lowercase(), attribute_type),
+        _ => AttributeValue::parse(text.trim(), attribute_type),
+    }
+}
+
+pub fn make_graphml_string<T>(graph : &Graph<T>) -> String
+    where T : Clone + Eq + Hash + Display
+{
+    let mut graphml = Vec::new();
+    write_graphml(graph, &mut graphml).unwrap();  // Writing to a Vec can't fail
+    String::from_utf8(graphml).unwrap()
+}
+
+pub fn to_graphml_file<T>(graph : &Graph<T>, filename : &str) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display
+{
+    write_atomically(filename, |writer| write_graphml(graph, writer))
+}
+
+pub fn write_graphml<T, W>(graph : &Graph<T>, mut writer : W) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display, W : Write
+{
+    writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(writer, "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">")?;
+
+    // Keys are numbered d0, d1, ... with the node attributes first.
+    let node_keys = write_keys(&mut writer, "node", &graph.node_attributes, 0)?;
+    let edge_keys = write_keys(&mut writer, "edge", &graph.edge_attributes, node_keys.len())?;
+
+    let ids : Vec<String> = graph.nodes.iter().map(|node| xml::escape(&node.to_string())).collect();
+    writeln!(writer, "  <graph id=\"G\" edgedefault=\"{}\">", if graph.is_directed() { "directed" } else { "undirected" })?;
+    for (index, id) in ids.iter().enumerate() {
+        let data : Vec<(&str, &AttributeValue)> = graph.node_attributes.names()
+                                                       .filter_map(|name| graph.node_attributes.get_explicit(index, name).map(|v| (name, v)))
+                                                       .collect();
+        write_element(&mut writer, &format!("node id=\"{}\"", id), "node", &data, &node_keys)?;
+    }
+    for source in 0..graph.number_of_vertices() {
+        for (&dest, &edge) in graph.adjacency_list[source].iter().zip(graph.adjacency_edges[source].iter()) {
+            // Each undirected edge is only written once, from its lower endpoint.
+            if !graph.is_directed() && dest < source { continue }
+            let data : Vec<(&str, &AttributeValue)> = graph.edge_attributes.names()
+                                                           .filter_map(|name| graph.edge_attributes.get_explicit(edge, name).map(|v| (name, v)))
+                                                           .collect();
+            write_element(&mut writer, &format!("edge source=\"{}\" target=\"{}\"", ids[source], ids[dest]), "edge", &data, &edge_keys)?;
+        }
+    }
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</graphml>")
+}
+
+// Writes a <key> for each attribute, returning the key ID of each attribute name.
+fn write_keys<K, W>(writer : &mut W, domain : &str, store : &AttributeStore<K>, first : usize) -> io::Result<HashMap<String, String>>
+    where K : Copy + Eq + Hash + Ord, W : Write
+{
+    let mut ids = HashMap::new();
+    for (i, name) in store.names().enumerate() {
+        let id = format!("d{}", first + i);
+        let graphml_type = match store.attribute_type(name).unwrap() {
+            AttributeType::Bool  => "boolean",
+            AttributeType::Int   => "long",
+            AttributeType::Float => "double",
+            AttributeType::Str   => "string",
+        };
+        let tag = format!("key id=\"{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"{}\"", id, domain, xml::escape(name), graphml_type);
+        match store.default_value(name) {
+            Some(default) => {
+                writeln!(writer, "  <{}>", tag)?;
+                writeln!(writer, "    <default>{}</default>", xml::escape(&default.to_string()))?;
+                writeln!(writer, "  </key>")?;
+            },
+            None => writeln!(writer, "  <{}/>", tag)?,
+        }
+        ids.insert(String::from(name), id);
+    }
+    Ok(ids)
+}
+
+fn write_element<W : Write>(writer : &mut W, tag : &str, name : &str, data : &[(&str, &AttributeValue)], keys : &HashMap<String, String>) -> io::Result<()> {
+    if data.is_empty() {
+        return writeln!(writer, "    <{}/>", tag)
+    }
+    writeln!(writer, "    <{}>", tag)?;
+    for &(attribute, value) in data {
+        writeln!(writer, "      <data key=\"{}\">{}</data>", keys[attribute], xml::escape(&value.to_string()))?;
+    }
+    writeln!(writer, "    </{}>", name)
+}
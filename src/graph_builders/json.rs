@@ -0,0 +1,325 @@
+// A small JSON reader, for the node-link format. Numbers are kept as they were
+// written, so that integers and reals can be told apart, and objects keep their
+// keys in order.
+//
+// Problems are reported as a Diagnostic in the given section; reading stops at
+// the first one.
+
+use super::parser::{Diagnostic, Location, Section, Severity};
+
+pub enum Value {
+    Null,
+    Bool   (bool),
+    Number (String),
+    Str    (String),
+    Array  (Vec<Located>),
+    Object (Vec<(String, Located)>),
+}
+
+pub struct Located {
+    pub value    : Value,
+    pub location : Location,
+}
+
+impl Value {
+    // How the value is shown in a diagnostic.
+    pub fn describe(&self) -> String {
+        match *self {
+            Value::Null          => String::from("null"),
+            Value::Bool(b)       => format!("{}", b),
+            Value::Number(ref n) => n.clone(),
+            Value::Str(ref s)    => quote(s),
+            Value::Array(_)      => String::from("an array"),
+            Value::Object(_)     => String::from("an object"),
+        }
+    }
+}
+
+// Arrays and objects can't be nested any deeper than this, so that a hostile
+// file can't overflow the stack.
+const MAX_DEPTH : usize = 256;
+
+pub fn parse(text : &str, section : Section) -> Result<Located, Diagnostic> {
+    let mut reader = Reader { chars : text.chars().collect(), position : 0, line : 1, column : 1, section };
+    reader.skip_whitespace();
+    let value = reader.read_value(0)?;
+    reader.skip_whitespace();
+    if reader.peek().is_some() {
+        let location = reader.location();
+        return reader.error(location, "end of file", reader.found())
+    }
+    Ok(value)
+}
+
+struct Reader {
+    chars    : Vec<char>,
+    position : usize,
+    line     : usize,
+    column   : usize,
+    section  : Section,
+}
+
+impl Reader {
+
+    fn location(&self) -> Location {
+        Location { line : self.line, column : self.column }
+    }
+
+    fn error<X>(&self, location : Location, expected : &str, found : String) -> Result<X, Diagnostic> {
+        Err(Diagnostic { line : location.line, column : location.column, section : self.section,
+                         expected : String::from(expected), found, severity : Severity::Error })
+    }
+
+    fn found(&self) -> String {
+        match self.peek() {
+            Some(c) => format!("'{}'", c),
+            None => String::from("end of file"),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).cloned()
+    }
+
+    fn advance(&mut self) {
+        if let Some(c) = self.peek() {
+            if c == '\n' { self.line += 1; self.column = 1 } else { self.column += 1 }
+            self.position += 1;
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c == ' ' || c == '\t' || c == '\n' || c == '\r') { self.advance() }
+    }
+
+    // Moves past c, which must come next.
+    fn expect(&mut self, c : char, expected : &str) -> Result<(), Diagnostic> {
+        if self.peek() != Some(c) {
+            let location = self.location();
+            return self.error(location, expected, self.found())
+        }
+        self.advance();
+        Ok(())
+    }
+
+    fn read_value(&mut self, depth : usize) -> Result<Located, Diagnostic> {
+        let location = self.location();
+        let value = match self.peek() {
+            Some('{') | Some('[') if depth == MAX_DEPTH =>
+                return self.error(location, &format!("arrays and objects nested no more than {} deep", MAX_DEPTH), self.found()),
+            Some('{') => self.read_object(depth)?,
+            Some('[') => self.read_array(depth)?,
+            Some('"') => Value::Str(self.read_string()?),
+            Some(c) if c == '-' || c.is_ascii_digit() => Value::Number(self.read_number()?),
+            Some(c) if c.is_ascii_alphabetic() => {
+                let mut word = String::new();
+                while let Some(c) = self.peek().filter(|c| c.is_ascii_alphanumeric()) {
+                    word.push(c);
+                    self.advance();
+                }
+                match word.as_str() {
+                    "true"  => Value::Bool(true),
+                    "false" => Value::Bool(false),
+                    "null"  => Value::Null,
+                    _ => return self.error(location, "a value", format!("\"{}\"", word)),
+                }
+            },
+            _ => return self.error(location, "a value", self.found()),
+        };
+        Ok(Located { value, location })
+    }
+
+    fn read_object(&mut self, depth : usize) -> Result<Value, Diagnostic> {
+        self.advance();
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.advance();
+            return Ok(Value::Object(members))
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('"') {
+                let location = self.location();
+                return self.error(location, "a key in double quotes", self.found())
+            }
+            let key = self.read_string()?;
+            self.skip_whitespace();
+            self.expect(':', "':' after the key")?;
+            self.skip_whitespace();
+            let value = self.read_value(depth + 1)?;
+            members.push((key, value));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.advance(),
+                Some('}') => {
+                    self.advance();
+                    return Ok(Value::Object(members))
+                },
+                _ => {
+                    let location = self.location();
+                    return self.error(location, "',' or '}'", self.found())
+                },
+            }
+        }
+    }
+
+    fn read_array(&mut self, depth : usize) -> Result<Value, Diagnostic> {
+        self.advance();
+        let mut elements = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.advance();
+            return Ok(Value::Array(elements))
+        }
+        loop {
+            self.skip_whitespace();
+            elements.push(self.read_value(depth + 1)?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.advance(),
+                Some(']') => {
+                    self.advance();
+                    return Ok(Value::Array(elements))
+                },
+                _ => {
+                    let location = self.location();
+                    return self.error(location, "',' or ']'", self.found())
+                },
+            }
+        }
+    }
+
+    fn read_string(&mut self) -> Result<String, Diagnostic> {
+        let start = self.location();
+        self.advance();
+        let mut text = String::new();
+        loop {
+            let location = self.location();
+            match self.peek() {
+                None => return self.error(start, "a closing '\"'", String::from("end of file")),
+                Some('"') => {
+                    self.advance();
+                    return Ok(text)
+                },
+                Some('\\') => {
+                    self.advance();
+                    let c = match self.peek() {
+                        Some('"')  => '"',
+                        Some('\\') => '\\',
+                        Some('/')  => '/',
+                        Some('b')  => '\u{8}',
+                        Some('f')  => '\u{c}',
+                        Some('n')  => '\n',
+                        Some('r')  => '\r',
+                        Some('t')  => '\t',
+                        Some('u')  => {
+                            self.advance();
+                            let mut code = self.read_hex(location)?;
+                            // A surrogate pair, for a character outside the basic multilingual plane.
+                            if (0xd800..0xdc00).contains(&code) && self.peek() == Some('\\') {
+                                let low_location = self.location();
+                                self.advance();
+                                self.expect('u', "\\u and the second half of a surrogate pair")?;
+                                let low = self.read_hex(low_location)?;
+                                if !(0xdc00..0xe000).contains(&low) {
+                                    return self.error(low_location, "the second half of a surrogate pair", format!("\\u{:04x}", low))
+                                }
+                                code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                            }
+                            match ::std::char::from_u32(code) {
+                                Some(c) => text.push(c),
+                                None => return self.error(location, "a character", format!("\\u{:04x}", code)),
+                            }
+                            continue
+                        },
+                        Some(c) => return self.error(location, "an escape such as \\n or \\u00e9", format!("\"\\{}\"", c)),
+                        None => return self.error(location, "an escape such as \\n or \\u00e9", String::from("end of file")),
+                    };
+                    self.advance();
+                    text.push(c);
+                },
+                Some(c) if (c as u32) < 0x20 => return self.error(location, "a control character to be escaped", format!("{:?}", c)),
+                Some(c) => {
+                    text.push(c);
+                    self.advance();
+                },
+            }
+        }
+    }
+
+    // The four hex digits of a \u escape that starts at location.
+    fn read_hex(&mut self, location : Location) -> Result<u32, Diagnostic> {
+        let mut code = 0;
+        for _ in 0..4 {
+            match self.peek().and_then(|c| c.to_digit(16)) {
+                Some(digit) => code = code * 16 + digit,
+                None => return self.error(location, "four hex digits after \\u", self.found()),
+            }
+            self.advance();
+        }
+        Ok(code)
+    }
+
+    // -?(0|[1-9][0-9]*)(.[0-9]+)?([eE][+-]?[0-9]+)?
+    fn read_number(&mut self) -> Result<String, Diagnostic> {
+        let location = self.location();
+        let mut text = String::new();
+        self.take_one(&mut text, "-");
+        let leading_zero = self.peek() == Some('0');
+        let digits = self.take_digits(&mut text);
+        let mut valid = digits > 0 && !(leading_zero && digits > 1);
+        if self.take_one(&mut text, ".") {
+            valid &= self.take_digits(&mut text) > 0;
+        }
+        if self.take_one(&mut text, "eE") {
+            self.take_one(&mut text, "+-");
+            valid &= self.take_digits(&mut text) > 0;
+        }
+        if !valid {
+            // Show the whole of the bad number.
+            while let Some(c) = self.peek().filter(|c| c.is_ascii_alphanumeric() || "+-.".contains(*c)) {
+                text.push(c);
+                self.advance();
+            }
+            return self.error(location, "a number", format!("\"{}\"", text))
+        }
+        Ok(text)
+    }
+
+    // Moves past the next character if it's one of these, adding it to the text.
+    fn take_one(&mut self, text : &mut String, one_of : &str) -> bool {
+        match self.peek().filter(|&c| one_of.contains(c)) {
+            Some(c) => {
+                text.push(c);
+                self.advance();
+                true
+            },
+            None => false,
+        }
+    }
+
+    fn take_digits(&mut self, text : &mut String) -> usize {
+        let mut count = 0;
+        while self.take_one(text, "0123456789") { count += 1 }
+        count
+    }
+}
+
+// The text as a JSON string, in double quotes.
+pub fn quote(text : &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"'  => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
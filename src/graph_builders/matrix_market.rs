@@ -0,0 +1,232 @@
+// Matrix Market coordinate files (.mtx), read as the adjacency matrix of a graph.
+//
+//     %%MatrixMarket matrix coordinate real general
+//     % A comment
+//     3 3 2
+//     1 2 0.5
+//     2 3 1.5
+//
+// An entry in row i and column j is an edge from vertex i-1 to vertex j-1, so
+// the matrix must be square. A general matrix gives a directed graph and a
+// symmetric one an undirected graph, with each edge given once, in the lower
+// triangle. Real and integer values are kept as the edge attribute "weight",
+// as a float or an int; pattern matrices have no values. Complex and
+// skew-symmetric matrices aren't supported. Repeated entries are kept as
+// parallel edges.
+
+use super::super::{Graph, GraphConfig, ParallelEdgePolicy, SelfLoopPolicy};
+use super::super::attributes::{AttributeType, AttributeValue};
+use super::{unconnected_with_config, write_atomically, Diagnostic, ParseError, ParseMode, Section, Severity};
+use std::fs::File;
+use std::hash::Hash;
+use std::io;
+use std::io::BufReader;
+use std::io::prelude::*;
+
+const SECTION : Section = Section::Other("Matrix Market");
+
+pub fn from_matrix_market_file(filename : &str) -> Result<Graph<usize>, ParseError> {
+    from_matrix_market_reader(BufReader::new(File::open(filename)?))
+}
+
+pub fn from_matrix_market_str(text : &str) -> Result<Graph<usize>, ParseError> {
+    from_matrix_market_reader(text.as_bytes())
+}
+
+pub fn from_matrix_market_reader<R : BufRead>(reader : R) -> Result<Graph<usize>, ParseError> {
+    from_matrix_market_reader_with_mode(reader, ParseMode::Strict)
+}
+
+fn diagnostic(line : usize, column : usize, expected : &str, found : String) -> Diagnostic {
+    Diagnostic { line, column, section : SECTION, expected : String::from(expected), found, severity : Severity::Error }
+}
+
+pub fn from_matrix_market_reader_with_mode<R : BufRead>(reader : R, mode : ParseMode) -> Result<Graph<usize>, ParseError> {
+    let mut diagnostics = Vec::new();
+    let mut lines = reader.lines().enumerate();
+
+    // The banner, which has to be right; nothing after it can be read otherwise.
+    let banner = match lines.next() {
+        Some((_, line)) => line?,
+        None => String::new(),
+    };
+    let words : Vec<String> = banner.split_whitespace().map(|w| w.to_lowercase()).collect();
+    let expected_words = ["%%matrixmarket", "matrix", "coordinate"];
+    for (i, expected) in expected_words.iter().enumerate() {
+        if words.get(i).map(|w| w.as_str()) != Some(*expected) {
+            let found = words.get(i).map(|w| format!("\"{}\"", w)).unwrap_or_else(|| String::from("end of line"));
+            let expected = if i == 0 { "a %%MatrixMarket banner" } else if i == 1 { "matrix" } else { "coordinate; array matrices aren't supported" };
+            return Err(ParseError::Invalid(vec![diagnostic(1, 1, expected, found)]))
+        }
+    }
+    let field = match words.get(3).map(|w| w.as_str()) {
+        Some("real")    => Some(AttributeType::Float),
+        Some("integer") => Some(AttributeType::Int),
+        Some("pattern") => None,
+        found => return Err(ParseError::Invalid(vec![diagnostic(1, 1, "real, integer or pattern",
+                                                                found.map(|w| format!("\"{}\"", w)).unwrap_or_else(|| String::from("end of line")))])),
+    };
+    let symmetric = match words.get(4).map(|w| w.as_str()) {
+        Some("general")   => false,
+        Some("symmetric") => true,
+        found => return Err(ParseError::Invalid(vec![diagnostic(1, 1, "general or symmetric",
+                                                                found.map(|w| format!("\"{}\"", w)).unwrap_or_else(|| String::from("end of line")))])),
+    };
+
+    let mut graph : Option<Graph<usize>> = None;
+    let mut size_line = 0;
+    let mut expected_entries = 0;
+    let mut entries = 0;
+    let mut last_line = 1;
+    for (line_index, line) in lines {
+        let line = line?;
+        let line_number = line_index + 1;
+        last_line = line_number;
+        if mode == ParseMode::Strict && !diagnostics.is_empty() { break }
+        if line.starts_with('%') || line.trim().is_empty() { continue }
+
+        let fields : Vec<&str> = line.split_whitespace().collect();
+        let column_of = |index : usize| {
+            match fields.get(index) {
+                Some(field) => line[..(field.as_ptr() as usize - line.as_ptr() as usize)].chars().count() + 1,
+                None => line.chars().count() + 1,
+            }
+        };
+        let found_at = |index : usize| fields.get(index).map(|f| format!("\"{}\"", f)).unwrap_or_else(|| String::from("end of line"));
+
+        let g = match graph {
+            Some(ref mut g) => g,
+            None => {
+                let numbers : Vec<Option<usize>> = (0..3).map(|i| fields.get(i).and_then(|f| f.parse().ok())).collect();
+                if let Some(i) = numbers.iter().position(|n| n.is_none()) {
+                    diagnostics.push(diagnostic(line_number, column_of(i), "the number of rows, columns and entries", found_at(i)));
+                    break
+                }
+                if fields.len() > 3 {
+                    diagnostics.push(diagnostic(line_number, column_of(3), "end of line", found_at(3)));
+                    break
+                }
+                if numbers[0] != numbers[1] {
+                    diagnostics.push(diagnostic(line_number, column_of(1), &format!("{} columns, for a square matrix", numbers[0].unwrap()), found_at(1)));
+                    break
+                }
+                let config = GraphConfig { parallel_edges : ParallelEdgePolicy::Keep, self_loops : SelfLoopPolicy::Allow };
+                let mut g = unconnected_with_config((0..numbers[0].unwrap()).collect(), !symmetric, config);
+                if let Some(field) = field {
+                    g.edge_attributes.declare("weight", field, None).unwrap();
+                }
+                graph = Some(g);
+                size_line = line_number;
+                expected_entries = numbers[2].unwrap();
+                continue
+            },
+        };
+
+        entries += 1;
+        let n = g.number_of_vertices();
+        let mut endpoints = [0; 2];
+        let mut valid = true;
+        for (i, endpoint) in endpoints.iter_mut().enumerate() {
+            match fields.get(i).and_then(|f| f.parse::<usize>().ok()) {
+                Some(v) if v >= 1 && v <= n => *endpoint = v - 1,
+                _ => {
+                    diagnostics.push(diagnostic(line_number, column_of(i), &format!("a {} number from 1 to {}", if i == 0 { "row" } else { "column" }, n), found_at(i)));
+                    valid = false;
+                    break
+                },
+            }
+        }
+        if !valid { continue }
+        let value_count = if field.is_some() { 3 } else { 2 };
+        let weight = match field {
+            Some(attribute_type) => match fields.get(2).and_then(|f| AttributeValue::parse(f, attribute_type)) {
+                Some(value) => Some(value),
+                None => {
+                    diagnostics.push(diagnostic(line_number, column_of(2), &format!("a value of type {}", attribute_type), found_at(2)));
+                    continue
+                },
+            },
+            None => None,
+        };
+        if fields.len() > value_count {
+            diagnostics.push(diagnostic(line_number, column_of(value_count), "end of line", found_at(value_count)));
+            continue
+        }
+        let (row, column) = (endpoints[0], endpoints[1]);
+        let edge = if symmetric {
+            if column > row {
+                diagnostics.push(diagnostic(line_number, column_of(1), "an entry in the lower triangle of a symmetric matrix",
+                                            format!("row {}, column {}", row + 1, column + 1)));
+                continue
+            }
+            g.add_undirected_edge(row, column)
+        } else {
+            g.add_directed_edge(row, column)
+        };
+        if let Some(weight) = weight {
+            g.edge_attributes.set(edge, "weight", weight).unwrap();
+        }
+    }
+
+    match graph {
+        Some(_) if entries != expected_entries && diagnostics.is_empty() =>
+            diagnostics.push(diagnostic(size_line, 1, &format!("{} entries, as given on the size line", expected_entries), format!("{}", entries))),
+        None if diagnostics.is_empty() =>
+            diagnostics.push(diagnostic(last_line + 1, 1, "the number of rows, columns and entries", String::from("end of file"))),
+        _ => (),
+    }
+
+    if diagnostics.is_empty() {
+        return Ok(graph.unwrap())
+    }
+    if mode == ParseMode::Strict {
+        diagnostics.truncate(1);
+    }
+    Err(ParseError::Invalid(diagnostics))
+}
+
+pub fn make_matrix_market_string<T>(graph : &Graph<T>) -> String
+    where T : Clone + Eq + Hash
+{
+    let mut mtx = Vec::new();
+    write_matrix_market(graph, &mut mtx).unwrap();  // Writing to a Vec can't fail
+    String::from_utf8(mtx).unwrap()
+}
+
+pub fn to_matrix_market_file<T>(graph : &Graph<T>, filename : &str) -> io::Result<()>
+    where T : Clone + Eq + Hash
+{
+    write_atomically(filename, |writer| write_matrix_market(graph, writer))
+}
+
+// The field is integer or real if the graph has an int or float "weight"
+// attribute, and pattern otherwise. Edges without a weight are written as 1.
+pub fn write_matrix_market<T, W>(graph : &Graph<T>, mut writer : W) -> io::Result<()>
+    where T : Clone + Eq + Hash, W : Write
+{
+    let field = match graph.edge_attributes.attribute_type("weight") {
+        Some(AttributeType::Int)   => Some("integer"),
+        Some(AttributeType::Float) => Some("real"),
+        _ => None,
+    };
+    let symmetry = if graph.is_directed() { "general" } else { "symmetric" };
+    writeln!(writer, "%%MatrixMarket matrix coordinate {} {}", field.unwrap_or("pattern"), symmetry)?;
+
+    let mut entries = Vec::new();
+    for source in 0..graph.number_of_vertices() {
+        for (&dest, &edge) in graph.adjacency_list[source].iter().zip(graph.adjacency_edges[source].iter()) {
+            if !graph.is_directed() && dest > source { continue }
+            entries.push((source, dest, edge));
+        }
+    }
+    let n = graph.number_of_vertices();
+    writeln!(writer, "{} {} {}", n, n, entries.len())?;
+    for (row, column, edge) in entries {
+        match (field, graph.edge_attributes.get(edge, "weight")) {
+            (None, _) => writeln!(writer, "{} {}", row + 1, column + 1)?,
+            (Some(_), Some(weight)) => writeln!(writer, "{} {} {}", row + 1, column + 1, weight)?,
+            (Some(_), None) => writeln!(writer, "{} {} 1", row + 1, column + 1)?,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,404 @@
+// METIS graph files, as used by METIS, KaHIP and other graph partitioners.
+//
+//     % A triangle with edge weights
+//     3 3 1
+//     2 5 3 1
+//     1 5 3 2
+//     1 1 2 2
+//
+// The header gives the numbers of vertices and edges, and optionally a format
+// and the number of vertex weights. The format's three digits say whether each
+// line starts with a vertex size, then the vertex weights, and whether each
+// neighbour is followed by an edge weight. Line i+1 after the header lists the
+// neighbours of vertex i, numbered from 1, and so becomes adjacency_list[i] in
+// the same order.
+//
+// Sizes are kept as the int node attribute "size", and vertex weights as
+// "weight", or "weight0", "weight1" and so on when there are several. Edge
+// weights are kept as the int edge attribute "weight".
+//
+// METIS graphs are undirected, so each edge has to be listed by both of its
+// endpoints, with the same weight, and self-loops aren't allowed. A directed
+// graph can also be read, with each line giving a vertex's arcs; then the
+// header gives the number of arcs.
+
+use super::super::{Graph, GraphConfig, ParallelEdgePolicy, SelfLoopPolicy};
+use super::super::attributes::{AttributeType, AttributeValue};
+use super::parser::Location;
+use super::{unconnected_with_config, whole_number, write_atomically, Diagnostic, ParseError, ParseMode, Section, Severity};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::hash::Hash;
+use std::io;
+use std::io::BufReader;
+use std::io::prelude::*;
+
+const SECTION : Section = Section::Other("METIS");
+
+pub fn from_metis_file(filename : &str) -> Result<Graph<usize>, ParseError> {
+    from_metis_reader(BufReader::new(File::open(filename)?))
+}
+
+pub fn from_metis_str(text : &str) -> Result<Graph<usize>, ParseError> {
+    from_metis_reader(text.as_bytes())
+}
+
+pub fn from_metis_reader<R : BufRead>(reader : R) -> Result<Graph<usize>, ParseError> {
+    from_metis_reader_with_options(reader, ParseMode::Strict, false)
+}
+
+fn diagnostic(location : Location, expected : &str, found : String) -> Diagnostic {
+    Diagnostic { line : location.line, column : location.column, section : SECTION,
+                 expected : String::from(expected), found, severity : Severity::Error }
+}
+
+// A neighbour in a vertex's line: (neighbour, edge weight, location)
+type Neighbour = (usize, Option<i64>, Location);
+
+pub fn from_metis_reader_with_options<R : BufRead>(reader : R, mode : ParseMode, directed : bool) -> Result<Graph<usize>, ParseError> {
+    let mut diagnostics = Vec::new();
+    // (n, m, has sizes, number of vertex weights, has edge weights, location)
+    let mut header : Option<(usize, usize, bool, usize, bool, Location)> = None;
+    let mut vertex_values : Vec<(Option<i64>, Vec<i64>)> = Vec::new();
+    let mut lists : Vec<Vec<Neighbour>> = Vec::new();
+    let mut last_line = 0;
+
+    for (line_index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = line_index + 1;
+        last_line = line_number;
+        if mode == ParseMode::Strict && !diagnostics.is_empty() { break }
+        if line.starts_with('%') { continue }
+
+        // Each field, with where it starts.
+        let mut fields : Vec<(Location, &str)> = Vec::new();
+        let mut start = None;
+        for (column, (offset, c)) in (1..).zip(line.char_indices().chain(Some((line.len(), ' ')))) {
+            match (c.is_whitespace(), start) {
+                (true, Some((s, from))) => {
+                    fields.push((Location { line : line_number, column : s }, &line[from..offset]));
+                    start = None;
+                },
+                (false, None) => start = Some((column, offset)),
+                _ => (),
+            }
+        }
+        let end_of_line = Location { line : line_number, column : line.chars().count() + 1 };
+        let found_at = |index : usize| match fields.get(index) {
+            Some((location, text)) => (*location, format!("\"{}\"", text)),
+            None => (end_of_line, String::from("end of line")),
+        };
+        let number = |index : usize| fields.get(index).and_then(|f| f.1.parse::<i64>().ok());
+
+        let (n, has_sizes, weights, has_edge_weights) = match header {
+            Some((n, _, has_sizes, weights, has_edge_weights, _)) => (n, has_sizes, weights, has_edge_weights),
+            None => {
+                if fields.is_empty() { continue }
+                let counts : Vec<Option<usize>> = (0..2).map(|i| fields.get(i).and_then(|f| f.1.parse().ok())).collect();
+                if let Some(i) = counts.iter().position(|c| c.is_none()) {
+                    let (location, found) = found_at(i);
+                    diagnostics.push(diagnostic(location, if i == 0 { "the number of vertices" } else { "the number of edges" }, found));
+                    break
+                }
+                let format = match fields.get(2) {
+                    None => Some([false; 3]),
+                    Some((_, text)) if text.len() <= 3 && text.chars().all(|c| c == '0' || c == '1') => {
+                        let digits : Vec<bool> = format!("{:0>3}", text).chars().map(|c| c == '1').collect();
+                        Some([digits[0], digits[1], digits[2]])
+                    },
+                    Some(_) => None,
+                };
+                let format = match format {
+                    Some(format) => format,
+                    None => {
+                        let (location, found) = found_at(2);
+                        diagnostics.push(diagnostic(location, "a format of up to three 0s and 1s, such as 011", found));
+                        break
+                    },
+                };
+                let weights = match fields.get(3) {
+                    None => if format[1] { 1 } else { 0 },
+                    Some((_, text)) => match text.parse::<usize>() {
+                        Ok(weights) if format[1] && weights >= 1 => weights,
+                        _ => {
+                            let (location, found) = found_at(3);
+                            diagnostics.push(diagnostic(location, "the number of vertex weights, for a format with vertex weights", found));
+                            break
+                        },
+                    },
+                };
+                if fields.len() > 4 {
+                    let (location, found) = found_at(4);
+                    diagnostics.push(diagnostic(location, "end of line", found));
+                    break
+                }
+                header = Some((counts[0].unwrap(), counts[1].unwrap(), format[0], weights, format[2], fields[0].0));
+                continue
+            },
+        };
+
+        if lists.len() == n {
+            // Blank lines after the last vertex are just the end of the file.
+            if fields.is_empty() { continue }
+            let (location, found) = found_at(0);
+            diagnostics.push(diagnostic(location, &format!("only {} vertex lines", n), found));
+            break
+        }
+        let vertex = lists.len();
+        let mut position = 0;
+        let mut size = None;
+        if has_sizes {
+            match number(0) {
+                Some(s) => size = Some(s),
+                None => {
+                    let (location, found) = found_at(0);
+                    diagnostics.push(diagnostic(location, "a vertex size", found));
+                },
+            }
+            position += 1;
+        }
+        let mut vertex_weights = Vec::new();
+        for i in position..(position + weights) {
+            match number(i) {
+                Some(w) => vertex_weights.push(w),
+                None => {
+                    let (location, found) = found_at(i);
+                    diagnostics.push(diagnostic(location, "a vertex weight", found));
+                    break
+                },
+            }
+        }
+        position += weights;
+        vertex_values.push((size, vertex_weights));
+
+        let mut list = Vec::new();
+        let step = if has_edge_weights { 2 } else { 1 };
+        while position < fields.len() {
+            let location = fields[position].0;
+            let neighbour = match fields[position].1.parse::<usize>() {
+                Ok(v) if v >= 1 && v <= n => v - 1,
+                _ => {
+                    let (location, found) = found_at(position);
+                    diagnostics.push(diagnostic(location, &format!("a vertex number from 1 to {}", n), found));
+                    position += step;
+                    continue
+                },
+            };
+            let weight = if has_edge_weights {
+                match number(position + 1) {
+                    Some(w) => Some(w),
+                    None => {
+                        let (location, found) = found_at(position + 1);
+                        diagnostics.push(diagnostic(location, "an edge weight", found));
+                        position += step;
+                        continue
+                    },
+                }
+            } else {
+                None
+            };
+            list.push((neighbour, weight, location));
+            if !directed && neighbour == vertex {
+                diagnostics.push(diagnostic(location, "no self-loops in an undirected graph", format!("vertex {} in its own list", vertex + 1)));
+            }
+            position += step;
+        }
+        lists.push(list);
+    }
+
+    let end_of_file = Location { line : last_line + 1, column : 1 };
+    let (n, m, header_location) = match header {
+        Some((n, m, _, _, _, location)) => (n, m, location),
+        None => {
+            if diagnostics.is_empty() {
+                diagnostics.push(diagnostic(end_of_file, "a header with the numbers of vertices and edges", String::from("end of file")));
+            }
+            return Err(finish(diagnostics, mode))
+        },
+    };
+    if lists.len() < n && diagnostics.is_empty() {
+        diagnostics.push(diagnostic(end_of_file, &format!("{} vertex lines", n), format!("{}", lists.len())));
+    }
+    if !diagnostics.is_empty() {
+        return Err(finish(diagnostics, mode))
+    }
+
+    let config = GraphConfig { parallel_edges : ParallelEdgePolicy::Keep, self_loops : SelfLoopPolicy::Allow };
+    let mut g = unconnected_with_config((0..n).collect(), directed, config);
+    let (_, _, has_sizes, weights, has_edge_weights, _) = header.unwrap();
+    if has_sizes {
+        g.node_attributes.declare("size", AttributeType::Int, None).unwrap();
+    }
+    let weight_names : Vec<String> = if weights == 1 { vec![String::from("weight")] }
+                                     else { (0..weights).map(|i| format!("weight{}", i)).collect() };
+    for name in &weight_names {
+        g.node_attributes.declare(name, AttributeType::Int, None).unwrap();
+    }
+    if has_edge_weights {
+        g.edge_attributes.declare("weight", AttributeType::Int, None).unwrap();
+    }
+    for (vertex, (size, vertex_weights)) in vertex_values.into_iter().enumerate() {
+        if let Some(size) = size {
+            g.node_attributes.set(vertex, "size", size).unwrap();
+        }
+        for (name, weight) in weight_names.iter().zip(vertex_weights) {
+            g.node_attributes.set(vertex, name, weight).unwrap();
+        }
+    }
+
+    if directed {
+        for (source, list) in lists.iter().enumerate() {
+            for &(dest, weight, _) in list {
+                let edge = g.add_directed_edge(source, dest);
+                if let Some(weight) = weight {
+                    g.edge_attributes.set(edge, "weight", weight).unwrap();
+                }
+            }
+        }
+    } else {
+        // Each listing of u in v's line, by (v, u), to check that it matches a listing of v in u's line.
+        let mut listings : HashMap<(usize, usize), (Option<i64>, Location)> = HashMap::new();
+        for (vertex, list) in lists.iter().enumerate() {
+            for &(neighbour, weight, location) in list {
+                if let Some(&(_, first)) = listings.get(&(vertex, neighbour)) {
+                    diagnostics.push(diagnostic(location, &format!("each neighbour of vertex {} to be listed once", vertex + 1),
+                                                format!("vertex {}, also listed at column {}", neighbour + 1, first.column)));
+                } else {
+                    listings.insert((vertex, neighbour), (weight, location));
+                }
+            }
+        }
+        for (vertex, list) in lists.iter().enumerate() {
+            for &(neighbour, weight, location) in list {
+                match listings.get(&(neighbour, vertex)) {
+                    None => diagnostics.push(diagnostic(location, &format!("vertex {} to list vertex {} too, as the graph is undirected", neighbour + 1, vertex + 1),
+                                                        String::from("no such neighbour"))),
+                    Some(&(other, other_location)) if other != weight && vertex < neighbour =>
+                        diagnostics.push(diagnostic(location, &format!("the same weight as on line {}", other_location.line),
+                                                    format!("{} and {}", weight.unwrap(), other.unwrap()))),
+                    _ => (),
+                }
+            }
+        }
+        if !diagnostics.is_empty() {
+            return Err(finish(diagnostics, mode))
+        }
+        // Arcs are pushed directly, rather than with add_undirected_edge, so that
+        // each adjacency list keeps the order of its line.
+        let mut edges = HashMap::new();
+        for (vertex, list) in lists.iter().enumerate() {
+            for &(neighbour, weight, _) in list {
+                let edge = if vertex < neighbour {
+                    let edge = g.new_edge(vertex, neighbour);
+                    if let Some(weight) = weight {
+                        g.edge_attributes.set(edge, "weight", weight).unwrap();
+                    }
+                    edges.insert((vertex, neighbour), edge);
+                    edge
+                } else {
+                    edges[&(neighbour, vertex)]
+                };
+                g.push_arc(vertex, neighbour, edge);
+            }
+        }
+    }
+
+    if g.number_of_edges() != m {
+        let kind = if directed { "arcs" } else { "edges" };
+        diagnostics.push(diagnostic(header_location, &format!("{} {}, as given in the header", m, kind), format!("{}", g.number_of_edges())));
+        return Err(finish(diagnostics, mode))
+    }
+    Ok(g)
+}
+
+fn finish(mut diagnostics : Vec<Diagnostic>, mode : ParseMode) -> ParseError {
+    diagnostics.sort_by_key(|d| (d.line, d.column));
+    if mode == ParseMode::Strict {
+        diagnostics.truncate(1);
+    }
+    ParseError::Invalid(diagnostics)
+}
+
+pub fn make_metis_string<T>(graph : &Graph<T>) -> String
+    where T : Clone + Eq + Hash
+{
+    let mut metis = Vec::new();
+    write_metis(graph, &mut metis).unwrap();  // Writing to a Vec can't fail
+    String::from_utf8(metis).unwrap()
+}
+
+pub fn to_metis_file<T>(graph : &Graph<T>, filename : &str) -> io::Result<()>
+    where T : Clone + Eq + Hash
+{
+    write_atomically(filename, |writer| write_metis(graph, writer))
+}
+
+// A size or weight as written: only whole numbers can be, and a missing value is 1.
+fn integer(value : Option<&AttributeValue>, what : String) -> io::Result<String> {
+    match value {
+        None => Ok(String::from("1")),
+        Some(v) => match whole_number(v) {
+            Some(i) => Ok(i.to_string()),
+            None => Err(io::Error::new(io::ErrorKind::InvalidInput, format!("METIS can only hold whole numbers, not {} for {}", v, what))),
+        },
+    }
+}
+
+// Writes each adjacency list as a line. Vertex sizes, vertex weights and edge
+// weights are written if the graph has the attributes the reader gives them,
+// with 1 for any missing value; any that isn't a whole number is an InvalidInput
+// error.
+// So is an undirected graph with a self-loop or parallel edges. The header
+// gives the number of arcs written for a directed graph, so an edge added to
+// one with add_undirected_edge counts twice.
+pub fn write_metis<T, W>(graph : &Graph<T>, mut writer : W) -> io::Result<()>
+    where T : Clone + Eq + Hash, W : Write
+{
+    let n = graph.number_of_vertices();
+    let arcs : usize = graph.adjacency_list.iter().map(|list| list.len()).sum();
+    if !graph.is_directed() {
+        if let Some(v) = (0..n).find(|&v| graph.adjacency_list[v].contains(&v)) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("METIS graphs can't have self-loops, as at vertex {}", v)))
+        }
+        for (v, list) in graph.adjacency_list.iter().enumerate() {
+            let mut seen = HashSet::new();
+            if let Some(&w) = list.iter().find(|&&w| !seen.insert(w)) {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                          format!("an undirected METIS graph can't have parallel edges, as between vertices {} and {}", v, w)))
+            }
+        }
+    }
+    let has_sizes = graph.node_attributes.attribute_type("size").is_some();
+    let weight_names : Vec<String> = if graph.node_attributes.attribute_type("weight").is_some() {
+        vec![String::from("weight")]
+    } else {
+        (0..).map(|i| format!("weight{}", i)).take_while(|name| graph.node_attributes.attribute_type(name).is_some()).collect()
+    };
+    let has_edge_weights = graph.edge_attributes.attribute_type("weight").is_some();
+
+    write!(writer, "{} {}", n, if graph.is_directed() { arcs } else { arcs / 2 })?;
+    if has_sizes || !weight_names.is_empty() || has_edge_weights {
+        let digit = |b : bool| if b { "1" } else { "0" };
+        write!(writer, " {}{}{}", digit(has_sizes), digit(!weight_names.is_empty()), digit(has_edge_weights))?;
+        if weight_names.len() > 1 {
+            write!(writer, " {}", weight_names.len())?;
+        }
+    }
+    writeln!(writer)?;
+
+    for vertex in 0..n {
+        let mut fields = Vec::new();
+        let names = if has_sizes { Some("size") } else { None }.into_iter().chain(weight_names.iter().map(|s| s.as_str()));
+        for name in names {
+            fields.push(integer(graph.node_attributes.get(vertex, name), format!("the {} of vertex {}", name, vertex))?);
+        }
+        for (&dest, &edge) in graph.adjacency_list[vertex].iter().zip(graph.adjacency_edges[vertex].iter()) {
+            fields.push((dest + 1).to_string());
+            if has_edge_weights {
+                fields.push(integer(graph.edge_attributes.get(edge, "weight"), format!("the weight of the edge from {} to {}", vertex, dest))?);
+            }
+        }
+        writeln!(writer, "{}", fields.join(" "))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,397 @@
+use super::{Graph, GraphConfig, Metadata, ParallelEdgePolicy, SelfLoopPolicy};
+use super::attributes::AttributeStore;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::fs;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::io::prelude::*;
+use std::io;
+use std::str::FromStr;
+use std::fmt::Display;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub mod adjacency_matrix;
+pub mod binary;
+pub mod dimacs;
+pub mod dot;
+pub mod edge_list;
+pub mod gml;
+pub mod graphml;
+pub mod matrix_market;
+pub mod metis;
+pub mod node_link;
+pub mod pajek;
+mod json;
+mod parser;
+mod validation;
+mod xml;
+
+pub use self::parser::{Diagnostic, ParseError, ParseMode, Section, Severity, FORMAT_VERSION};
+use self::parser::{ParsedFile, quote_if_needed};
+use super::attributes::AttributeValue;
+
+pub fn unconnected<T : Eq + Clone + Hash>(nodes : Vec<T>, directed : bool) -> Graph<T> {
+    unconnected_with_config(nodes, directed, GraphConfig::default())
+}
+
+pub fn unconnected_with_config<T : Eq + Clone + Hash>(nodes : Vec<T>, directed : bool, config : GraphConfig) -> Graph<T> {
+    let hash_map : HashMap<T, usize> =
+        nodes.iter()
+             .cloned()
+             .enumerate()
+             .map(|(i, x)| { (x, i) })
+             .collect();
+    let adjacency_list = vec![Vec::new(); nodes.len()];
+    let adjacency_edges = vec![Vec::new(); nodes.len()];
+    Graph { nodes : nodes.clone(), directed, node_indices : hash_map, config,
+            adjacency_list, adjacency_edges, edge_endpoints : Vec::new(), edge_index : None,
+            node_attributes : AttributeStore::new(), edge_attributes : AttributeStore::new(), metadata : Metadata::default() }
+}
+
+pub fn from_file(filename : &str) -> Result<Graph<usize>, ParseError> {
+    from_reader(BufReader::new(File::open(filename)?))
+}
+
+pub fn from_file_with_nodes<T : Clone + Eq + Hash + FromStr>(filename : &str) -> Result<Graph<T>, ParseError> {
+    from_reader_with_nodes(BufReader::new(File::open(filename)?))
+}
+
+pub fn from_str(text : &str) -> Result<Graph<usize>, ParseError> {
+    from_reader(text.as_bytes())
+}
+
+pub fn from_str_with_nodes<T : Clone + Eq + Hash + FromStr>(text : &str) -> Result<Graph<T>, ParseError> {
+    from_reader_with_nodes(text.as_bytes())
+}
+
+pub fn from_reader<R : BufRead>(reader : R) -> Result<Graph<usize>, ParseError> {
+    from_reader_with_mode(reader, ParseMode::Strict)
+}
+
+pub fn from_reader_with_nodes<T : Clone + Eq + Hash + FromStr, R : BufRead>(reader : R) -> Result<Graph<T>, ParseError> {
+    from_reader_with_nodes_and_mode(reader, ParseMode::Strict)
+}
+
+// In lenient mode every error in the file is reported, rather than just the first.
+// Any labels are read as strings, since the graph's nodes are just its indices.
+pub fn from_reader_with_mode<R : BufRead>(reader : R, mode : ParseMode) -> Result<Graph<usize>, ParseError> {
+    let parsed = checked_parse::<String, R>(reader, mode, false)?;
+    let nodes = (0..parsed.number_of_vertices.unwrap()).collect();
+    Ok(build_graph(nodes, parsed))
+}
+
+pub fn from_reader_with_nodes_and_mode<T, R>(reader : R, mode : ParseMode) -> Result<Graph<T>, ParseError>
+    where T : Clone + Eq + Hash + FromStr, R : BufRead
+{
+    let mut parsed = checked_parse::<T, R>(reader, mode, true)?;
+    let nodes = parsed.nodes.split_off(0);
+    Ok(build_graph(nodes, parsed))
+}
+
+// Checks a graph file without building the graph, and returns every problem found,
+// including warnings. As well as anything that would stop the file from loading,
+// this reports edges that are listed more than once, even if the file says to
+// merge parallel edges.
+pub fn validate_reader<R : BufRead>(reader : R) -> io::Result<Vec<Diagnostic>> {
+    let mut parsed = parser::parse::<String, R>(reader, ParseMode::Lenient)?;
+    let mut diagnostics = parsed.diagnostics.split_off(0);
+    diagnostics.extend(validation::check(&parsed, false, true));
+    diagnostics.sort_by_key(|d| (d.line, d.column));
+    Ok(diagnostics)
+}
+
+pub fn validate_file(filename : &str) -> io::Result<Vec<Diagnostic>> {
+    validate_reader(BufReader::new(File::open(filename)?))
+}
+
+pub fn validate_str(text : &str) -> Vec<Diagnostic> {
+    validate_reader(text.as_bytes()).unwrap()    // Reading from a string can't fail
+}
+
+// Parses and validates the file, so that the graph can then be built without
+// any further checks.
+fn checked_parse<T, R>(reader : R, mode : ParseMode, expect_labels : bool) -> Result<ParsedFile<T>, ParseError>
+    where T : Clone + Eq + Hash + FromStr, R : BufRead
+{
+    let mut parsed = parser::parse::<T, R>(reader, mode)?;
+    let mut diagnostics = parsed.diagnostics.split_off(0);
+    let has_errors = |diagnostics : &[Diagnostic]| diagnostics.iter().any(|d| d.severity == Severity::Error);
+    if !has_errors(&diagnostics) || mode == ParseMode::Lenient {
+        diagnostics.extend(validation::check(&parsed, expect_labels, false));
+        diagnostics.sort_by_key(|d| (d.line, d.column));
+    }
+
+    // Warnings alone don't stop the file loading. If there are errors, lenient
+    // mode reports the warnings along with them.
+    if !has_errors(&diagnostics) {
+        return Ok(parsed)
+    }
+    if mode == ParseMode::Strict {
+        diagnostics.retain(|d| d.severity == Severity::Error);
+        diagnostics.truncate(1);
+    }
+    Err(ParseError::Invalid(diagnostics))
+}
+
+fn build_graph<T, U>(nodes : Vec<T>, parsed : ParsedFile<U>) -> Graph<T>
+    where T : Clone + Eq + Hash
+{
+    let mut g = unconnected_with_config(nodes, parsed.directed, parsed.config);
+    g.metadata = parsed.metadata;
+
+    let mut edge_indices = Vec::with_capacity(parsed.edges.len());
+    for (source, dest) in parsed.edges {
+        if parsed.directed { edge_indices.push(g.add_directed_edge(source, dest)); }
+        else { edge_indices.push(g.add_undirected_edge(source, dest)); }
+    }
+
+    // The file has already been validated, so none of this can fail.
+    for (name, attribute_type, default) in parsed.node_attribute_types {
+        g.node_attributes.declare(&name, attribute_type, default).unwrap();
+    }
+    for (name, attribute_type, default) in parsed.edge_attribute_types {
+        g.edge_attributes.declare(&name, attribute_type, default).unwrap();
+    }
+    for (node, name, value) in parsed.node_attributes {
+        g.node_attributes.set(node, &name, value).unwrap();
+    }
+    for (position, name, value) in parsed.edge_attributes {
+        g.edge_attributes.set(edge_indices[position], &name, value).unwrap();
+    }
+
+    g
+}
+
+impl FromStr for Graph<usize> {
+    type Err = ParseError;
+
+    fn from_str(text : &str) -> Result<Graph<usize>, ParseError> {
+        from_str(text)
+    }
+}
+
+pub fn make_serialization_string<T>(graph : &Graph<T>) -> String
+    where T : Clone + Eq + Hash
+{
+    let mut ser = Vec::new();
+    write_graph(graph, &mut ser).unwrap();  // Writing to a Vec can't fail
+    String::from_utf8(ser).unwrap()
+}
+
+pub fn make_serialization_string_with_nodes<T>(graph : &Graph<T>) -> String
+    where T : Clone + Eq + Hash + Display
+{
+    let mut ser = Vec::new();
+    write_graph_with_nodes(graph, &mut ser).unwrap();
+    String::from_utf8(ser).unwrap()
+}
+
+pub fn make_serialization_string_with_labelled_edges<T>(graph : &Graph<T>) -> String
+    where T : Clone + Eq + Hash + Display
+{
+    let mut ser = Vec::new();
+    write_graph_with_labelled_edges(graph, &mut ser).unwrap();
+    String::from_utf8(ser).unwrap()
+}
+
+// Writes the graph out line by line, so the whole serialization never has to be
+// held in memory. The writer should be buffered for large graphs.
+pub fn write_graph<T, W>(graph : &Graph<T>, writer : W) -> io::Result<()>
+    where T : Clone + Eq + Hash, W : Write
+{
+    write_structure(graph, writer, None)
+}
+
+// Like write_graph_with_nodes, but the edges refer to nodes by label, which is
+// easier to edit by hand.
+pub fn write_graph_with_labelled_edges<T, W>(graph : &Graph<T>, mut writer : W) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display, W : Write
+{
+    let labels : Vec<String> = graph.nodes.iter().map(|node| quote_if_needed(&node.to_string())).collect();
+    write_structure(graph, &mut writer, Some(&labels))?;
+    write_nodes(&labels, writer)
+}
+
+// Everything but the nodes section. If labels are given then the edges are
+// written using them, rather than using indices.
+fn write_structure<T, W>(graph : &Graph<T>, mut writer : W, labels : Option<&[String]>) -> io::Result<()>
+    where T : Clone + Eq + Hash, W : Write
+{
+    writeln!(writer, "// Graph")?;
+    writeln!(writer, "format_version: {}", FORMAT_VERSION)?;
+    if let Some(ref name) = graph.metadata.name {
+        writeln!(writer, "name: {}", quote_if_needed(name))?;
+    }
+    if let Some(ref description) = graph.metadata.description {
+        writeln!(writer, "description: {}", quote_if_needed(description))?;
+    }
+    writeln!(writer, "number_of_vertices: {}", graph.number_of_vertices())?;
+    writeln!(writer, "directed: {}", graph.is_directed())?;
+    if labels.is_some() {
+        writeln!(writer, "edge_format: label")?;
+    }
+    // The edge policies are only written out if they differ from the default.
+    let default_config = GraphConfig::default();
+    if graph.config.parallel_edges != default_config.parallel_edges {
+        writeln!(writer, "parallel_edges: {}", match graph.config.parallel_edges {
+            ParallelEdgePolicy::Reject => "reject",
+            ParallelEdgePolicy::Merge  => "merge",
+            ParallelEdgePolicy::Keep   => "keep",
+        })?;
+    }
+    if graph.config.self_loops != default_config.self_loops {
+        writeln!(writer, "self_loops: {}", match graph.config.self_loops {
+            SelfLoopPolicy::Reject => "reject",
+            SelfLoopPolicy::Allow  => "allow",
+        })?;
+    }
+    write_attribute_declarations(&mut writer, "node", &graph.node_attributes)?;
+    write_attribute_declarations(&mut writer, "edge", &graph.edge_attributes)?;
+
+    writeln!(writer, "edges:")?;
+    // Edge attributes refer to edges by their position in the edges section, so if
+    // there are any we need to remember where each edge was written.
+    let record_positions = !graph.edge_attributes.is_empty();
+    let mut edge_positions = HashMap::new();
+    let mut position = 0;
+    for source in 0..(graph.number_of_vertices()) {
+        for (dest, edge) in graph.adjacency_list[source].iter().zip(graph.adjacency_edges[source].iter()) {
+            // An undirected edge appears in the adjacency lists of both endpoints, but a
+            // self-loop only appears once.
+            if graph.is_directed() || *dest >= source {
+                match labels {
+                    Some(labels) => writeln!(writer, "{} {} {}", labels[source], if graph.is_directed() { "->" } else { "--" }, labels[*dest])?,
+                    None => writeln!(writer, "{} {}", source, *dest)?,
+                }
+                if record_positions {
+                    edge_positions.entry(*edge).or_insert(position);
+                }
+                position += 1;
+            }
+        }
+    }
+
+    if record_positions {
+        let mut values = Vec::new();
+        for name in graph.edge_attributes.names() {
+            for (edge, value) in graph.edge_attributes.iter(name) {
+                if let Some(position) = edge_positions.get(&edge) {
+                    values.push((*position, name, value));
+                }
+            }
+        }
+        values.sort_by_key(|&(position, name, _)| (position, name));
+        writeln!(writer, "edge_attributes:")?;
+        for (position, name, value) in values {
+            writeln!(writer, "{} {} {}", position, name, attribute_text(value))?;
+        }
+    }
+
+    if !graph.node_attributes.is_empty() {
+        let mut values = Vec::new();
+        for name in graph.node_attributes.names() {
+            for (node, value) in graph.node_attributes.iter(name) {
+                values.push((node, name, value));
+            }
+        }
+        values.sort_by_key(|&(node, name, _)| (node, name));
+        writeln!(writer, "node_attributes:")?;
+        for (node, name, value) in values {
+            writeln!(writer, "{} {} {}", node, name, attribute_text(value))?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn write_graph_with_nodes<T, W>(graph : &Graph<T>, mut writer : W) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display, W : Write
+{
+    write_graph(graph, &mut writer)?; // Re-use the above code to serialize the structure
+
+    let labels : Vec<String> = graph.nodes.iter().map(|node| quote_if_needed(&node.to_string())).collect();
+    write_nodes(&labels, writer)
+}
+
+fn write_nodes<W : Write>(labels : &[String], mut writer : W) -> io::Result<()> {
+    writeln!(writer, "nodes:")?;
+    for label in labels {
+        writeln!(writer, "{}", label)?;
+    }
+    Ok(())
+}
+
+fn write_attribute_declarations<K, W>(writer : &mut W, kind : &str, store : &AttributeStore<K>) -> io::Result<()>
+    where K : Copy + Eq + Hash + Ord, W : Write
+{
+    for name in store.names() {
+        let attribute_type = store.attribute_type(name).unwrap();
+        match store.default_value(name) {
+            Some(default) => writeln!(writer, "{}_attribute: {} {} {}", kind, name, attribute_type, attribute_text(default))?,
+            None => writeln!(writer, "{}_attribute: {} {}", kind, name, attribute_type)?,
+        }
+    }
+    Ok(())
+}
+
+// Strings are quoted if they would otherwise be read back differently.
+fn attribute_text(value : &AttributeValue) -> String {
+    match *value {
+        AttributeValue::Str(ref s) => quote_if_needed(s),
+        ref other => other.to_string(),
+    }
+}
+
+// An int, or a float holding a whole number that fits in one, for formats that
+// can only hold whole numbers. Many readers keep weights as floats.
+fn whole_number(value : &AttributeValue) -> Option<i64> {
+    match *value {
+        AttributeValue::Int(i) => Some(i),
+        AttributeValue::Float(x) if x.fract() == 0.0 && x >= i64::MIN as f64 && x < -(i64::MIN as f64) => Some(x as i64),
+        _ => None,
+    }
+}
+
+// Writes the graph to a temporary file alongside the destination and then renames
+// it into place, so that readers never see a partially written file.
+pub fn to_file<T>(graph : &Graph<T>, filename : &str) -> io::Result<()>
+    where T : Clone + Eq + Hash
+{
+    write_atomically(filename, |writer| write_graph(graph, writer))
+}
+
+pub fn to_file_with_nodes<T>(graph : &Graph<T>, filename : &str) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display
+{
+    write_atomically(filename, |writer| write_graph_with_nodes(graph, writer))
+}
+
+pub fn to_file_with_labelled_edges<T>(graph : &Graph<T>, filename : &str) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display
+{
+    write_atomically(filename, |writer| write_graph_with_labelled_edges(graph, writer))
+}
+
+// Counts calls to write_atomically, so that threads writing the same file don't
+// share a temporary file.
+static TEMP_FILES : AtomicUsize = AtomicUsize::new(0);
+
+fn write_atomically<F>(filename : &str, write : F) -> io::Result<()>
+    where F : FnOnce(&mut BufWriter<File>) -> io::Result<()>
+{
+    let count = TEMP_FILES.fetch_add(1, Ordering::Relaxed);
+    let temp_filename = format!("{}.{}.{}.tmp", filename, process::id(), count);
+    let result = File::create(&temp_filename).and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        write(&mut writer)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()
+    }).and_then(|_| fs::rename(&temp_filename, filename));
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_filename);
+    }
+    result
+}
@@ -0,0 +1,386 @@
+// Node-link JSON, as written by NetworkX's node_link_data and read by D3.
+//
+//     { "directed": false, "multigraph": false, "graph": {"name": "roads"},
+//       "nodes": [ {"id": 1, "colour": "red"}, {"id": 2} ],
+//       "links": [ {"source": 1, "target": 2, "weight": 2.5} ] }
+//
+// Links refer to nodes by id, and a node's label is its id, which can be a
+// number or a string, parsed as T. Links may be given as "edges" instead, as
+// newer versions of NetworkX do. Other booleans, numbers and strings on nodes
+// and links become attributes; an attribute given as both integers and reals
+// is read as floats, and one given as anything else mixed as strings. Nested
+// arrays and objects, nulls, and fields whose names couldn't be attribute names
+// in the native format are ignored. The graph's "name" and
+// "description" are kept as its metadata.
+//
+// A multigraph keeps parallel links, and any other graph merges them. When
+// writing, integer labels are written as numbers and all others as strings,
+// and attributes called id, source or target are left out.
+
+use super::super::{EdgeIndex, Graph, GraphConfig, Metadata, ParallelEdgePolicy, SelfLoopPolicy};
+use super::super::attributes::{is_attribute_name, AttributeType, AttributeValue};
+use super::json;
+use super::json::{Located, Value};
+use super::parser::Location;
+use super::{unconnected_with_config, write_atomically, Diagnostic, ParseError, ParseMode, Section, Severity};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::fs::File;
+use std::hash::Hash;
+use std::io;
+use std::io::BufReader;
+use std::io::prelude::*;
+use std::str::FromStr;
+
+const SECTION : Section = Section::Other("node-link JSON");
+
+pub fn from_node_link_file<T>(filename : &str) -> Result<Graph<T>, ParseError>
+    where T : Clone + Eq + Hash + FromStr
+{
+    from_node_link_reader(BufReader::new(File::open(filename)?))
+}
+
+pub fn from_node_link_str<T>(text : &str) -> Result<Graph<T>, ParseError>
+    where T : Clone + Eq + Hash + FromStr
+{
+    from_node_link_reader(text.as_bytes())
+}
+
+pub fn from_node_link_reader<T, R>(reader : R) -> Result<Graph<T>, ParseError>
+    where T : Clone + Eq + Hash + FromStr, R : Read
+{
+    from_node_link_reader_with_mode(reader, ParseMode::Strict)
+}
+
+// In lenient mode every problem with the graph is reported, but badly formed
+// JSON still stops at the first problem.
+pub fn from_node_link_reader_with_mode<T, R>(mut reader : R, mode : ParseMode) -> Result<Graph<T>, ParseError>
+    where T : Clone + Eq + Hash + FromStr, R : Read
+{
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let document = json::parse(&text, SECTION).map_err(|d| ParseError::Invalid(vec![d]))?;
+
+    let mut diagnostics = Vec::new();
+    let graph = build_graph(document, &mut diagnostics);
+    if diagnostics.is_empty() {
+        return Ok(graph.unwrap())
+    }
+    if mode == ParseMode::Strict {
+        diagnostics.truncate(1);
+    }
+    Err(ParseError::Invalid(diagnostics))
+}
+
+fn error(location : Location, expected : &str, found : String) -> Diagnostic {
+    Diagnostic { line : location.line, column : location.column, section : SECTION,
+                 expected : String::from(expected), found, severity : Severity::Error }
+}
+
+// Node ids and link endpoints, as text to be matched up and parsed.
+fn id_text(value : &Value) -> Option<String> {
+    match *value {
+        Value::Number(ref n) => Some(n.clone()),
+        Value::Str(ref s)    => Some(s.clone()),
+        _ => None,
+    }
+}
+
+// Scalars as attribute values; nulls, arrays and objects have none.
+fn attribute_value(value : &Value) -> Option<AttributeValue> {
+    match *value {
+        Value::Bool(b)       => Some(AttributeValue::Bool(b)),
+        Value::Number(ref n) => Some(n.parse::<i64>().map(AttributeValue::Int)
+                                      .unwrap_or_else(|_| AttributeValue::Float(n.parse().unwrap()))),
+        Value::Str(ref s)    => Some(AttributeValue::Str(s.clone())),
+        _ => None,
+    }
+}
+
+// The fields of an object, or a diagnostic if the value is something else.
+fn fields_of(located : Located, what : &str, diagnostics : &mut Vec<Diagnostic>) -> Option<Vec<(String, Located)>> {
+    match located.value {
+        Value::Object(fields) => Some(fields),
+        value => {
+            diagnostics.push(error(located.location, what, value.describe()));
+            None
+        },
+    }
+}
+
+// Attribute values by name, in the order the names were first seen.
+type AttributeValues<K> = Vec<(String, Vec<(K, AttributeValue)>)>;
+
+fn add_value<K>(values : &mut AttributeValues<K>, name : &str, key : K, value : AttributeValue) {
+    match values.iter_mut().find(|(n, _)| n == name) {
+        Some((_, list)) => list.push((key, value)),
+        None => values.push((String::from(name), vec![(key, value)])),
+    }
+}
+
+// Checks the document and builds the graph, adding any problems to diagnostics.
+fn build_graph<T>(document : Located, diagnostics : &mut Vec<Diagnostic>) -> Option<Graph<T>>
+    where T : Clone + Eq + Hash + FromStr
+{
+    let start = document.location;
+    let members = fields_of(document, "an object with nodes and links", diagnostics)?;
+    let mut directed = false;
+    let mut multigraph = false;
+    let mut metadata = Metadata::default();
+    let mut nodes = None;
+    let mut links = Vec::new();
+    for (key, member) in members {
+        match (key.as_str(), member.value) {
+            ("directed", Value::Bool(b))   => directed = b,
+            ("multigraph", Value::Bool(b)) => multigraph = b,
+            ("directed", value) | ("multigraph", value) =>
+                diagnostics.push(error(member.location, &format!("true or false for {}", key), value.describe())),
+            ("graph", Value::Object(fields)) => {
+                for (name, field) in fields {
+                    match (name.as_str(), field.value) {
+                        ("name", Value::Str(s))        => metadata.name = Some(s),
+                        ("description", Value::Str(s)) => metadata.description = Some(s),
+                        _ => (),
+                    }
+                }
+            },
+            ("nodes", Value::Array(elements)) => nodes = Some(elements),
+            ("links", Value::Array(elements)) | ("edges", Value::Array(elements)) => links = elements,
+            ("nodes", value) | ("links", value) | ("edges", value) =>
+                diagnostics.push(error(member.location, &format!("an array of {}", key), value.describe())),
+            _ => (),
+        }
+    }
+    let nodes = match nodes {
+        Some(nodes) => nodes,
+        None => {
+            diagnostics.push(error(start, "a \"nodes\" array", String::from("none")));
+            return None
+        },
+    };
+
+    let mut labels : Vec<T> = Vec::new();
+    let mut seen_labels : HashSet<T> = HashSet::new();
+    let mut ids : HashMap<String, usize> = HashMap::new();
+    let mut node_values : AttributeValues<usize> = Vec::new();
+    for node in nodes {
+        let location = node.location;
+        let fields = match fields_of(node, "a node object", diagnostics) {
+            Some(fields) => fields,
+            None => continue,
+        };
+        let mut id = None;
+        let mut values = Vec::new();
+        for (name, field) in fields {
+            if name == "id" {
+                id = Some(field);
+            } else if !is_attribute_name(&name) {
+                continue
+            } else if let Some(value) = attribute_value(&field.value) {
+                values.push((name, value));
+            }
+        }
+        let id = match id {
+            Some(id) => id,
+            None => {
+                diagnostics.push(error(location, "an id for the node", String::from("none")));
+                continue
+            },
+        };
+        let text = match id_text(&id.value) {
+            Some(text) => text,
+            None => {
+                diagnostics.push(error(id.location, "a number or string id", id.value.describe()));
+                continue
+            },
+        };
+        let label = match text.parse::<T>() {
+            Ok(label) => label,
+            Err(_) => {
+                diagnostics.push(error(id.location, "an id that can be read as a node label", id.value.describe()));
+                continue
+            },
+        };
+        if ids.contains_key(&text) || seen_labels.contains(&label) {
+            diagnostics.push(error(id.location, "unique node ids", format!("a second node with id {}", id.value.describe())));
+            continue
+        }
+        let index = labels.len();
+        ids.insert(text, index);
+        seen_labels.insert(label.clone());
+        labels.push(label);
+        for (name, value) in values {
+            add_value(&mut node_values, &name, index, value);
+        }
+    }
+
+    let parallel_edges = if multigraph { ParallelEdgePolicy::Keep } else { ParallelEdgePolicy::Merge };
+    let config = GraphConfig { parallel_edges, self_loops : SelfLoopPolicy::Allow };
+    let mut g = unconnected_with_config(labels, directed, config);
+    g.metadata = metadata;
+    let mut edge_values : AttributeValues<EdgeIndex> = Vec::new();
+    for link in links {
+        let location = link.location;
+        let fields = match fields_of(link, "a link object", diagnostics) {
+            Some(fields) => fields,
+            None => continue,
+        };
+        let mut endpoints = [None, None];
+        let mut values = Vec::new();
+        for (name, field) in fields {
+            match name.as_str() {
+                "source" => endpoints[0] = Some(field),
+                "target" => endpoints[1] = Some(field),
+                _ if !is_attribute_name(&name) => (),
+                _ => if let Some(value) = attribute_value(&field.value) { values.push((name, value)) },
+            }
+        }
+        let mut indices = [0; 2];
+        let mut found = true;
+        for (i, (endpoint, which)) in endpoints.iter().zip(["source", "target"].iter()).enumerate() {
+            match *endpoint {
+                Some(ref endpoint) => match id_text(&endpoint.value).and_then(|text| ids.get(&text)) {
+                    Some(&index) => indices[i] = index,
+                    None => {
+                        diagnostics.push(error(endpoint.location, "the id of a node in the graph", endpoint.value.describe()));
+                        found = false;
+                    },
+                },
+                None => {
+                    diagnostics.push(error(location, &format!("a {} for the link", which), String::from("none")));
+                    found = false;
+                },
+            }
+        }
+        if !found { continue }
+        let edge = if directed { g.add_directed_edge(indices[0], indices[1]) }
+                   else { g.add_undirected_edge(indices[0], indices[1]) };
+        for (name, value) in values {
+            add_value(&mut edge_values, &name, edge, value);
+        }
+    }
+
+    for (name, values) in node_values {
+        let attribute_type = common_type(&values);
+        g.node_attributes.declare(&name, attribute_type, None).unwrap();
+        for (index, value) in values {
+            g.node_attributes.set(index, &name, convert(value, attribute_type)).unwrap();
+        }
+    }
+    for (name, values) in edge_values {
+        let attribute_type = common_type(&values);
+        g.edge_attributes.declare(&name, attribute_type, None).unwrap();
+        for (index, value) in values {
+            g.edge_attributes.set(index, &name, convert(value, attribute_type)).unwrap();
+        }
+    }
+    Some(g)
+}
+
+// The type of all the values if they have just one, float for a mixture of
+// ints and floats, and string for any other mixture.
+fn common_type<K>(values : &[(K, AttributeValue)]) -> AttributeType {
+    let first = values[0].1.attribute_type();
+    if values.iter().all(|(_, v)| v.attribute_type() == first) {
+        first
+    } else if values.iter().all(|(_, v)| v.as_float().is_some()) {
+        AttributeType::Float
+    } else {
+        AttributeType::Str
+    }
+}
+
+fn convert(value : AttributeValue, attribute_type : AttributeType) -> AttributeValue {
+    match attribute_type {
+        AttributeType::Float => AttributeValue::Float(value.as_float().unwrap()),
+        AttributeType::Str   => AttributeValue::Str(value.to_string()),
+        _ => value,
+    }
+}
+
+pub fn make_node_link_string<T>(graph : &Graph<T>) -> String
+    where T : Clone + Eq + Hash + Display
+{
+    let mut json = Vec::new();
+    write_node_link(graph, &mut json).unwrap();  // Writing to a Vec can't fail
+    String::from_utf8(json).unwrap()
+}
+
+pub fn to_node_link_file<T>(graph : &Graph<T>, filename : &str) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display
+{
+    write_atomically(filename, |writer| write_node_link(graph, writer))
+}
+
+pub fn write_node_link<T, W>(graph : &Graph<T>, mut writer : W) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display, W : Write
+{
+    let ids : Vec<String> = graph.nodes.iter().map(|label| json_id(&label.to_string())).collect();
+    let mut graph_fields = Vec::new();
+    if let Some(ref name) = graph.metadata.name {
+        graph_fields.push(format!("\"name\": {}", json::quote(name)));
+    }
+    if let Some(ref description) = graph.metadata.description {
+        graph_fields.push(format!("\"description\": {}", json::quote(description)));
+    }
+
+    let mut nodes = Vec::new();
+    for (index, id) in ids.iter().enumerate() {
+        let mut fields = vec![format!("\"id\": {}", id)];
+        for (name, value) in graph.node_attributes.attributes_of(index) {
+            if name != "id" {
+                fields.push(format!("{}: {}", json::quote(name), json_value(value)));
+            }
+        }
+        nodes.push(format!("{{{}}}", fields.join(", ")));
+    }
+    let mut links = Vec::new();
+    for source in 0..graph.number_of_vertices() {
+        for (&dest, &edge) in graph.adjacency_list[source].iter().zip(graph.adjacency_edges[source].iter()) {
+            if !graph.is_directed() && dest < source { continue }
+            let mut fields = vec![format!("\"source\": {}", ids[source]), format!("\"target\": {}", ids[dest])];
+            for (name, value) in graph.edge_attributes.attributes_of(edge) {
+                if name != "source" && name != "target" {
+                    fields.push(format!("{}: {}", json::quote(name), json_value(value)));
+                }
+            }
+            links.push(format!("{{{}}}", fields.join(", ")));
+        }
+    }
+
+    writeln!(writer, "{{")?;
+    writeln!(writer, "  \"directed\": {},", graph.is_directed())?;
+    writeln!(writer, "  \"multigraph\": {},", graph.config.parallel_edges == ParallelEdgePolicy::Keep)?;
+    writeln!(writer, "  \"graph\": {{{}}},", graph_fields.join(", "))?;
+    write_array(&mut writer, "nodes", &nodes, ",")?;
+    write_array(&mut writer, "links", &links, "")?;
+    writeln!(writer, "}}")
+}
+
+fn write_array<W : Write>(writer : &mut W, name : &str, elements : &[String], end : &str) -> io::Result<()> {
+    if elements.is_empty() {
+        return writeln!(writer, "  \"{}\": []{}", name, end)
+    }
+    writeln!(writer, "  \"{}\": [", name)?;
+    writeln!(writer, "    {}", elements.join(",\n    "))?;
+    writeln!(writer, "  ]{}", end)
+}
+
+// Integers are written as numbers, so that NetworkX reads them back as ints.
+fn json_id(label : &str) -> String {
+    match label.parse::<i64>() {
+        Ok(i) if i.to_string() == label => String::from(label),
+        _ => json::quote(label),
+    }
+}
+
+// JSON has no infinities or NaN, so they're written as null.
+fn json_value(value : &AttributeValue) -> String {
+    match *value {
+        AttributeValue::Bool(b)                   => format!("{}", b),
+        AttributeValue::Int(i)                    => format!("{}", i),
+        AttributeValue::Float(x) if x.is_finite() => format!("{:?}", x),
+        AttributeValue::Float(_)                  => String::from("null"),
+        AttributeValue::Str(ref s)                => json::quote(s),
+    }
+}
@@ -0,0 +1,313 @@
+// Pajek network files (.net).
+//
+//     *Vertices 3
+//     1 "a"
+//     2 "b" 0.1 0.2 0.5
+//     3 "c"
+//     *Arcs
+//     1 2 1.5
+//     *Edges
+//     2 3
+//
+// Vertices are numbered from 1 in the file; a vertex with no line of its own is
+// labelled by its number. *Arcslist and *Edgeslist sections, which give a
+// vertex followed by its neighbours, are read too, and arc and edge weights are
+// kept as the float edge attribute "weight". Coordinates, shapes and colours
+// are ignored.
+//
+// A file with only edges gives an undirected graph. One with any arcs gives a
+// directed graph, in which each edge becomes a pair of arcs sharing an
+// EdgeIndex, as with add_undirected_edge; the writer turns such pairs back into
+// edges.
+
+use super::super::{Graph, GraphConfig, ParallelEdgePolicy, SelfLoopPolicy};
+use super::super::attributes::AttributeType;
+use super::{unconnected_with_config, write_atomically, Diagnostic, ParseError, ParseMode, Section, Severity};
+use std::fmt::Display;
+use std::fs::File;
+use std::hash::Hash;
+use std::collections::HashMap;
+use std::io;
+use std::io::BufReader;
+use std::io::prelude::*;
+
+const SECTION : Section = Section::Other("Pajek");
+
+pub fn from_pajek_file(filename : &str) -> Result<Graph<String>, ParseError> {
+    from_pajek_reader(BufReader::new(File::open(filename)?))
+}
+
+pub fn from_pajek_str(text : &str) -> Result<Graph<String>, ParseError> {
+    from_pajek_reader(text.as_bytes())
+}
+
+pub fn from_pajek_reader<R : BufRead>(reader : R) -> Result<Graph<String>, ParseError> {
+    from_pajek_reader_with_mode(reader, ParseMode::Strict)
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum Part {
+    Start,
+    Vertices,
+    Arcs,
+    Edges,
+    ArcsList,
+    EdgesList,
+    Other,      // *Network and the like, whose lines are ignored
+}
+
+struct Token<'a> {
+    column : usize,
+    text   : &'a str,
+}
+
+fn diagnostic(line : usize, column : usize, expected : &str, found : String) -> Diagnostic {
+    Diagnostic { line, column, section : SECTION, expected : String::from(expected), found, severity : Severity::Error }
+}
+
+pub fn from_pajek_reader_with_mode<R : BufRead>(reader : R, mode : ParseMode) -> Result<Graph<String>, ParseError> {
+    let mut diagnostics = Vec::new();
+    let mut part = Part::Start;
+    let mut labels : Vec<Option<(String, usize)>> = Vec::new();
+    let mut seen_vertices = false;
+    let mut vertices_line = 0;     // Vertices without a line of their own are reported here
+    // (source, dest, weight, directed)
+    let mut lines : Vec<(usize, usize, Option<f64>, bool)> = Vec::new();
+
+    for (line_index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = line_index + 1;
+        if mode == ParseMode::Strict && !diagnostics.is_empty() { break }
+        if line.starts_with('%') { continue }
+
+        let tokens = match tokenize(&line) {
+            Ok(tokens) => tokens,
+            Err(column) => {
+                diagnostics.push(diagnostic(line_number, column, "a closing '\"'", String::from("end of line")));
+                continue
+            },
+        };
+        let found_at = |index : usize| match tokens.get(index) {
+            Some(token) => (token.column, format!("\"{}\"", token.text)),
+            None => (line.chars().count() + 1, String::from("end of line")),
+        };
+        let mut error = |index : usize, expected : &str| {
+            let (column, found) = found_at(index);
+            diagnostics.push(diagnostic(line_number, column, expected, found));
+        };
+        if tokens.is_empty() { continue }
+
+        if tokens[0].text.starts_with('*') {
+            part = match tokens[0].text.to_lowercase().as_str() {
+                "*vertices" => {
+                    if seen_vertices {
+                        error(0, "only one *Vertices section");
+                        Part::Other
+                    } else {
+                        seen_vertices = true;
+                        vertices_line = line_number;
+                        // Two-mode networks give the size of the first mode too, which isn't needed.
+                        match tokens.get(1).and_then(|t| t.text.parse::<usize>().ok()) {
+                            Some(n) => labels = vec![None; n],
+                            None => error(1, "the number of vertices"),
+                        }
+                        Part::Vertices
+                    }
+                },
+                "*arcs"      => Part::Arcs,
+                "*edges"     => Part::Edges,
+                "*arcslist"  => Part::ArcsList,
+                "*edgeslist" => Part::EdgesList,
+                "*matrix"    => {
+                    error(0, "*Arcs or *Edges; *Matrix sections aren't supported");
+                    Part::Other
+                },
+                _ => Part::Other,
+            };
+            if part != Part::Vertices && part != Part::Other && !seen_vertices {
+                error(0, "a *Vertices section before any arcs or edges");
+                part = Part::Other;
+            }
+            continue
+        }
+
+        let n = labels.len();
+        let vertex = |index : usize| -> Option<usize> {
+            tokens.get(index).and_then(|t| t.text.parse::<usize>().ok()).filter(|&v| v >= 1 && v <= n).map(|v| v - 1)
+        };
+        let expected_vertex = format!("a vertex number from 1 to {}", n);
+        match part {
+            Part::Start => error(0, "a *Vertices section"),
+            Part::Other => (),
+            Part::Vertices => {
+                let v = match vertex(0) {
+                    Some(v) => v,
+                    None => {
+                        error(0, &expected_vertex);
+                        continue
+                    },
+                };
+                if let Some((_, first)) = labels[v] {
+                    error(0, &format!("one line for each vertex, but vertex {} is on line {} too", v + 1, first));
+                    continue
+                }
+                let label = tokens.get(1).map(|t| unquote(t.text)).unwrap_or_else(|| (v + 1).to_string());
+                labels[v] = Some((label, line_number));
+            },
+            Part::Arcs | Part::Edges => {
+                let (source, dest) = match (vertex(0), vertex(1)) {
+                    (Some(source), Some(dest)) => (source, dest),
+                    (None, _) => {
+                        error(0, &expected_vertex);
+                        continue
+                    },
+                    (_, None) => {
+                        error(1, &expected_vertex);
+                        continue
+                    },
+                };
+                // Anything after the weight, or instead of it, is drawing options.
+                let weight = tokens.get(2).and_then(|t| t.text.parse::<f64>().ok());
+                lines.push((source, dest, weight, part == Part::Arcs));
+            },
+            Part::ArcsList | Part::EdgesList => {
+                let source = match vertex(0) {
+                    Some(source) => source,
+                    None => {
+                        error(0, &expected_vertex);
+                        continue
+                    },
+                };
+                for i in 1..tokens.len() {
+                    match vertex(i) {
+                        Some(dest) => lines.push((source, dest, None, part == Part::ArcsList)),
+                        None => error(i, &expected_vertex),
+                    }
+                }
+            },
+        }
+    }
+
+    if !seen_vertices && diagnostics.is_empty() {
+        diagnostics.push(diagnostic(1, 1, "a *Vertices section", String::from("none")));
+    }
+    let labels : Vec<(String, usize)> = labels.into_iter().enumerate()
+                                              .map(|(i, label)| label.unwrap_or_else(|| ((i + 1).to_string(), vertices_line)))
+                                              .collect();
+    let mut first_seen : HashMap<&str, usize> = HashMap::new();
+    for (label, line) in &labels {
+        match first_seen.get(label.as_str()) {
+            Some(first) => diagnostics.push(diagnostic(*line, 1, "unique vertex labels", format!("a repeat of the label on line {}", first))),
+            None => { first_seen.insert(label, *line); },
+        }
+    }
+    if !diagnostics.is_empty() {
+        diagnostics.sort_by_key(|d| (d.line, d.column));
+        if mode == ParseMode::Strict {
+            diagnostics.truncate(1);
+        }
+        return Err(ParseError::Invalid(diagnostics))
+    }
+
+    let directed = lines.iter().any(|line| line.3);
+    let config = GraphConfig { parallel_edges : ParallelEdgePolicy::Keep, self_loops : SelfLoopPolicy::Allow };
+    let mut g = unconnected_with_config(labels.into_iter().map(|(label, _)| label).collect(), directed, config);
+    if lines.iter().any(|line| line.2.is_some()) {
+        g.edge_attributes.declare("weight", AttributeType::Float, None).unwrap();
+    }
+    for (source, dest, weight, arc) in lines {
+        let edge = if arc { g.add_directed_edge(source, dest) } else { g.add_undirected_edge(source, dest) };
+        if let Some(weight) = weight {
+            g.edge_attributes.set(edge, "weight", weight).unwrap();
+        }
+    }
+    Ok(g)
+}
+
+// Splits a line into words, where a quoted label is one word. An unclosed
+// quote gives the column where it starts.
+fn tokenize(line : &str) -> Result<Vec<Token<'_>>, usize> {
+    let mut tokens = Vec::new();
+    let mut chars = line.char_indices().enumerate().peekable();
+    while let Some((column, (start, c))) = chars.next() {
+        if c.is_whitespace() { continue }
+        let mut end = line.len();
+        if c == '"' {
+            match chars.by_ref().find(|&(_, (_, c))| c == '"') {
+                Some((_, (i, _))) => end = i + 1,
+                None => return Err(column + 1),
+            }
+        } else {
+            while let Some(&(_, (i, c))) = chars.peek() {
+                if c.is_whitespace() {
+                    end = i;
+                    break
+                }
+                chars.next();
+            }
+        }
+        tokens.push(Token { column : column + 1, text : &line[start..end] });
+    }
+    Ok(tokens)
+}
+
+fn unquote(text : &str) -> String {
+    String::from(text.strip_prefix('"').and_then(|t| t.strip_suffix('"')).unwrap_or(text))
+}
+
+pub fn make_pajek_string<T>(graph : &Graph<T>) -> String
+    where T : Clone + Eq + Hash + Display
+{
+    let mut pajek = Vec::new();
+    write_pajek(graph, &mut pajek).unwrap();  // Writing to a Vec can't fail
+    String::from_utf8(pajek).unwrap()
+}
+
+pub fn to_pajek_file<T>(graph : &Graph<T>, filename : &str) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display
+{
+    write_atomically(filename, |writer| write_pajek(graph, writer))
+}
+
+// Labels are always quoted, so can't contain a '"'. If the graph has a "weight"
+// attribute, every arc and edge is given a weight, defaulting to 1.
+pub fn write_pajek<T, W>(graph : &Graph<T>, mut writer : W) -> io::Result<()>
+    where T : Clone + Eq + Hash + Display, W : Write
+{
+    writeln!(writer, "*Vertices {}", graph.number_of_vertices())?;
+    for (index, label) in graph.nodes.iter().enumerate() {
+        let label = label.to_string();
+        if label.contains('"') {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("the Pajek label {} can't contain '\"'", label)))
+        }
+        writeln!(writer, "{} \"{}\"", index + 1, label)?;
+    }
+
+    let weighted = graph.edge_attributes.attribute_type("weight").is_some();
+    let mut arcs = Vec::new();
+    let mut edges = Vec::new();
+    for source in 0..graph.number_of_vertices() {
+        for (&dest, &edge) in graph.adjacency_list[source].iter().zip(graph.adjacency_edges[source].iter()) {
+            // An arc whose reverse has the same EdgeIndex is half of an edge.
+            let reversed = source != dest && graph.adjacency_list[dest].iter().zip(graph.adjacency_edges[dest].iter())
+                                                                     .any(|(&d, &e)| d == source && e == edge);
+            if !graph.is_directed() || reversed {
+                if dest >= source { edges.push((source, dest, edge)) }
+            } else {
+                arcs.push((source, dest, edge));
+            }
+        }
+    }
+    for (heading, lines) in [("*Arcs", arcs), ("*Edges", edges)].iter() {
+        if lines.is_empty() { continue }
+        writeln!(writer, "{}", heading)?;
+        for &(source, dest, edge) in lines {
+            match graph.edge_attributes.get(edge, "weight") {
+                Some(weight) => writeln!(writer, "{} {} {}", source + 1, dest + 1, weight)?,
+                None if weighted => writeln!(writer, "{} {} 1", source + 1, dest + 1)?,
+                None => writeln!(writer, "{} {}", source + 1, dest + 1)?,
+            }
+        }
+    }
+    Ok(())
+}
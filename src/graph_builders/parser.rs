@@ -0,0 +1,902 @@
+// Parser for the native text graph format.
+//
+// Problems are reported as Diagnostics giving the line, column and section of
+// the file, along with what was expected and what was found instead. In strict
+// mode parsing stops at the first error; in lenient mode every line is checked
+// and all of the problems are reported together. Warnings don't stop a file from
+// loading. A key from a newer version of the format is one: it's skipped, along
+// with the lines after it up to the next known key, wherever in the file it is.
+
+use super::super::{GraphConfig, Metadata, ParallelEdgePolicy, SelfLoopPolicy};
+use super::super::attributes::{is_attribute_name, AttributeType, AttributeValue};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+use std::io;
+use std::io::prelude::*;
+use std::str::FromStr;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ParseMode {
+    Strict,     // Stop at the first error
+    Lenient,    // Report every error in the file
+}
+
+// How the edges section refers to vertices.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum EdgeFormat {
+    Index,      // 0 1
+    Label,      // A -> B, A -- B or A B
+}
+
+// The part of the file that a line belongs to.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Section {
+    Header,
+    Edges,
+    Nodes,
+    NodeAttributes,
+    EdgeAttributes,
+    Skipped,                // After a key this version doesn't know, up to the next one it does
+    Other (&'static str),   // A part of a file in another format, such as a DOT graph
+}
+
+// The newest version of the format that this parser understands.
+pub const FORMAT_VERSION : u32 = 1;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Severity {
+    Error,
+    Warning,    // Reported, but the file can still be loaded
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Diagnostic {
+    pub line     : usize,   // Counting from 1
+    pub column   : usize,   // Counting from 1, in characters
+    pub section  : Section,
+    pub expected : String,
+    pub found    : String,
+    pub severity : Severity,
+}
+
+#[derive(Debug)]
+pub enum ParseError {
+    Io      (io::Error),
+    Invalid (Vec<Diagnostic>),
+}
+
+// Everything read from a graph file, along with where it was found so that
+// later checks can refer back to the file.
+pub struct ParsedFile<T> {
+    pub number_of_vertices   : Option<usize>,   // Only None if there are diagnostics
+    pub directed             : bool,
+    pub config               : GraphConfig,
+    pub metadata             : Metadata,
+    pub edges                : Vec<(usize, usize)>,
+    pub nodes                : Vec<T>,
+    // (name, type, default) for each declared attribute
+    pub node_attribute_types : Vec<(String, AttributeType, Option<AttributeValue>)>,
+    pub edge_attribute_types : Vec<(String, AttributeType, Option<AttributeValue>)>,
+    // (node index or position in the edges section, name, value)
+    pub node_attributes      : Vec<(usize, String, AttributeValue)>,
+    pub edge_attributes      : Vec<(usize, String, AttributeValue)>,
+    pub locations            : Locations,
+    // Problems found while parsing. Lines with problems are left out of the above.
+    pub diagnostics          : Vec<Diagnostic>,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct Location {
+    pub line   : usize,
+    pub column : usize,
+}
+
+#[derive(Default)]
+pub struct Locations {
+    pub number_of_vertices : Location,
+    pub nodes_section      : Option<Location>,
+    pub end_of_file        : Location,
+    pub edges              : Vec<[Location; 2]>,   // Of each endpoint
+    pub nodes              : Vec<Location>,
+    pub node_attributes    : Vec<Location>,        // Of the node index
+    pub edge_attributes    : Vec<Location>,        // Of the edge position
+}
+
+impl fmt::Display for Section {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match *self {
+            Section::Header         => "header",
+            Section::Edges          => "edges:",
+            Section::Nodes          => "nodes:",
+            Section::NodeAttributes => "node_attributes:",
+            Section::EdgeAttributes => "edge_attributes:",
+            Section::Skipped        => "a skipped section",
+            Section::Other(name)    => name,
+        })
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        if self.severity == Severity::Warning {
+            write!(f, "warning: ")?;
+        }
+        write!(f, "line {}, column {} (in {}): expected {}, found {}",
+               self.line, self.column, self.section, self.expected, self.found)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseError::Io(ref e) => write!(f, "{}", e),
+            ParseError::Invalid(ref diagnostics) => {
+                for (i, d) in diagnostics.iter().enumerate() {
+                    if i > 0 { writeln!(f)? }
+                    write!(f, "{}", d)?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl From<io::Error> for ParseError {
+    fn from(e : io::Error) -> ParseError {
+        ParseError::Io(e)
+    }
+}
+
+impl ParseError {
+    // The diagnostics, if this was a problem with the contents of the file.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        match *self {
+            ParseError::Io(_) => &[],
+            ParseError::Invalid(ref diagnostics) => diagnostics,
+        }
+    }
+}
+
+// Returns whatever could be parsed. Problems with the contents of the file are
+// returned as diagnostics, rather than as an error.
+pub fn parse<T, R>(reader : R, mode : ParseMode) -> io::Result<ParsedFile<T>>
+    where T : FromStr + Clone + Eq + Hash, R : BufRead
+{
+    let mut parser = Parser::new(mode);
+    for line in reader.lines() {
+        let line = line?;
+        parser.parse_line(&line);
+        if parser.should_stop() { break }
+    }
+    Ok(parser.finish())
+}
+
+// A whitespace-separated word on a line, or a quoted string.
+#[derive(Clone, Copy)]
+struct Token<'a> {
+    column : usize,
+    start  : usize,   // Byte offset into the line
+    text   : &'a str, // Including the quotes, if quoted
+    quoted : bool,
+}
+
+// A problem found while reading a line: (column, expected, found)
+type LineError = (usize, String, String);
+
+struct AttributeAssignment {
+    line       : usize,
+    column     : usize,
+    section    : Section,
+    key        : usize,
+    key_column : usize,
+    name       : String,
+    value      : String,
+}
+
+struct Parser<T> {
+    mode                 : ParseMode,
+    diagnostics          : Vec<Diagnostic>,
+    line                 : usize,
+    section              : Section,
+    // The section that an unknown key interrupted, to go back to at the next known one.
+    resumed_section      : Section,
+    number_of_vertices   : Option<usize>,
+    directed             : Option<bool>,
+    parallel_edges       : Option<ParallelEdgePolicy>,
+    self_loops           : Option<SelfLoopPolicy>,
+    edge_format          : Option<EdgeFormat>,
+    format_version       : Option<u32>,
+    metadata             : Metadata,
+    edges                : Vec<(usize, usize)>,
+    // Resolved to indices once the whole file has been read.
+    labelled_edges       : Vec<(String, String, [Location; 2])>,
+    nodes                : Vec<T>,
+    node_attribute_types : Vec<(String, AttributeType, Option<AttributeValue>)>,
+    edge_attribute_types : Vec<(String, AttributeType, Option<AttributeValue>)>,
+    // Checked against the declarations once the whole file has been read.
+    assignments          : Vec<AttributeAssignment>,
+    locations            : Locations,
+}
+
+// Keys that change which section we're in.
+const SECTION_KEYS : [&str; 4] = ["edges", "nodes", "node_attributes", "edge_attributes"];
+// Keys that take a value.
+const VALUE_KEYS : [&str; 10] = ["format_version", "name", "description", "number_of_vertices", "directed",
+                                 "parallel_edges", "self_loops", "edge_format", "node_attribute", "edge_attribute"];
+
+impl<T> Parser<T> where T : FromStr + Clone + Eq + Hash {
+
+    #[allow(clippy::redundant_field_names)]
+    fn new(mode : ParseMode) -> Parser<T> {
+        Parser { mode                 : mode,
+                 diagnostics          : Vec::new(),
+                 line                 : 0,
+                 section              : Section::Header,
+                 resumed_section      : Section::Header,
+                 number_of_vertices   : None,
+                 directed             : None,
+                 parallel_edges       : None,
+                 self_loops           : None,
+                 edge_format          : None,
+                 format_version       : None,
+                 metadata             : Metadata::default(),
+                 edges                : Vec::new(),
+                 labelled_edges       : Vec::new(),
+                 nodes                : Vec::new(),
+                 node_attribute_types : Vec::new(),
+                 edge_attribute_types : Vec::new(),
+                 assignments          : Vec::new(),
+                 locations            : Locations::default() }
+    }
+
+    fn should_stop(&self) -> bool {
+        self.mode == ParseMode::Strict && self.diagnostics.iter().any(|d| d.severity == Severity::Error)
+    }
+
+    fn error<E, F>(&mut self, column : usize, expected : E, found : F) where E : Into<String>, F : Into<String> {
+        let (line, section) = (self.line, self.section);
+        self.error_at(line, column, section, expected, found);
+    }
+
+    fn error_at<E, F>(&mut self, line : usize, column : usize, section : Section, expected : E, found : F)
+        where E : Into<String>, F : Into<String> {
+        if !self.should_stop() {
+            self.diagnostics.push(Diagnostic { line, column, section,
+                                               expected : expected.into(), found : found.into(), severity : Severity::Error });
+        }
+    }
+
+    fn warning<E, F>(&mut self, column : usize, expected : E, found : F) where E : Into<String>, F : Into<String> {
+        if !self.should_stop() {
+            self.diagnostics.push(Diagnostic { line : self.line, column, section : self.section,
+                                               expected : expected.into(), found : found.into(), severity : Severity::Warning });
+        }
+    }
+
+    fn parse_line(&mut self, line : &str) {
+        self.line += 1;
+        // Strip out comments
+        let content = &line[..comment_start(line)];
+        let key = split_key(content);
+        let is_known = key.is_some_and(|(key, _)| SECTION_KEYS.contains(&key) || VALUE_KEYS.contains(&key));
+        if self.section == Section::Skipped && !is_known { return }
+        let tokens = match tokenize(content, 0) {
+            Ok(tokens) => tokens,
+            Err((column, expected, found)) => return self.error(column, expected, found),
+        };
+        if tokens.is_empty() { return }
+
+        if let Some((key, value_start)) = key {
+            if is_known {
+                if self.section == Section::Skipped { self.section = self.resumed_section }
+                // The key itself can't contain quotes, so this can't fail if the whole line didn't.
+                let value_tokens = tokenize(content, value_start).unwrap();
+                let end_column = column_of(content, content.len());
+                self.parse_key(key, content, &value_tokens, end_column);
+            } else {
+                // Perhaps from a newer version of the format, so it's skipped rather than
+                // rejected, along with anything after it up to the next known key.
+                self.warning(tokens[0].column, "a known key such as number_of_vertices:", format!("{}:", key));
+                self.resumed_section = self.section;
+                self.section = Section::Skipped;
+            }
+            return
+        }
+
+        let end_column = column_of(content, content.len());
+        match self.section {
+            Section::Header | Section::Skipped | Section::Other(_) => {
+                self.error(tokens[0].column, "a key such as number_of_vertices:", quote_found(tokens[0].text))
+            },
+            Section::Edges if self.edge_format == Some(EdgeFormat::Label) => self.parse_labelled_edge(&tokens, end_column),
+            Section::Edges => self.parse_edge(&tokens, end_column),
+            Section::Nodes => self.parse_node(&tokens),
+            Section::NodeAttributes | Section::EdgeAttributes => self.parse_assignment(content, &tokens, end_column),
+        }
+    }
+
+    fn parse_key(&mut self, key : &str, content : &str, tokens : &[Token], end_column : usize) {
+        match key {
+            "edges" | "nodes" | "node_attributes" | "edge_attributes" => {
+                self.section = match key {
+                    "edges" => Section::Edges,
+                    "nodes" => Section::Nodes,
+                    "node_attributes" => Section::NodeAttributes,
+                    _ => Section::EdgeAttributes,
+                };
+                if self.section == Section::Nodes && self.locations.nodes_section.is_none() {
+                    self.locations.nodes_section = Some(Location { line : self.line, column : 1 });
+                }
+                if let Some(t) = tokens.first() {
+                    self.error(t.column, format!("end of line after {}:", key), quote_found(t.text));
+                }
+            },
+            "format_version" => {
+                if let Some(t) = self.single_value(key, tokens, end_column) {
+                    match t.text.parse::<u32>() {
+                        Ok(_) if self.format_version.is_some() =>
+                            self.error(t.column, "format_version to be given once", "a second format_version"),
+                        Ok(v) if v >= 1 => {
+                            self.format_version = Some(v);
+                            if v > FORMAT_VERSION {
+                                self.warning(t.column, format!("format_version {} or earlier", FORMAT_VERSION), format!("format_version {}", v));
+                            }
+                        },
+                        _ => self.error(t.column, "a format version such as 1", quote_found(t.text)),
+                    }
+                }
+            },
+            "name" | "description" => {
+                if tokens.is_empty() {
+                    return self.error(end_column, format!("a value for {}", key), "end of line")
+                }
+                let given = if key == "name" { self.metadata.name.is_some() } else { self.metadata.description.is_some() };
+                if given {
+                    return self.error(tokens[0].column, format!("{} to be given once", key), format!("a second {}", key))
+                }
+                let value = self.rest_of_line(content, tokens, 0);
+                if key == "name" { self.metadata.name = value } else { self.metadata.description = value }
+            },
+            "number_of_vertices" => {
+                if let Some(t) = self.single_value(key, tokens, end_column) {
+                    match t.text.parse::<usize>() {
+                        Ok(_) if self.number_of_vertices.is_some() =>
+                            self.error(t.column, "number_of_vertices to be given once", "a second number_of_vertices"),
+                        Ok(n) => {
+                            self.number_of_vertices = Some(n);
+                            self.locations.number_of_vertices = Location { line : self.line, column : t.column };
+                        },
+                        Err(_) => self.error(t.column, "a number of vertices", quote_found(t.text)),
+                    }
+                }
+            },
+            "directed" => {
+                if let Some(t) = self.single_value(key, tokens, end_column) {
+                    match t.text.parse::<bool>() {
+                        Ok(_) if self.directed.is_some() =>
+                            self.error(t.column, "directed to be given once", "a second directed"),
+                        Ok(d) => self.directed = Some(d),
+                        Err(_) => self.error(t.column, "true or false", quote_found(t.text)),
+                    }
+                }
+            },
+            "parallel_edges" => {
+                if let Some(t) = self.single_value(key, tokens, end_column) {
+                    let policy = match t.text {
+                        "reject" => Some(ParallelEdgePolicy::Reject),
+                        "merge"  => Some(ParallelEdgePolicy::Merge),
+                        "keep"   => Some(ParallelEdgePolicy::Keep),
+                        _ => None,
+                    };
+                    match policy {
+                        Some(_) if self.parallel_edges.is_some() =>
+                            self.error(t.column, "parallel_edges to be given once", "a second parallel_edges"),
+                        Some(p) => self.parallel_edges = Some(p),
+                        None => self.error(t.column, "reject, merge or keep", quote_found(t.text)),
+                    }
+                }
+            },
+            "self_loops" => {
+                if let Some(t) = self.single_value(key, tokens, end_column) {
+                    let policy = match t.text {
+                        "reject" => Some(SelfLoopPolicy::Reject),
+                        "allow"  => Some(SelfLoopPolicy::Allow),
+                        _ => None,
+                    };
+                    match policy {
+                        Some(_) if self.self_loops.is_some() =>
+                            self.error(t.column, "self_loops to be given once", "a second self_loops"),
+                        Some(p) => self.self_loops = Some(p),
+                        None => self.error(t.column, "reject or allow", quote_found(t.text)),
+                    }
+                }
+            },
+            "edge_format" => {
+                if let Some(t) = self.single_value(key, tokens, end_column) {
+                    let format = match t.text {
+                        "index" => Some(EdgeFormat::Index),
+                        "label" => Some(EdgeFormat::Label),
+                        _ => None,
+                    };
+                    match format {
+                        Some(_) if self.edge_format.is_some() =>
+                            self.error(t.column, "edge_format to be given once", "a second edge_format"),
+                        Some(_) if !self.edges.is_empty() || !self.labelled_edges.is_empty() =>
+                            self.error(t.column, "edge_format to be given before any edges", "edge_format after edges"),
+                        Some(f) => self.edge_format = Some(f),
+                        None => self.error(t.column, "index or label", quote_found(t.text)),
+                    }
+                }
+            },
+            _ => self.parse_declaration(key, content, tokens, end_column),
+        }
+    }
+
+    // The one and only value token after a key.
+    fn single_value<'a>(&mut self, key : &str, tokens : &[Token<'a>], end_column : usize) -> Option<Token<'a>> {
+        match tokens.len() {
+            0 => {
+                self.error(end_column, format!("a value for {}", key), "end of line");
+                None
+            },
+            1 => Some(tokens[0]),
+            _ => {
+                self.error(tokens[1].column, "end of line", quote_found(tokens[1].text));
+                None
+            },
+        }
+    }
+
+    // node_attribute: name type [default]
+    fn parse_declaration(&mut self, key : &str, content : &str, tokens : &[Token], end_column : usize) {
+        if tokens.len() < 2 {
+            let what = if tokens.is_empty() { "an attribute name" } else { "an attribute type" };
+            self.error(end_column, what, "end of line");
+            return
+        }
+        if !is_attribute_name(tokens[0].text) {
+            self.error(tokens[0].column, "an attribute name made of letters, digits and '_'", quote_found(tokens[0].text));
+            return
+        }
+        let attribute_type = match tokens[1].text.parse::<AttributeType>() {
+            Ok(t) => t,
+            Err(_) => {
+                self.error(tokens[1].column, "bool, int, float or str", quote_found(tokens[1].text));
+                return
+            },
+        };
+        let default = match tokens.get(2) {
+            None => None,
+            Some(t) => {
+                let text = match self.rest_of_line(content, tokens, 2) {
+                    Some(text) => text,
+                    None => return,
+                };
+                match AttributeValue::parse(&text, attribute_type) {
+                    Some(value) => Some(value),
+                    None => {
+                        self.error(t.column, format!("a default value of type {}", attribute_type), quote_found(&text));
+                        return
+                    },
+                }
+            },
+        };
+        let declarations = if key == "node_attribute" { &self.node_attribute_types } else { &self.edge_attribute_types };
+        if declarations.iter().any(|d| d.0 == tokens[0].text) {
+            self.error(tokens[0].column, format!("each {} to be declared once", key), format!("a second declaration of {}", tokens[0].text));
+            return
+        }
+        let declaration = (String::from(tokens[0].text), attribute_type, default);
+        if key == "node_attribute" {
+            self.node_attribute_types.push(declaration)
+        } else {
+            self.edge_attribute_types.push(declaration)
+        }
+    }
+
+    fn parse_edge(&mut self, tokens : &[Token], end_column : usize) {
+        let mut endpoints = [0; 2];
+        for (i, endpoint) in endpoints.iter_mut().enumerate() {
+            match tokens.get(i) {
+                None => {
+                    self.error(end_column, "a destination vertex index", "end of line");
+                    return
+                },
+                Some(t) => match t.text.parse::<usize>() {
+                    Ok(v) => *endpoint = v,
+                    Err(_) => {
+                        self.error(t.column, "a vertex index", quote_found(t.text));
+                        return
+                    },
+                },
+            }
+        }
+        if let Some(t) = tokens.get(2) {
+            self.error(t.column, "end of line", quote_found(t.text));
+            return
+        }
+        self.edges.push((endpoints[0], endpoints[1]));
+        self.locations.edges.push([Location { line : self.line, column : tokens[0].column },
+                                   Location { line : self.line, column : tokens[1].column }]);
+    }
+
+    // A -> B, A -- B or just A B. Which vertices the labels refer to is worked
+    // out once the nodes: section, if any, has been read.
+    fn parse_labelled_edge(&mut self, tokens : &[Token], end_column : usize) {
+        let is_arrow = |t : &Token| !t.quoted && (t.text == "->" || t.text == "--");
+        let endpoints = match tokens.len() {
+            1 => {
+                self.error(end_column, "a destination label", "end of line");
+                return
+            },
+            2 if is_arrow(&tokens[1]) => {
+                self.error(end_column, "a destination label", "end of line");
+                return
+            },
+            2 => [tokens[0], tokens[1]],
+            3 if is_arrow(&tokens[1]) => [tokens[0], tokens[2]],
+            3 => {
+                self.error(tokens[1].column, "-> or -- between the labels", quote_found(tokens[1].text));
+                return
+            },
+            _ => {
+                let extra = if is_arrow(&tokens[1]) { tokens[3] } else { tokens[2] };
+                self.error(extra.column, "end of line", quote_found(extra.text));
+                return
+            },
+        };
+        let mut labels = Vec::with_capacity(2);
+        for t in &endpoints {
+            match unquote(t) {
+                Ok(label) => labels.push(label),
+                Err((column, expected, found)) => return self.error(column, expected, found),
+            }
+        }
+        let dest = labels.pop().unwrap();
+        let source = labels.pop().unwrap();
+        self.labelled_edges.push((source, dest, [Location { line : self.line, column : endpoints[0].column },
+                                                 Location { line : self.line, column : endpoints[1].column }]));
+    }
+
+    // Turns labelled edges into indices. Labels refer to the nodes: section if
+    // there is one; otherwise the nodes are taken from the edges, in the order
+    // in which they first appear.
+    fn resolve_labelled_edges(&mut self) {
+        let infer_nodes = self.locations.nodes_section.is_none();
+        let mut indices : HashMap<T, usize> = HashMap::new();
+        for (i, node) in self.nodes.iter().enumerate() {
+            indices.entry(node.clone()).or_insert(i);
+        }
+
+        let labelled_edges = self.labelled_edges.split_off(0);
+        'edges: for (source, dest, locations) in labelled_edges {
+            let mut endpoints = [0; 2];
+            for (i, label) in [source, dest].iter().enumerate() {
+                let location = locations[i];
+                let node = match label.parse::<T>() {
+                    Ok(node) => node,
+                    Err(_) => {
+                        self.error_at(location.line, location.column, Section::Edges, "a valid node label", quote_found(label));
+                        continue 'edges
+                    },
+                };
+                endpoints[i] = match indices.get(&node) {
+                    Some(&index) => index,
+                    None if infer_nodes => {
+                        let index = self.nodes.len();
+                        indices.insert(node.clone(), index);
+                        self.nodes.push(node);
+                        self.locations.nodes.push(location);
+                        index
+                    },
+                    None => {
+                        self.error_at(location.line, location.column, Section::Edges, "a label listed in the nodes: section", quote_found(label));
+                        continue 'edges
+                    },
+                };
+            }
+            self.edges.push((endpoints[0], endpoints[1]));
+            self.locations.edges.push(locations);
+        }
+
+        match self.number_of_vertices {
+            None => self.number_of_vertices = Some(self.nodes.len()),
+            Some(n) if infer_nodes && n != self.nodes.len() => {
+                let location = self.locations.number_of_vertices;
+                let found = self.nodes.len();
+                self.error_at(location.line, location.column, Section::Header,
+                              format!("number_of_vertices to match the {} labels found in the edges", found), format!("{}", n));
+                self.number_of_vertices = Some(found);
+            },
+            Some(_) => (),
+        }
+    }
+
+    fn parse_node(&mut self, tokens : &[Token]) {
+        let t = tokens[0];
+        if let Some(extra) = tokens.get(1) {
+            self.error(extra.column, "end of line", quote_found(extra.text));
+            return
+        }
+        let label = match unquote(&t) {
+            Ok(label) => label,
+            Err((column, expected, found)) => return self.error(column, expected, found),
+        };
+        match label.parse::<T>() {
+            Ok(node) => {
+                self.nodes.push(node);
+                self.locations.nodes.push(Location { line : self.line, column : t.column });
+            },
+            Err(_) => self.error(t.column, "a valid node label", quote_found(&label)),
+        }
+    }
+
+    // index name value
+    #[allow(clippy::redundant_field_names)]
+    fn parse_assignment(&mut self, content : &str, tokens : &[Token], end_column : usize) {
+        let key = match tokens[0].text.parse::<usize>() {
+            Ok(k) => k,
+            Err(_) => {
+                let what = if self.section == Section::NodeAttributes { "a node index" } else { "an edge position" };
+                self.error(tokens[0].column, what, quote_found(tokens[0].text));
+                return
+            },
+        };
+        if tokens.len() < 3 {
+            let what = if tokens.len() < 2 { "an attribute name" } else { "an attribute value" };
+            self.error(end_column, what, "end of line");
+            return
+        }
+        let value = match self.rest_of_line(content, tokens, 2) {
+            Some(value) => value,
+            None => return,
+        };
+        self.assignments.push(AttributeAssignment { line       : self.line,
+                                                    column     : tokens[1].column,
+                                                    section    : self.section,
+                                                    key        : key,
+                                                    key_column : tokens[0].column,
+                                                    name       : String::from(tokens[1].text),
+                                                    value      : value });
+    }
+
+    // A value that runs to the end of the line, starting at the given token.
+    // A quoted value must be the last thing on the line; otherwise the value is
+    // taken literally, including any spaces within it.
+    fn rest_of_line(&mut self, content : &str, tokens : &[Token], first : usize) -> Option<String> {
+        let t = tokens[first];
+        if !t.quoted {
+            return Some(String::from(content[t.start..].trim()))
+        }
+        if let Some(extra) = tokens.get(first + 1) {
+            self.error(extra.column, "end of line after a quoted value", quote_found(extra.text));
+            return None
+        }
+        match unquote(&t) {
+            Ok(value) => Some(value),
+            Err((column, expected, found)) => {
+                self.error(column, expected, found);
+                None
+            },
+        }
+    }
+
+    #[allow(clippy::redundant_field_names)]
+    fn finish(mut self) -> ParsedFile<T> {
+        self.locations.end_of_file = Location { line : self.line + 1, column : 1 };
+        if self.edge_format == Some(EdgeFormat::Label) {
+            self.resolve_labelled_edges();
+        }
+        if self.number_of_vertices.is_none() {
+            let line = self.line + 1;
+            self.error_at(line, 1, Section::Header, "number_of_vertices:", "end of file");
+        }
+
+        let assignments = self.assignments.split_off(0);
+        let mut node_attributes = Vec::new();
+        let mut edge_attributes = Vec::new();
+        for a in assignments {
+            let declarations = if a.section == Section::NodeAttributes { &self.node_attribute_types } else { &self.edge_attribute_types };
+            let attribute_type = declarations.iter().find(|d| d.0 == a.name).map(|d| d.1);
+            match attribute_type {
+                None => {
+                    let kind = if a.section == Section::NodeAttributes { "node" } else { "edge" };
+                    self.error_at(a.line, a.column, a.section, format!("a declared {} attribute", kind), quote_found(&a.name));
+                },
+                Some(t) => match AttributeValue::parse(&a.value, t) {
+                    Some(value) => {
+                        let location = Location { line : a.line, column : a.key_column };
+                        if a.section == Section::NodeAttributes {
+                            node_attributes.push((a.key, a.name, value));
+                            self.locations.node_attributes.push(location);
+                        } else {
+                            edge_attributes.push((a.key, a.name, value));
+                            self.locations.edge_attributes.push(location);
+                        }
+                    },
+                    None => {
+                        let column = a.column + a.name.chars().count() + 1;
+                        self.error_at(a.line, column, a.section, format!("a value of type {}", t), quote_found(&a.value));
+                    },
+                },
+            }
+        }
+
+        let default_config = GraphConfig::default();
+        let config = GraphConfig { parallel_edges : self.parallel_edges.unwrap_or(default_config.parallel_edges),
+                                   self_loops     : self.self_loops.unwrap_or(default_config.self_loops) };
+        ParsedFile { number_of_vertices   : self.number_of_vertices,
+                     directed             : self.directed.unwrap_or(true),
+                     config               : config,
+                     metadata             : self.metadata,
+                     edges                : self.edges,
+                     nodes                : self.nodes,
+                     node_attribute_types : self.node_attribute_types,
+                     edge_attribute_types : self.edge_attribute_types,
+                     node_attributes      : node_attributes,
+                     edge_attributes      : edge_attributes,
+                     locations            : self.locations,
+                     diagnostics          : self.diagnostics }
+    }
+}
+
+// The position of the start of any comment on the line, or the end of the
+// line if there isn't one. A "//" inside a quoted string doesn't count.
+fn comment_start(line : &str) -> usize {
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let mut previous_slash = false;
+    for (i, c) in line.char_indices() {
+        if in_quotes {
+            if escaped { escaped = false }
+            else if c == '\\' { escaped = true }
+            else if c == '"' { in_quotes = false }
+        } else if c == '"' {
+            in_quotes = true;
+        } else if c == '/' && previous_slash {
+            return i - 1
+        }
+        previous_slash = !in_quotes && c == '/';
+    }
+    line.len()
+}
+
+// Splits the part of a line from the given byte position onwards into tokens.
+// A token is either a run of non-whitespace characters, or a quoted string,
+// which may contain whitespace and backslash escapes.
+fn tokenize(line : &str, from : usize) -> Result<Vec<Token<'_>>, LineError> {
+    let mut tokens = Vec::new();
+    let mut chars = line[from..].char_indices().map(|(i, c)| (from + i, c)).peekable();
+    while let Some((start, c)) = chars.next() {
+        if c.is_whitespace() { continue }
+        let mut end = line.len();
+        if c == '"' {
+            let mut escaped = false;
+            let mut closed = false;
+            for (i, c) in &mut chars {
+                if escaped { escaped = false }
+                else if c == '\\' { escaped = true }
+                else if c == '"' {
+                    end = i + 1;
+                    closed = true;
+                    break
+                }
+            }
+            if !closed {
+                return Err((column_of(line, start), String::from("a closing '\"'"), String::from("end of line")))
+            }
+            if let Some(&(i, c)) = chars.peek() {
+                if !c.is_whitespace() {
+                    return Err((column_of(line, i), String::from("whitespace after a closing '\"'"), format!("'{}'", c)))
+                }
+            }
+        } else {
+            while let Some(&(i, c)) = chars.peek() {
+                if c.is_whitespace() {
+                    end = i;
+                    break
+                }
+                chars.next();
+            }
+        }
+        tokens.push(Token { column : column_of(line, start), start, text : &line[start..end], quoted : c == '"' });
+    }
+    Ok(tokens)
+}
+
+// The text of a token, with any quotes removed and escapes decoded.
+fn unquote(token : &Token) -> Result<String, LineError> {
+    if !token.quoted {
+        return Ok(String::from(token.text))
+    }
+    let inner = &token.text[1..(token.text.len() - 1)];
+    let mut text = String::new();
+    let mut chars = inner.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue
+        }
+        let column = token.column + 1 + inner[..i].chars().count();
+        let bad_escape = |found : String| -> LineError {
+            (column, String::from("an escape sequence such as \\\", \\\\, \\n or \\u{..}"), found)
+        };
+        match chars.next().map(|(_, c)| c) {
+            Some('"')  => text.push('"'),
+            Some('\\') => text.push('\\'),
+            Some('n')  => text.push('\n'),
+            Some('t')  => text.push('\t'),
+            Some('r')  => text.push('\r'),
+            Some('u')  => {
+                let rest = &inner[(i + 2)..];
+                let code = rest.strip_prefix('{').and_then(|braced| braced.find('}').map(|end| &braced[..end]));
+                match code.and_then(|hex| u32::from_str_radix(hex, 16).ok()).and_then(::std::char::from_u32) {
+                    Some(decoded) => {
+                        text.push(decoded);
+                        // Skip over the braces and hex digits
+                        for _ in 0..(code.unwrap().len() + 2) { chars.next(); }
+                    },
+                    None => return Err(bad_escape(format!("\\u{}", rest.chars().take(8).collect::<String>()))),
+                }
+            },
+            Some(other) => return Err(bad_escape(format!("\\{}", other))),
+            None => return Err(bad_escape(String::from("\\"))),
+        }
+    }
+    Ok(text)
+}
+
+// Whether text must be quoted to be read back as a single token exactly as written.
+fn needs_quoting(text : &str) -> bool {
+    text.is_empty()
+        || text.starts_with('"')
+        || text.contains("//")
+        || text.chars().any(|c| c.is_whitespace() || c.is_control())
+        || split_key(text).is_some()
+}
+
+fn quote(text : &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"'  => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+pub fn quote_if_needed(text : &str) -> String {
+    if needs_quoting(text) { quote(text) } else { String::from(text) }
+}
+
+// How text that was found in the file is shown in a diagnostic.
+fn quote_found(text : &str) -> String {
+    if text.starts_with('"') { String::from(text) } else { format!("\"{}\"", text) }
+}
+
+// The column of a byte position within a line.
+fn column_of(line : &str, position : usize) -> usize {
+    line[..position].chars().count() + 1
+}
+
+// Splits "key: value" into the key and the byte position of the value. Only
+// lower-case keys followed by a single ':' count, so that labels such as
+// std::vec aren't mistaken for keys.
+fn split_key(content : &str) -> Option<(&str, usize)> {
+    let colon = content.find(':')?;
+    if content[(colon + 1)..].starts_with(':') { return None }
+    let key = content[..colon].trim();
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_lowercase() || c == '_') { return None }
+    Some((key, colon + 1))
+}
@@ -0,0 +1,105 @@
+// Consistency checks on a parsed graph file, beyond the syntax of individual
+// lines: edges must refer to vertices that exist and respect the file's edge
+// policies, labels must match number_of_vertices, and attributes must refer
+// to nodes and edges that exist.
+
+use super::parser::{Diagnostic, Location, ParsedFile, Section, Severity};
+use super::super::{ParallelEdgePolicy, SelfLoopPolicy};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// Returns every problem found, in order of position in the file.
+// Labels are checked if expect_labels is set or the file has a nodes: section.
+// If report_duplicate_edges is set then edges listed more than once are reported
+// even when the file says that parallel edges should be merged.
+pub fn check<T>(parsed : &ParsedFile<T>, expect_labels : bool, report_duplicate_edges : bool) -> Vec<Diagnostic>
+    where T : Eq + Hash
+{
+    let mut diagnostics = Vec::new();
+    let n = match parsed.number_of_vertices {
+        Some(n) => n,
+        None => return diagnostics,   // Already reported by the parser
+    };
+    let locations = &parsed.locations;
+    let mut report = |location : Location, section : Section, expected : String, found : String| {
+        diagnostics.push(Diagnostic { line : location.line, column : location.column, section,
+                                      expected, found, severity : Severity::Error });
+    };
+
+    // Where each edge was first listed, keyed by its endpoints. An undirected edge
+    // may be listed in either direction.
+    let mut first_listed : HashMap<(usize, usize), usize> = HashMap::new();
+    for (&(source, dest), endpoint_locations) in parsed.edges.iter().zip(locations.edges.iter()) {
+        let mut in_range = true;
+        for &(vertex, location) in &[(source, endpoint_locations[0]), (dest, endpoint_locations[1])] {
+            if vertex >= n {
+                report(location, Section::Edges, format!("a vertex index less than {}", n), format!("{}", vertex));
+                in_range = false;
+            }
+        }
+        if !in_range { continue }
+
+        let line = endpoint_locations[0].line;
+        if source == dest && parsed.config.self_loops == SelfLoopPolicy::Reject {
+            report(endpoint_locations[0], Section::Edges, String::from("no self-loops, since self_loops is reject"),
+                   format!("a self-loop on vertex {}", source));
+        }
+
+        let key = if parsed.directed || source <= dest { (source, dest) } else { (dest, source) };
+        match first_listed.get(&key) {
+            Some(&first_line) => {
+                let rejected = parsed.config.parallel_edges == ParallelEdgePolicy::Reject;
+                let duplicate = report_duplicate_edges && parsed.config.parallel_edges == ParallelEdgePolicy::Merge;
+                if rejected || duplicate {
+                    let expected = if parsed.directed { "each edge to be listed once" }
+                                   else { "each undirected edge to be listed once, in either direction" };
+                    report(endpoint_locations[0], Section::Edges, String::from(expected),
+                           format!("a repeat of the edge on line {}", first_line));
+                }
+            },
+            None => { first_listed.insert(key, line); },
+        }
+    }
+
+    if expect_labels || !parsed.nodes.is_empty() {
+        let expected = format!("{} node labels, as given by number_of_vertices on line {}", n, locations.number_of_vertices.line);
+        for location in locations.nodes.iter().skip(n) {
+            report(*location, Section::Nodes, expected.clone(), String::from("an extra label"));
+        }
+        if parsed.nodes.len() < n {
+            // Point just past the last label, or at the end of the file if there's no nodes: section.
+            let location = match (locations.nodes.last(), locations.nodes_section) {
+                (Some(last), _) => Location { line : last.line + 1, column : 1 },
+                (None, Some(section)) => Location { line : section.line + 1, column : 1 },
+                (None, None) => locations.end_of_file,
+            };
+            let section = if locations.nodes_section.is_some() { Section::Nodes } else { Section::Header };
+            report(location, section, expected, format!("only {}", parsed.nodes.len()));
+        }
+
+        let mut first_labelled : HashMap<&T, usize> = HashMap::new();
+        for (node, location) in parsed.nodes.iter().zip(locations.nodes.iter()) {
+            match first_labelled.get(node) {
+                Some(&first_line) => report(*location, Section::Nodes, String::from("unique node labels"),
+                                            format!("a repeat of the label on line {}", first_line)),
+                None => { first_labelled.insert(node, location.line); },
+            }
+        }
+    }
+
+    for (&(node, _, _), location) in parsed.node_attributes.iter().zip(locations.node_attributes.iter()) {
+        if node >= n {
+            report(*location, Section::NodeAttributes, format!("a node index less than {}", n), format!("{}", node));
+        }
+    }
+    let number_of_edges = parsed.edges.len();
+    for (&(position, _, _), location) in parsed.edge_attributes.iter().zip(locations.edge_attributes.iter()) {
+        if position >= number_of_edges {
+            report(*location, Section::EdgeAttributes, format!("an edge position less than {}, the number of edges listed", number_of_edges),
+                   format!("{}", position));
+        }
+    }
+
+    diagnostics.sort_by_key(|d| (d.line, d.column));
+    diagnostics
+}
@@ -0,0 +1,298 @@
+// A small XML reader, with just enough of XML for GraphML: elements, attributes,
+// text, CDATA sections and the predefined and numeric character references.
+// Comments, processing instructions and doctype declarations are skipped.
+//
+// The document is checked for well-formed nesting, and problems are reported
+// as Diagnostics in the given section.
+
+use super::parser::{Diagnostic, Location, Section, Severity};
+
+pub enum Event {
+    Start { name : String, attributes : Vec<Attribute> },
+    End,
+    Text  (String),
+}
+
+pub struct Attribute {
+    pub name     : String,
+    pub value    : String,
+    pub location : Location,
+}
+
+// The events of the whole document, each with the location where it starts.
+// An empty element such as <node id="a"/> is followed by its own End event.
+pub fn parse(text : &str, section : Section) -> Result<Vec<(Event, Location)>, Diagnostic> {
+    let mut reader = Reader { chars : text.chars().collect(), position : 0, line : 1, column : 1, section };
+    reader.read_document()
+}
+
+struct Reader {
+    chars    : Vec<char>,
+    position : usize,
+    line     : usize,
+    column   : usize,
+    section  : Section,
+}
+
+impl Reader {
+
+    fn location(&self) -> Location {
+        Location { line : self.line, column : self.column }
+    }
+
+    fn error<X>(&self, location : Location, expected : &str, found : String) -> Result<X, Diagnostic> {
+        Err(Diagnostic { line : location.line, column : location.column, section : self.section,
+                         expected : String::from(expected), found, severity : Severity::Error })
+    }
+
+    // What's at the current position, for diagnostics.
+    fn found(&self) -> String {
+        match self.peek() {
+            Some(c) => format!("'{}'", c),
+            None => String::from("end of file"),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).cloned()
+    }
+
+    fn starts_with(&self, s : &str) -> bool {
+        s.chars().enumerate().all(|(i, c)| self.chars.get(self.position + i) == Some(&c))
+    }
+
+    fn advance(&mut self, n : usize) {
+        for _ in 0..n {
+            if let Some(c) = self.peek() {
+                if c == '\n' { self.line += 1; self.column = 1 } else { self.column += 1 }
+                self.position += 1;
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace()) { self.advance(1) }
+    }
+
+    // Skips to just after the terminator, which must appear before the end of the file.
+    fn skip_past(&mut self, terminator : &str, start : Location) -> Result<(), Diagnostic> {
+        while !self.starts_with(terminator) {
+            if self.peek().is_none() {
+                return self.error(start, &format!("a closing {}", terminator), String::from("end of file"))
+            }
+            self.advance(1);
+        }
+        self.advance(terminator.chars().count());
+        Ok(())
+    }
+
+    fn read_document(&mut self) -> Result<Vec<(Event, Location)>, Diagnostic> {
+        let mut events = Vec::new();
+        let mut open : Vec<(String, Location)> = Vec::new();
+        let mut seen_root = false;
+        while self.peek().is_some() {
+            let start = self.location();
+            if self.starts_with("<!--") {
+                self.skip_past("-->", start)?;
+            } else if self.starts_with("<?") {
+                self.skip_past("?>", start)?;
+            } else if self.starts_with("<![CDATA[") {
+                self.advance(9);
+                let from = self.position;
+                self.skip_past("]]>", start)?;
+                let text : String = self.chars[from..(self.position - 3)].iter().collect();
+                if open.is_empty() {
+                    return self.error(start, "an element", String::from("text outside the root element"))
+                }
+                events.push((Event::Text(text), start));
+            } else if self.starts_with("<!") {
+                self.skip_doctype(start)?;
+            } else if self.starts_with("</") {
+                self.advance(2);
+                let name = self.read_name()?;
+                self.skip_whitespace();
+                if self.peek() != Some('>') {
+                    let found = self.found();
+                    return self.error(self.location(), "'>'", found)
+                }
+                self.advance(1);
+                match open.pop() {
+                    Some((ref expected, _)) if *expected == name => events.push((Event::End, start)),
+                    Some((expected, _)) => return self.error(start, &format!("</{}>", expected), format!("</{}>", name)),
+                    None => return self.error(start, "end of file", format!("</{}>", name)),
+                }
+            } else if self.peek() == Some('<') {
+                if open.is_empty() && seen_root {
+                    return self.error(start, "end of file after the root element", String::from("another element"))
+                }
+                self.advance(1);
+                let (name, attributes, empty) = self.read_start_tag()?;
+                seen_root = true;
+                if !empty {
+                    open.push((name.clone(), start));
+                }
+                events.push((Event::Start { name, attributes }, start));
+                if empty {
+                    events.push((Event::End, start));
+                }
+            } else {
+                let text = self.read_text()?;
+                if open.is_empty() {
+                    if !text.trim().is_empty() {
+                        return self.error(start, "an element", String::from("text outside the root element"))
+                    }
+                } else {
+                    events.push((Event::Text(text), start));
+                }
+            }
+        }
+        if let Some((name, location)) = open.pop() {
+            return self.error(location, &format!("a closing </{}>", name), String::from("end of file"))
+        }
+        if !seen_root {
+            return self.error(self.location(), "a root element", String::from("end of file"))
+        }
+        Ok(events)
+    }
+
+    // <!DOCTYPE ...>, which may have an internal subset in brackets.
+    fn skip_doctype(&mut self, start : Location) -> Result<(), Diagnostic> {
+        let mut depth = 0;
+        loop {
+            match self.peek() {
+                None => return self.error(start, "a closing '>'", String::from("end of file")),
+                Some('[') => depth += 1,
+                Some(']') => depth -= 1,
+                Some('>') if depth == 0 => {
+                    self.advance(1);
+                    return Ok(())
+                },
+                _ => (),
+            }
+            self.advance(1);
+        }
+    }
+
+    fn read_name(&mut self) -> Result<String, Diagnostic> {
+        let from = self.position;
+        while self.peek().is_some_and(|c| c.is_alphanumeric() || "_:-.".contains(c)) { self.advance(1) }
+        if from == self.position {
+            let found = self.found();
+            return self.error(self.location(), "a name", found)
+        }
+        Ok(self.chars[from..self.position].iter().collect())
+    }
+
+    // Everything after the '<' of a start tag: (name, attributes, whether it's empty)
+    fn read_start_tag(&mut self) -> Result<(String, Vec<Attribute>, bool), Diagnostic> {
+        let name = self.read_name()?;
+        let mut attributes : Vec<Attribute> = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.starts_with("/>") {
+                self.advance(2);
+                return Ok((name, attributes, true))
+            }
+            if self.peek() == Some('>') {
+                self.advance(1);
+                return Ok((name, attributes, false))
+            }
+            let location = self.location();
+            let attribute_name = self.read_name()?;
+            self.skip_whitespace();
+            if self.peek() != Some('=') {
+                let found = self.found();
+                return self.error(self.location(), "'=' after the attribute name", found)
+            }
+            self.advance(1);
+            self.skip_whitespace();
+            let quote = match self.peek() {
+                Some(q) if q == '"' || q == '\'' => q,
+                _ => {
+                    let found = self.found();
+                    return self.error(self.location(), "a quoted attribute value", found)
+                },
+            };
+            let quote_location = self.location();
+            self.advance(1);
+            let mut value = String::new();
+            loop {
+                match self.peek() {
+                    None => return self.error(quote_location, &format!("a closing {}", quote), String::from("end of file")),
+                    Some(c) if c == quote => break,
+                    Some('&') => value.push_str(&self.read_reference()?),
+                    Some('<') => return self.error(self.location(), "no '<' in an attribute value", String::from("'<'")),
+                    Some(c) => {
+                        value.push(c);
+                        self.advance(1);
+                    },
+                }
+            }
+            self.advance(1);
+            if attributes.iter().any(|a| a.name == attribute_name) {
+                return self.error(location, "each attribute to be given once", format!("a second {}", attribute_name))
+            }
+            attributes.push(Attribute { name : attribute_name, value, location });
+        }
+    }
+
+    fn read_text(&mut self) -> Result<String, Diagnostic> {
+        let mut text = String::new();
+        while let Some(c) = self.peek() {
+            match c {
+                '<' => break,
+                '&' => text.push_str(&self.read_reference()?),
+                c => {
+                    text.push(c);
+                    self.advance(1);
+                },
+            }
+        }
+        Ok(text)
+    }
+
+    // A reference such as &amp; or &#233;, starting at the '&'.
+    fn read_reference(&mut self) -> Result<String, Diagnostic> {
+        let start = self.location();
+        self.advance(1);
+        let from = self.position;
+        while self.peek().is_some_and(|c| c != ';' && !c.is_whitespace() && c != '<' && c != '&') { self.advance(1) }
+        let name : String = self.chars[from..self.position].iter().collect();
+        if self.peek() != Some(';') {
+            return self.error(start, "a reference ending in ';', such as &amp;", format!("\"&{}\"", name))
+        }
+        self.advance(1);
+        let decoded = match name.as_str() {
+            "lt"   => Some('<'),
+            "gt"   => Some('>'),
+            "amp"  => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ if name.starts_with("#x") => u32::from_str_radix(&name[2..], 16).ok().and_then(::std::char::from_u32),
+            _ if name.starts_with('#') => name[1..].parse::<u32>().ok().and_then(::std::char::from_u32),
+            _ => None,
+        };
+        match decoded {
+            Some(c) => Ok(c.to_string()),
+            None => self.error(start, "a reference such as &amp; or &#233;", format!("\"&{};\"", name)),
+        }
+    }
+}
+
+// Escapes text for use in element content or a double-quoted attribute value.
+pub fn escape(text : &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '&'  => escaped.push_str("&amp;"),
+            '<'  => escaped.push_str("&lt;"),
+            '>'  => escaped.push_str("&gt;"),
+            '"'  => escaped.push_str("&quot;"),
+            '\n' => escaped.push_str("&#10;"),
+            '\r' => escaped.push_str("&#13;"),
+            '\t' => escaped.push_str("&#9;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,453 @@
+// A dense adjacency-matrix representation of a graph.
+//
+// This is intended for small, dense graphs (a few thousand vertices at most),
+// where the n^2 storage is affordable and we want O(1) edge existence tests.
+// Unweighted graphs are stored as a bit matrix, weighted graphs as a matrix of
+// optional values.
+//
+// Node attributes are keyed by node index, as in Graph. There is at most one edge
+// from each vertex to each other, so edge attributes are keyed by the pair of
+// vertices instead of an EdgeIndex: (source, dest), with the smaller index first
+// in an undirected graph. The weights of a weighted graph are the entries of the
+// matrix, not an attribute.
+
+use super::{EdgeError, Graph, DFSEdgeType, Metadata};
+use super::attributes::{AttributeStore, AttributeType, AttributeValue};
+use super::graph_builders;
+use super::traversal;
+use super::traversal::Traversable;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::hash::Hash;
+
+pub struct MatrixGraph<T> where T : Clone + Eq + Hash {
+    directed        : bool,
+    nodes           : Vec<T>,
+    node_indices    : HashMap<T, usize>,
+    matrix          : AdjacencyMatrix,
+    node_attributes : AttributeStore<usize>,
+    edge_attributes : AttributeStore<(usize, usize)>,
+    metadata        : Metadata,
+}
+
+enum AdjacencyMatrix {
+    Unweighted(BitMatrix),
+    Weighted(Vec<Option<f64>>),
+}
+
+// Row-major n x n bit matrix, with each row padded out to a whole number of words.
+#[derive(Clone)]
+struct BitMatrix {
+    words_per_row : usize,
+    words         : Vec<u64>,
+}
+
+impl BitMatrix {
+    fn new(size : usize) -> BitMatrix {
+        let words_per_row = size.div_ceil(64);
+        BitMatrix { words_per_row, words : vec![0; size * words_per_row] }
+    }
+
+    fn get(&self, row : usize, col : usize) -> bool {
+        self.words[row * self.words_per_row + col / 64] & (1 << (col % 64)) != 0
+    }
+
+    fn set(&mut self, row : usize, col : usize, value : bool) {
+        let word = &mut self.words[row * self.words_per_row + col / 64];
+        if value {
+            *word |= 1 << (col % 64);
+        } else {
+            *word &= !(1 << (col % 64));
+        }
+    }
+
+    fn row_count(&self, row : usize) -> usize {
+        let start = row * self.words_per_row;
+        self.words[start..(start + self.words_per_row)].iter()
+                                                       .map(|w| w.count_ones() as usize)
+                                                       .sum()
+    }
+}
+
+impl<T> MatrixGraph<T> where T : Clone + Eq + Hash {
+
+    pub fn unweighted(nodes : Vec<T>, directed : bool) -> MatrixGraph<T> {
+        let matrix = AdjacencyMatrix::Unweighted(BitMatrix::new(nodes.len()));
+        MatrixGraph::with_matrix(nodes, directed, matrix)
+    }
+
+    pub fn weighted(nodes : Vec<T>, directed : bool) -> MatrixGraph<T> {
+        let matrix = AdjacencyMatrix::Weighted(vec![None; nodes.len() * nodes.len()]);
+        MatrixGraph::with_matrix(nodes, directed, matrix)
+    }
+
+    fn with_matrix(nodes : Vec<T>, directed : bool, matrix : AdjacencyMatrix) -> MatrixGraph<T> {
+        let node_indices : HashMap<T, usize> =
+            nodes.iter()
+                 .cloned()
+                 .enumerate()
+                 .map(|(i, x)| { (x, i) })
+                 .collect();
+        MatrixGraph { directed, nodes, node_indices, matrix,
+                      node_attributes : AttributeStore::new(), edge_attributes : AttributeStore::new(), metadata : Metadata::default() }
+    }
+
+    pub fn number_of_vertices(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn is_weighted(&self) -> bool {
+        match self.matrix {
+            AdjacencyMatrix::Unweighted(_) => false,
+            AdjacencyMatrix::Weighted(_)   => true,
+        }
+    }
+
+    pub fn node_from_index(&self, index : usize) -> T {
+        if index < self.number_of_vertices() {
+            self.nodes[index].clone()
+        } else {
+            panic!("Called node_from_index({}) on a graph with only {} vertices!", index, self.number_of_vertices())
+        }
+    }
+
+    pub fn index_from_node(&self, node : T) -> usize {
+        match self.node_indices.get(&node) {
+            Some(i) => *i,
+            None => panic!("Node was not present in the graph."),
+        }
+    }
+
+    // Attributes of the nodes, keyed by node index.
+    pub fn node_attributes(&self) -> &AttributeStore<usize> {
+        &self.node_attributes
+    }
+
+    pub fn node_attributes_mut(&mut self) -> &mut AttributeStore<usize> {
+        &mut self.node_attributes
+    }
+
+    // Attributes of the edges, keyed by edge_key.
+    pub fn edge_attributes(&self) -> &AttributeStore<(usize, usize)> {
+        &self.edge_attributes
+    }
+
+    pub fn edge_attributes_mut(&mut self) -> &mut AttributeStore<(usize, usize)> {
+        &mut self.edge_attributes
+    }
+
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    pub fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+
+    // The key of the edge from source to dest in the edge attributes. Both
+    // directions of an undirected edge share a key.
+    pub fn edge_key(&self, source_index : usize, dest_index : usize) -> (usize, usize) {
+        if self.directed || source_index <= dest_index { (source_index, dest_index) } else { (dest_index, source_index) }
+    }
+
+    // For an undirected graph a self-loop contributes two to the degree of its
+    // vertex, as in Graph.
+    pub fn get_degree_from_index(&self, index : usize) -> usize {
+        self.check_indices(index, index);
+        let degree = match self.matrix {
+            AdjacencyMatrix::Unweighted(ref bits) => bits.row_count(index),
+            AdjacencyMatrix::Weighted(ref values) => {
+                let n = self.number_of_vertices();
+                values[(index * n)..((index + 1) * n)].iter().filter(|w| w.is_some()).count()
+            },
+        };
+        if !self.directed && self.has_edge(index, index) { degree + 1 } else { degree }
+    }
+
+    pub fn has_edge(&self, source_index : usize, dest_index : usize) -> bool {
+        self.check_indices(source_index, dest_index);
+        match self.matrix {
+            AdjacencyMatrix::Unweighted(ref bits) => bits.get(source_index, dest_index),
+            AdjacencyMatrix::Weighted(ref values) => values[source_index * self.number_of_vertices() + dest_index].is_some(),
+        }
+    }
+
+    // Returns None if there is no such edge. Edges of an unweighted graph have weight 1.
+    pub fn get_weight(&self, source_index : usize, dest_index : usize) -> Option<f64> {
+        self.check_indices(source_index, dest_index);
+        match self.matrix {
+            AdjacencyMatrix::Unweighted(ref bits) => {
+                if bits.get(source_index, dest_index) { Some(1.0) } else { None }
+            },
+            AdjacencyMatrix::Weighted(ref values) => values[source_index * self.number_of_vertices() + dest_index],
+        }
+    }
+
+    // Adding an edge that's already present to an unweighted graph does nothing.
+    // For a weighted graph the edge is given unit weight if it is not already present.
+    pub fn add_directed_edge(&mut self, source_index : usize, dest_index : usize) {
+        if !self.has_edge(source_index, dest_index) {
+            self.set_entry(source_index, dest_index, Some(1.0));
+        }
+    }
+
+    pub fn add_undirected_edge(&mut self, source_index : usize, dest_index : usize) {
+        self.add_directed_edge(source_index, dest_index);
+        self.add_directed_edge(dest_index, source_index);
+    }
+
+    // Sets the weight of a directed edge, adding the edge if necessary.
+    pub fn set_directed_weight(&mut self, source_index : usize, dest_index : usize, weight : f64) {
+        if !self.is_weighted() {
+            panic!("Called set_directed_weight on an unweighted MatrixGraph.")
+        }
+        self.set_entry(source_index, dest_index, Some(weight));
+    }
+
+    pub fn set_undirected_weight(&mut self, source_index : usize, dest_index : usize, weight : f64) {
+        self.set_directed_weight(source_index, dest_index, weight);
+        self.set_directed_weight(dest_index, source_index, weight);
+    }
+
+    // The edge's attributes are removed along with it; in an undirected graph,
+    // once neither direction is left.
+    pub fn remove_directed_edge(&mut self, source_index : usize, dest_index : usize) {
+        self.set_entry(source_index, dest_index, None);
+        if self.directed || !self.has_edge(dest_index, source_index) {
+            let key = self.edge_key(source_index, dest_index);
+            self.edge_attributes.remove_all(key);
+        }
+    }
+
+    pub fn remove_undirected_edge(&mut self, source_index : usize, dest_index : usize) {
+        self.remove_directed_edge(source_index, dest_index);
+        self.remove_directed_edge(dest_index, source_index);
+    }
+
+    fn set_entry(&mut self, source_index : usize, dest_index : usize, weight : Option<f64>) {
+        self.check_indices(source_index, dest_index);
+        let n = self.number_of_vertices();
+        match self.matrix {
+            AdjacencyMatrix::Unweighted(ref mut bits) => bits.set(source_index, dest_index, weight.is_some()),
+            AdjacencyMatrix::Weighted(ref mut values) => values[source_index * n + dest_index] = weight,
+        }
+    }
+
+    fn check_indices(&self, source_index : usize, dest_index : usize) {
+        if dest_index >= self.number_of_vertices() {
+            panic!("dest_index {} was >= {}, the number of vertices in the graph.", dest_index, self.number_of_vertices())
+        }
+        if source_index >= self.number_of_vertices() {
+            panic!("source_index {} was >= {}, the number of vertices in the graph.", source_index, self.number_of_vertices())
+        }
+    }
+
+    // The graph with every edge reversed. An undirected graph is its own transpose.
+    #[allow(clippy::redundant_field_names)]
+    pub fn transpose(&self) -> MatrixGraph<T> {
+        let n = self.number_of_vertices();
+        let matrix = match self.matrix {
+            AdjacencyMatrix::Unweighted(ref bits) => {
+                let mut transposed = BitMatrix::new(n);
+                for row in 0..n {
+                    for col in 0..n {
+                        if bits.get(row, col) { transposed.set(col, row, true) }
+                    }
+                }
+                AdjacencyMatrix::Unweighted(transposed)
+            },
+            AdjacencyMatrix::Weighted(ref values) => {
+                let mut transposed = vec![None; n * n];
+                for row in 0..n {
+                    for col in 0..n {
+                        transposed[col * n + row] = values[row * n + col];
+                    }
+                }
+                AdjacencyMatrix::Weighted(transposed)
+            },
+        };
+        let directed = self.directed;
+        MatrixGraph { directed        : directed,
+                      nodes           : self.nodes.clone(),
+                      node_indices    : self.node_indices.clone(),
+                      matrix          : matrix,
+                      node_attributes : self.node_attributes.map_keys(Some),
+                      edge_attributes : self.edge_attributes.map_keys(|(s, d)| Some(if directed { (d, s) } else { (s, d) })),
+                      metadata        : self.metadata.clone() }
+    }
+
+    // The matrix as dense rows, with 0.0 where there is no edge.
+    pub fn to_dense_rows(&self) -> Vec<Vec<f64>> {
+        let n = self.number_of_vertices();
+        (0..n).map(|row| {
+            (0..n).map(|col| self.get_weight(row, col).unwrap_or(0.0)).collect()
+        }).collect()
+    }
+
+    // Neighbours of a vertex, in increasing order of index.
+    pub fn neighbours_from_index<'a>(&'a self, index : usize) -> Box<dyn Iterator<Item = usize> + 'a> {
+        Box::new((0..self.number_of_vertices()).filter(move |&dest| self.has_edge(index, dest)))
+    }
+
+    pub fn breadth_first_iter_from_index<F, G> (&self,
+                                                mut process_vertex : F,
+                                                mut process_edge : G,
+                                                root_index : usize)
+        where F : FnMut(&T), G : FnMut(&T, &T) {
+        traversal::breadth_first(self, &mut process_vertex, &mut process_edge, root_index)
+    }
+
+    pub fn breadth_first_iter_from_node<F, G> (&self,
+                                               process_vertex : F,
+                                               process_edge : G,
+                                               root_node : T)
+        where F : FnMut(&T), G : FnMut(&T, &T) {
+        let root_index = self.index_from_node(root_node);
+        self.breadth_first_iter_from_index(process_vertex, process_edge, root_index)
+    }
+
+    pub fn shortest_path_from_index(&self, source_index : usize, dest_index : usize) -> Option<Vec<usize>> {
+        self.check_indices(source_index, dest_index);
+        traversal::shortest_path(self, source_index, dest_index)
+    }
+
+    pub fn depth_first_iter_from_index<F, G, H> (&self,
+                                                 mut process_vertex_early : F,
+                                                 mut process_vertex_late  : G,
+                                                 mut process_edge         : H,
+                                                 root_node : usize)
+        where F : FnMut(&T), G : FnMut(&T), H : FnMut(&T, &T, DFSEdgeType, &Vec<Option<usize>>) {
+        traversal::depth_first_from_index(self,
+                                          &mut process_vertex_early,
+                                          &mut process_vertex_late,
+                                          &mut process_edge,
+                                          root_node)
+    }
+
+    pub fn depth_first_iter<F, G, H> (&self,
+                                      mut process_vertex_early : F,
+                                      mut process_vertex_late  : G,
+                                      mut process_edge         : H)
+        where F : FnMut(&T), G : FnMut(&T), H : FnMut(&T, &T, DFSEdgeType, &Vec<Option<usize>>) {
+        traversal::depth_first(self,
+                               &mut process_vertex_early,
+                               &mut process_vertex_late,
+                               &mut process_edge)
+    }
+}
+
+impl<T> Traversable<T> for MatrixGraph<T> where T : Clone + Eq + Hash {
+    fn nodes(&self) -> &[T] {
+        &self.nodes
+    }
+
+    fn directed(&self) -> bool {
+        self.directed
+    }
+
+    fn neighbours<'a>(&'a self, index : usize) -> Box<dyn Iterator<Item = (usize, usize)> + 'a> {
+        // There is at most one edge between each ordered pair of vertices, so the
+        // matrix position serves as the edge key.
+        let n = self.number_of_vertices();
+        let directed = self.directed;
+        Box::new(self.neighbours_from_index(index).map(move |dest| {
+            if directed || index <= dest { (dest, index * n + dest) } else { (dest, dest * n + index) }
+        }))
+    }
+}
+
+// Converting from an adjacency list keeps the node and edge attributes and the
+// metadata. An int or float "weight" edge attribute gives a weighted matrix, with
+// 1 for an edge without a weight, and otherwise the matrix is unweighted. The
+// matrix can only hold one edge from each vertex to each other, so parallel
+// edges are an EdgeError::ParallelEdge. In a directed graph, an edge added with
+// add_undirected_edge becomes an arc each way, each with the edge's attributes.
+impl<'a, T> TryFrom<&'a Graph<T>> for MatrixGraph<T> where T : Clone + Eq + Hash {
+    type Error = EdgeError;
+
+    fn try_from(graph : &'a Graph<T>) -> Result<MatrixGraph<T>, EdgeError> {
+        let weighted = matches!(graph.edge_attributes.attribute_type("weight"), Some(AttributeType::Int) | Some(AttributeType::Float));
+        let mut m = if weighted {
+            MatrixGraph::weighted(graph.nodes.clone(), graph.directed)
+        } else {
+            MatrixGraph::unweighted(graph.nodes.clone(), graph.directed)
+        };
+        let mut keys = HashMap::new();
+        for source in 0..graph.number_of_vertices() {
+            for (&dest, &edge) in graph.adjacency_list[source].iter().zip(graph.adjacency_edges[source].iter()) {
+                if m.has_edge(source, dest) {
+                    return Err(EdgeError::ParallelEdge(source, dest))
+                }
+                let weight = if weighted { graph.edge_attributes.get(edge, "weight").and_then(|w| w.as_float()) } else { None };
+                m.set_entry(source, dest, Some(weight.unwrap_or(1.0)));
+                keys.entry(edge).or_insert_with(Vec::new).push(m.edge_key(source, dest));
+            }
+        }
+        m.node_attributes = graph.node_attributes.map_keys(Some);
+        // The declarations are kept as they are, but the weights are in the matrix
+        // rather than in "weight", which keeps its type and default for converting back.
+        let mut edge_attributes = graph.edge_attributes.map_keys(|_| None);
+        for name in graph.edge_attributes.names() {
+            if weighted && name == "weight" { continue }
+            for (edge, value) in graph.edge_attributes.iter(name) {
+                if let Some(keys) = keys.get(&edge) {
+                    for &key in keys {
+                        edge_attributes.set(key, name, value.clone()).unwrap();
+                    }
+                }
+            }
+        }
+        m.edge_attributes = edge_attributes;
+        m.metadata = graph.metadata.clone();
+        Ok(m)
+    }
+}
+
+// Converting back gives each adjacency list in increasing order of destination
+// index, with edges numbered in that order, and the node and edge attributes and
+// metadata. The weights of a weighted matrix become the "weight" edge attribute:
+// of the type and default declared for "weight" in the matrix's edge attributes,
+// if they fit, and float otherwise.
+impl<'a, T> From<&'a MatrixGraph<T>> for Graph<T> where T : Clone + Eq + Hash {
+    fn from(graph : &'a MatrixGraph<T>) -> Graph<T> {
+        let mut g = graph_builders::unconnected(graph.nodes.clone(), graph.directed);
+        let mut edges = HashMap::new();
+        let mut weights = Vec::new();
+        for source in 0..graph.number_of_vertices() {
+            for dest in graph.neighbours_from_index(source) {
+                let edge = if graph.directed || !graph.has_edge(dest, source) {
+                    g.add_directed_edge(source, dest)
+                } else if source <= dest {
+                    // Pair up the two directions of an undirected edge
+                    g.add_undirected_edge(source, dest)
+                } else {
+                    continue
+                };
+                edges.insert(graph.edge_key(source, dest), edge);
+                weights.push((edge, graph.get_weight(source, dest).unwrap()));
+            }
+        }
+        g.node_attributes = graph.node_attributes.map_keys(Some);
+        g.edge_attributes = graph.edge_attributes.map_keys(|key| edges.get(&key).cloned());
+        if graph.is_weighted() {
+            let whole = |w : f64| w.fract() == 0.0 && w.abs() < 9.0e15;
+            let declared = graph.edge_attributes.attribute_type("weight");
+            let as_ints = declared == Some(AttributeType::Int) && weights.iter().all(|&(_, w)| whole(w));
+            if !as_ints && declared != Some(AttributeType::Float) {
+                // Whatever was declared can't hold the weights.
+                g.edge_attributes.undeclare("weight");
+                g.edge_attributes.declare("weight", AttributeType::Float, None).unwrap();
+            }
+            for (edge, w) in weights {
+                let value = if as_ints { AttributeValue::Int(w as i64) } else { AttributeValue::Float(w) };
+                g.edge_attributes.set(edge, "weight", value).unwrap();
+            }
+        }
+        g.metadata = graph.metadata.clone();
+        g
+    }
+}
@@ -0,0 +1,97 @@
+// Serde support, behind the "serde" feature. A graph is serialized as
+//
+//     { "directed" : true,
+//       "nodes"    : ["a", "b", "c"],
+//       "edges"    : [[0, 1], [2, 0]],
+//       "config"   : { "parallel_edges" : "Merge", "self_loops" : "Allow" } }
+//
+// Edges are listed in EdgeIndex order by the indices of their endpoints, so an
+// undirected graph lists each edge once. In a directed graph the indices of any
+// edges added with add_undirected_edge are listed in "undirected_edges", which
+// is left out when empty. "config" can be left out for the default config.
+// Attributes and the edge index aren't serialized.
+//
+// Deserializing checks that the nodes are distinct and that each edge joins
+// nodes of the graph and is allowed by the config, so that node_indices always
+// matches nodes and the edges keep their indices.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error;
+use std::collections::HashSet;
+use std::hash::Hash;
+use super::{Graph, GraphConfig};
+use super::graph_builders::unconnected_with_config;
+
+#[derive(Serialize)]
+struct GraphRef<'a, T : 'a> {
+    directed         : bool,
+    nodes            : &'a [T],
+    edges            : &'a [(usize, usize)],
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    undirected_edges : Vec<usize>,
+    config           : GraphConfig,
+}
+
+#[derive(Deserialize)]
+struct GraphData<T> {
+    directed         : bool,
+    nodes            : Vec<T>,
+    edges            : Vec<(usize, usize)>,
+    #[serde(default)]
+    undirected_edges : Vec<usize>,
+    #[serde(default)]
+    config           : GraphConfig,
+}
+
+impl<T> Serialize for Graph<T> where T : Clone + Eq + Hash + Serialize {
+    fn serialize<S : Serializer>(&self, serializer : S) -> Result<S::Ok, S::Error> {
+        // An undirected edge in a directed graph has an arc each way with the same EdgeIndex.
+        let undirected_edges = if self.directed {
+            self.edge_endpoints.iter().enumerate()
+                .filter(|&(edge, &(source, dest))| {
+                    source != dest && self.adjacency_list[dest].iter().zip(self.adjacency_edges[dest].iter())
+                                                          .any(|(&d, &e)| d == source && e.index() == edge)
+                })
+                .map(|(edge, _)| edge)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        GraphRef { directed : self.directed, nodes : &self.nodes, edges : &self.edge_endpoints,
+                   undirected_edges, config : self.config }.serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Graph<T> where T : Clone + Eq + Hash + Deserialize<'de> {
+    fn deserialize<D : Deserializer<'de>>(deserializer : D) -> Result<Graph<T>, D::Error> {
+        let data = GraphData::<T>::deserialize(deserializer)?;
+        let n = data.nodes.len();
+        let mut g = unconnected_with_config(data.nodes, data.directed, data.config);
+        if g.node_indices.len() != n {
+            return Err(D::Error::custom(format!("the nodes must be distinct, but only {} of the {} are", g.node_indices.len(), n)))
+        }
+
+        let m = data.edges.len();
+        let undirected : HashSet<usize> = data.undirected_edges.into_iter().collect();
+        if !data.directed && !undirected.is_empty() {
+            return Err(D::Error::custom("undirected_edges can only be given for a directed graph"))
+        }
+        if let Some(edge) = undirected.iter().find(|&&edge| edge >= m) {
+            return Err(D::Error::custom(format!("undirected edge {} is not one of the {} edges", edge, m)))
+        }
+
+        for (edge, (source, dest)) in data.edges.into_iter().enumerate() {
+            if source >= n || dest >= n {
+                return Err(D::Error::custom(format!("edge {} joins {} and {}, but there are only {} nodes", edge, source, dest, n)))
+            }
+            let added = if !data.directed || undirected.contains(&edge) { g.try_add_undirected_edge(source, dest) }
+                        else { g.try_add_directed_edge(source, dest) };
+            match added {
+                Ok(index) if index.index() == edge => (),
+                Ok(_) => return Err(D::Error::custom(format!("edge {} repeats an earlier edge, which the config merges", edge))),
+                Err(e) => return Err(D::Error::custom(e)),
+            }
+        }
+        Ok(g)
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::{BFSTraversalState, DFSTraversalState, DFSEdgeType};
+
+// Anything that the traversal routines can walk over. Both the adjacency list
+// and adjacency matrix representations implement this, so they share a single
+// implementation of BFS and DFS.
+pub trait Traversable<T> {
+    fn nodes(&self) -> &[T];
+    fn directed(&self) -> bool;
+    // Yields (dest, edge key) pairs. The edge key identifies the edge, so that
+    // parallel edges can be told apart, and is the same from both ends of an
+    // undirected edge.
+    fn neighbours<'a>(&'a self, index : usize) -> Box<dyn Iterator<Item = (usize, usize)> + 'a>;
+}
+
+pub fn breadth_first<T, X, F, G> (graph : &X,
+                                  process_vertex : &mut F,
+                                  process_edge : &mut G,
+                                  root_index : usize)
+    where X : Traversable<T>, F : FnMut(&T), G : FnMut(&T, &T) {
+
+    let nodes = graph.nodes();
+    let mut node_states = vec![BFSTraversalState::Undiscovered; nodes.len()];
+    node_states[root_index] = BFSTraversalState::Discovered;
+
+    let mut nodes_to_process = VecDeque::<usize>::new();
+    nodes_to_process.push_back(root_index);
+
+    while let Some(current_node) = nodes_to_process.pop_front() {
+        for (dest_node, _) in graph.neighbours(current_node) {
+            // Note - this does both edges in both directions for an undirected graph.
+            process_edge(&nodes[current_node], &nodes[dest_node]);
+            if node_states[dest_node] == BFSTraversalState::Undiscovered {
+                node_states[dest_node] = BFSTraversalState::Discovered;
+                nodes_to_process.push_back(dest_node);
+            }
+        }
+
+        process_vertex(&nodes[current_node]);
+        node_states[current_node] = BFSTraversalState::Processed;
+    }
+}
+
+// The vertices of a path with as few edges as possible from source to dest,
+// including both ends, or None if dest can't be reached from source.
+pub fn shortest_path<T, X>(graph : &X, source : usize, dest : usize) -> Option<Vec<usize>>
+    where X : Traversable<T> {
+
+    let mut node_states = vec![BFSTraversalState::Undiscovered; graph.nodes().len()];
+    let mut parent = vec![None; graph.nodes().len()];
+    node_states[source] = BFSTraversalState::Discovered;
+
+    let mut nodes_to_process = VecDeque::<usize>::new();
+    nodes_to_process.push_back(source);
+
+    while let Some(current_node) = nodes_to_process.pop_front() {
+        if current_node == dest { break }
+        for (dest_node, _) in graph.neighbours(current_node) {
+            if node_states[dest_node] == BFSTraversalState::Undiscovered {
+                node_states[dest_node] = BFSTraversalState::Discovered;
+                parent[dest_node] = Some(current_node);
+                nodes_to_process.push_back(dest_node);
+            }
+        }
+        node_states[current_node] = BFSTraversalState::Processed;
+    }
+
+    if node_states[dest] == BFSTraversalState::Undiscovered {
+        return None
+    }
+    let mut path = vec![dest];
+    while let Some(previous) = parent[*path.last().unwrap()] {
+        path.push(previous);
+    }
+    path.reverse();
+    Some(path)
+}
+
+pub fn depth_first_from_index<T, X, F, G, H> (graph : &X,
+                                              process_vertex_early : &mut F,
+                                              process_vertex_late  : &mut G,
+                                              process_edge         : &mut H,
+                                              root_node : usize)
+    where X : Traversable<T>, F : FnMut(&T), G : FnMut(&T),
+          H : FnMut(&T, &T, DFSEdgeType, &Vec<Option<usize>>) {
+
+    let nodes = graph.nodes();
+    let mut discovery_state = vec![DFSTraversalState::Undiscovered; nodes.len()];
+    let mut parent = vec![None; nodes.len()];
+    let mut parent_edge = vec![None; nodes.len()];
+    // Call into a recursive function
+    inner_dfs(graph,
+              &mut |index| process_vertex_early(&nodes[index]),
+              &mut |index| process_vertex_late(&nodes[index]),
+              &mut |source, dest, _, edge_type, parent| process_edge(&nodes[source], &nodes[dest], edge_type, parent),
+              &mut discovery_state,
+              &mut parent,
+              &mut parent_edge,
+              0usize,
+              root_node);
+}
+
+pub fn depth_first<T, X, F, G, H> (graph : &X,
+                                   process_vertex_early : &mut F,
+                                   process_vertex_late  : &mut G,
+                                   process_edge         : &mut H)
+    where X : Traversable<T>, F : FnMut(&T), G : FnMut(&T),
+          H : FnMut(&T, &T, DFSEdgeType, &Vec<Option<usize>>) {
+
+    let nodes = graph.nodes();
+    depth_first_by_index(graph,
+                         &mut |index| process_vertex_early(&nodes[index]),
+                         &mut |index| process_vertex_late(&nodes[index]),
+                         &mut |source, dest, _, edge_type, parent| process_edge(&nodes[source], &nodes[dest], edge_type, parent));
+}
+
+// Classifies every edge by a depth-first search over the whole graph, keyed by
+// edge key. An undirected edge is classified once, from the end it was first
+// seen from.
+pub fn depth_first_edge_types<T, X>(graph : &X) -> HashMap<usize, DFSEdgeType>
+    where X : Traversable<T> {
+
+    let mut edge_types = HashMap::new();
+    depth_first_by_index(graph,
+                         &mut |_| (),
+                         &mut |_| (),
+                         &mut |_, _, edge, edge_type, _| { edge_types.entry(edge).or_insert(edge_type); });
+    edge_types
+}
+
+// DFS over the whole graph, passing vertex indices and edge keys to the callbacks.
+fn depth_first_by_index<T, X, F, G, H> (graph : &X,
+                                        process_vertex_early : &mut F,
+                                        process_vertex_late  : &mut G,
+                                        process_edge         : &mut H)
+    where X : Traversable<T>, F : FnMut(usize), G : FnMut(usize),
+          H : FnMut(usize, usize, usize, DFSEdgeType, &Vec<Option<usize>>) {
+
+    let mut discovery_state = vec![DFSTraversalState::Undiscovered; graph.nodes().len()];
+    let mut parent = vec![None; graph.nodes().len()];
+    let mut parent_edge = vec![None; graph.nodes().len()];
+    // The clock keeps running from one tree to the next, so that an edge into an
+    // earlier tree is seen as a cross edge.
+    let mut time = 0usize;
+    for root_node in 0..graph.nodes().len() {
+        if discovery_state[root_node] == DFSTraversalState::Undiscovered {
+            // Call into a recursive function
+            time = inner_dfs(graph,
+                             process_vertex_early,
+                             process_vertex_late,
+                             process_edge,
+                             &mut discovery_state,
+                             &mut parent,
+                             &mut parent_edge,
+                             time,
+                             root_node);
+        }
+    };
+}
+
+// Recursive part of DFS
+#[allow(clippy::too_many_arguments)]
+fn inner_dfs<T, X, F, G, H>(graph                    : &X,
+                            process_vertex_early     : &mut F,
+                            process_vertex_late      : &mut G,
+                            process_edge             : &mut H,
+                            discovery_state          : &mut Vec<DFSTraversalState>,
+                            parent                   : &mut Vec<Option<usize>>,
+                            parent_edge              : &mut Vec<Option<usize>>,   // Key of the tree edge into each vertex
+                            time                     : usize,
+                            current_node             : usize) -> usize    // Returns the exit time + 1
+    where X : Traversable<T>, F : FnMut(usize), G : FnMut(usize),
+          H : FnMut(usize, usize, usize, DFSEdgeType, &Vec<Option<usize>>) {
+        process_vertex_early(current_node);
+        let entry_time : usize = time;
+        let mut running_time = time + 1;
+        discovery_state[current_node] = DFSTraversalState::Processing(entry_time);
+        running_time += 1;
+        for (dest_node, edge) in graph.neighbours(current_node) {
+            match discovery_state[dest_node] {
+                DFSTraversalState::Undiscovered => {
+                    parent[dest_node] = Some(current_node);
+                    parent_edge[dest_node] = Some(edge);
+                    process_edge(current_node, dest_node, edge, DFSEdgeType::Tree, parent);
+                    running_time = inner_dfs(graph,
+                                             process_vertex_early,
+                                             process_vertex_late,
+                                             process_edge,
+                                             discovery_state,
+                                             parent,
+                                             parent_edge,
+                                             running_time,
+                                             dest_node);
+                },
+                DFSTraversalState::Processing(_) => {
+                    // In an undirected graph, don't report the tree edge we arrived by as a
+                    // back edge. Parallel edges to the parent and self-loops are back edges.
+                    if parent_edge[current_node] != Some(edge) || graph.directed() {
+                        process_edge(current_node, dest_node, edge, DFSEdgeType::Back, parent);
+                    }
+                },
+                DFSTraversalState::Processed(dest_entry_time, _) => {
+                    if graph.directed() {
+                        if dest_entry_time > entry_time {
+                            process_edge(current_node, dest_node, edge, DFSEdgeType::Forward, parent);
+                        } else {
+                            process_edge(current_node, dest_node, edge, DFSEdgeType::Cross, parent);
+                        }
+                    }
+                },
+            }
+        }
+        discovery_state[current_node] = DFSTraversalState::Processed(entry_time, running_time);
+        running_time += 1;
+        process_vertex_late(current_node);
+        // Return the next time available for use
+        running_time
+    }
@@ -0,0 +1,10 @@
+# A small directed network
+Creator "hand"
+graph [
+  directed 1
+  node [ id 10 label "Alice" age 31 ]
+  node [ id 20 label "Bob &amp; co" age 2.5 graphics [ x 1.0 y 2.0 ] ]
+  node [ id 30 ]
+  edge [ source 10 target 20 kind "friend" ]
+  edge [ source 30 target 10 ]
+]
@@ -0,0 +1,95 @@
+extern crate graph;
+
+use graph::*;
+use graph::graph_builders::*;
+use graph::graph_builders::adjacency_matrix::*;
+
+#[test]
+fn read_symmetric_matrix() {
+    let g = from_adjacency_matrix_file("test_data/graph15.txt").unwrap();
+
+    assert!(!g.is_directed());
+    assert_eq!(4, g.number_of_vertices());
+    assert_eq!(5, g.number_of_edges());
+    assert!(g.has_edge(2, 0) && !g.has_edge(1, 3));
+    assert_eq!(None, g.edge_attributes().attribute_type("weight"));
+    assert_eq!("0 1 1 1\n1 0 1 0\n1 1 0 1\n1 0 1 0\n", make_adjacency_matrix_string(&g, Diagonal::Zero));
+}
+
+#[test]
+fn weighted_matrix_round_trip() {
+    let text = "0 2.5 0\n0 0 -1\n4 0 0\n";
+    let g = from_adjacency_matrix_str(text).unwrap();
+    assert!(g.is_directed());
+    assert_eq!(3, g.number_of_edges());
+    let e = g.find_edge(0, 1).unwrap();
+    assert_eq!(Some(2.5), g.edge_attributes().get(e, "weight").and_then(|v| v.as_float()));
+    assert!(!g.has_edge(1, 0));
+    // The weights are floats, so the whole ones are written as floats too.
+    assert_eq!("0 2.5 0\n0 0 -1.0\n4.0 0 0\n", make_adjacency_matrix_string(&g, Diagonal::SelfLoops));
+
+    let g = from_adjacency_matrix_str("0 3\n3 0\n").unwrap();
+    assert!(!g.is_directed());
+    let e = g.find_edge(1, 0).unwrap();
+    assert_eq!(Some(3), g.edge_attributes().get(e, "weight").and_then(|v| v.as_int()));
+}
+
+#[test]
+fn whole_float_weights_stay_floats() {
+    let mut g = unconnected(vec![0, 1], true);
+    let e = g.add_directed_edge(0, 1);
+    g.add_directed_edge(1, 0);
+    g.edge_attributes_mut().declare("weight", attributes::AttributeType::Float, None).unwrap();
+    g.edge_attributes_mut().set(e, "weight", 2.0).unwrap();
+    let text = make_adjacency_matrix_string(&g, Diagonal::Zero);
+    assert_eq!("0 2.0\n1 0\n", text);
+
+    let h = from_adjacency_matrix_str(&text).unwrap();
+    assert_eq!(Some(attributes::AttributeType::Float), h.edge_attributes().attribute_type("weight"));
+    let e = h.find_edge(0, 1).unwrap();
+    assert_eq!(Some(&attributes::AttributeValue::Float(2.0)), h.edge_attributes().get(e, "weight"));
+
+    g.edge_attributes_mut().set(e, "weight", f64::INFINITY).unwrap();
+    let err = write_adjacency_matrix(&g, Vec::new(), Diagonal::Zero).err().unwrap();
+    assert_eq!(std::io::ErrorKind::InvalidInput, err.kind());
+}
+
+#[test]
+fn indented_comments_are_skipped() {
+    let g = from_adjacency_matrix_str("  # A comment\n0 1\n\t# Another\n1 0\n").unwrap();
+    assert_eq!(2, g.number_of_vertices());
+    assert!(!g.is_directed());
+    assert!(g.has_edge(0, 1));
+}
+
+#[test]
+fn diagonal_conventions() {
+    let text = "1 1\n1 0\n";
+    let g = from_adjacency_matrix_str(text).unwrap();
+    assert_eq!(2, g.number_of_edges());
+    assert!(g.has_edge(0, 0));
+    assert_eq!(text, make_adjacency_matrix_string(&g, Diagonal::SelfLoops));
+    assert_eq!("0 1\n1 0\n", make_adjacency_matrix_string(&g, Diagonal::Zero));
+
+    let g = from_adjacency_matrix_reader_with_options(text.as_bytes(), ParseMode::Strict, Diagonal::Zero).unwrap();
+    assert_eq!(1, g.number_of_edges());
+    assert!(!g.has_edge(0, 0));
+}
+
+#[test]
+fn adjacency_matrix_errors() {
+    let text = "0 1 0\n1 0\n0 x 0\n";
+    let err = from_adjacency_matrix_reader_with_options(text.as_bytes(), ParseMode::Lenient, Diagonal::SelfLoops).err().unwrap();
+    let locations : Vec<(usize, usize)> = err.diagnostics().iter().map(|d| (d.line, d.column)).collect();
+    assert_eq!(vec![(2, 4), (3, 3)], locations);
+
+    let err = from_adjacency_matrix_str("0 1\n").err().unwrap();
+    assert_eq!("line 2, column 1 (in adjacency matrix): expected 2 rows, for a square matrix, found 1", format!("{}", err));
+
+    let config = GraphConfig { parallel_edges : ParallelEdgePolicy::Keep, self_loops : SelfLoopPolicy::Allow };
+    let mut g = unconnected_with_config(vec![0, 1], true, config);
+    g.add_directed_edge(0, 1);
+    g.add_directed_edge(0, 1);
+    let err = write_adjacency_matrix(&g, Vec::new(), Diagonal::SelfLoops).err().unwrap();
+    assert_eq!(std::io::ErrorKind::InvalidInput, err.kind());
+}
@@ -0,0 +1,106 @@
+extern crate graph;
+use graph::attributes::*;
+use graph::graph_builders::*;
+
+#[test]
+fn get_set_remove_with_defaults() {
+    let mut store = AttributeStore::<usize>::new();
+    store.declare("colour", AttributeType::Str, Some(AttributeValue::from("white"))).unwrap();
+
+    assert_eq!(Some(&AttributeValue::from("white")), store.get(3, "colour"));
+    assert_eq!(None, store.set(3, "colour", "red").unwrap());
+    assert_eq!(Some(&AttributeValue::from("red")), store.get(3, "colour"));
+    assert_eq!(Some(AttributeValue::from("red")), store.remove(3, "colour"));
+    assert_eq!(Some(&AttributeValue::from("white")), store.get(3, "colour"));
+
+    // Setting an undeclared attribute declares it, with no default
+    store.set(1, "size", 4i64).unwrap();
+    assert_eq!(Some(AttributeType::Int), store.attribute_type("size"));
+    assert_eq!(None, store.get(0, "size"));
+    assert_eq!(vec!["colour", "size"], store.names().collect::<Vec<_>>());
+}
+
+#[test]
+fn type_mismatch() {
+    let mut store = AttributeStore::<usize>::new();
+    store.set(0, "weight", 1.5).unwrap();
+
+    assert_eq!(Err(AttributeError::TypeMismatch { name : String::from("weight"), expected : AttributeType::Float, found : AttributeType::Str }),
+               store.set(1, "weight", "heavy"));
+    assert!(store.set_from_str(1, "weight", "heavy").is_err());
+    assert!(store.set_from_str(1, "missing", "1").is_err());
+}
+
+#[test]
+fn names_must_be_writable() {
+    let mut store = AttributeStore::<usize>::new();
+    assert_eq!(Err(AttributeError::InvalidName(String::from("my attr"))), store.declare("my attr", AttributeType::Int, None));
+    assert_eq!(Err(AttributeError::InvalidName(String::new())), store.set(0, "", 1i64));
+    assert!(store.is_empty());
+    store.set(0, "my_attr_2", 1i64).unwrap();
+}
+
+#[test]
+fn iteration_in_key_order() {
+    let mut store = AttributeStore::<usize>::new();
+    store.declare("label", AttributeType::Str, Some(AttributeValue::from("?"))).unwrap();
+    store.set(5, "label", "five").unwrap();
+    store.set(2, "label", "two").unwrap();
+    store.set(2, "marked", true).unwrap();
+
+    let explicit : Vec<(usize, &AttributeValue)> = store.iter("label");
+    assert_eq!(vec![(2, &AttributeValue::from("two")), (5, &AttributeValue::from("five"))], explicit);
+    assert_eq!(vec![("label", &AttributeValue::from("two")), ("marked", &AttributeValue::Bool(true))], store.attributes_of(2));
+    assert_eq!(vec![("label", &AttributeValue::from("?"))], store.attributes_of(0));
+}
+
+#[test]
+fn read_attributes_from_file() {
+    let g = from_file("test_data/graph6").unwrap();
+
+    assert_eq!(Some(&AttributeValue::from("dark red")), g.node_attributes().get(1, "colour"));
+    assert_eq!(Some(&AttributeValue::from("white")), g.node_attributes().get(0, "colour"));
+
+    let weight = |s, d| g.edge_attributes().get(g.find_edge(s, d).unwrap(), "weight").and_then(|w| w.as_float());
+    assert_eq!(Some(1.5), weight(0, 1));
+    assert_eq!(None, weight(1, 2));
+    assert_eq!(Some(-3.0), weight(2, 0));
+}
+
+#[test]
+fn attributes_round_trip() {
+    let mut g = unconnected(vec!["A", "B", "C"], false);
+    g.add_undirected_edge(0, 2);
+    let e = g.add_undirected_edge(1, 0);
+    g.node_attributes_mut().set(2, "visited", true).unwrap();
+    g.edge_attributes_mut().declare("weight", AttributeType::Int, Some(AttributeValue::Int(1))).unwrap();
+    g.edge_attributes_mut().set(e, "weight", 7i64).unwrap();
+
+    let mut expected_string = String::from("// Graph\n");
+    expected_string.push_str("format_version: 1\n");
+    expected_string.push_str("number_of_vertices: 3\n");
+    expected_string.push_str("directed: false\n");
+    expected_string.push_str("node_attribute: visited bool\n");
+    expected_string.push_str("edge_attribute: weight int 1\n");
+    expected_string.push_str("edges:\n");
+    expected_string.push_str("0 2\n");
+    expected_string.push_str("0 1\n");
+    expected_string.push_str("edge_attributes:\n");
+    expected_string.push_str("1 weight 7\n");
+    expected_string.push_str("node_attributes:\n");
+    expected_string.push_str("2 visited true\n");
+    assert_eq!(expected_string, make_serialization_string(&g));
+
+    let h = from_str(&expected_string).unwrap();
+    assert_eq!(Some(AttributeType::Bool), h.node_attributes().attribute_type("visited"));
+    assert_eq!(None, h.node_attributes().default_value("visited"));
+    assert_eq!(Some(true), h.node_attributes().get(2, "visited").and_then(|v| v.as_bool()));
+    assert_eq!(None, h.node_attributes().get(0, "visited"));
+    assert_eq!(Some(AttributeType::Int), h.edge_attributes().attribute_type("weight"));
+    assert_eq!(Some(&AttributeValue::Int(1)), h.edge_attributes().default_value("weight"));
+    let e = h.find_edge(0, 1).unwrap();
+    assert_eq!(Some(&AttributeValue::Int(7)), h.edge_attributes().get_explicit(e, "weight"));
+    let e = h.find_edge(0, 2).unwrap();
+    assert_eq!(None, h.edge_attributes().get_explicit(e, "weight"));
+    assert_eq!(Some(&AttributeValue::Int(1)), h.edge_attributes().get(e, "weight"));
+}
@@ -0,0 +1,75 @@
+extern crate graph;
+
+use graph::graph_builders::*;
+use graph::graph_builders::binary::*;
+
+fn sample() -> graph::Graph<String> {
+    let mut g = unconnected(vec![String::from("a"), String::from("bé"), String::from("")], false);
+    g.add_undirected_edge(0, 1);
+    g.add_undirected_edge(1, 2);
+    g.add_undirected_edge(2, 2);
+    g
+}
+
+#[test]
+fn binary_layout() {
+    let bytes = make_binary_vec(&sample());
+    assert_eq!(b"GRAPHCSR", &bytes[0..8]);
+    assert_eq!(&[1, 0, 0, 0, 0, 0, 0, 0], &bytes[8..16]);
+    // 40 bytes of header, 4 offsets, 5 targets, 4 label offsets and 4 bytes of labels
+    assert_eq!(40 + 8 * (4 + 5 + 4) + 4, bytes.len());
+
+    let view = GraphView::from_bytes(&bytes).unwrap();
+    assert!(!view.is_directed());
+    assert_eq!(3, view.number_of_vertices());
+    assert_eq!(5, view.number_of_arcs());
+    assert_eq!(vec![0, 2], view.neighbours(1).collect::<Vec<usize>>());
+    assert_eq!(2, view.get_degree_from_index(2));
+    assert_eq!("bé", view.label(1));
+    assert_eq!("", view.label(2));
+}
+
+#[test]
+fn binary_file_round_trip() {
+    let path = std::env::temp_dir().join(format!("rust_graph_binary_{}", std::process::id()));
+    let filename = path.to_str().unwrap();
+    let mut g = unconnected((0..3).collect::<Vec<usize>>(), true);
+    g.add_directed_edge(2, 0);
+    g.add_directed_edge(0, 1);
+    to_binary_file(&g, filename).unwrap();
+
+    {
+        let mapped = map_binary_file(filename).unwrap();
+        let view = mapped.view();
+        assert!(view.is_directed());
+        assert_eq!(vec![0], view.neighbours(2).collect::<Vec<usize>>());
+        assert_eq!(0, view.neighbours(1).count());
+    }
+    let h = from_binary_file::<usize>(filename).unwrap();
+    std::fs::remove_file(filename).unwrap();
+    assert!(h.has_edge(2, 0));
+    assert!(h.has_edge(0, 1));
+    assert!(!h.has_edge(1, 0));
+
+    let s = GraphView::from_bytes(&make_binary_vec(&sample())).unwrap().to_graph::<String>().unwrap();
+    assert!(!s.is_directed());
+    assert_eq!(3, s.number_of_edges());
+    assert!(s.has_edge(2, 1));
+    assert!(s.has_edge(2, 2));
+}
+
+#[test]
+fn binary_rejects_bad_files() {
+    let good = make_binary_vec(&sample());
+    let message = |bytes : &[u8]| format!("{}", GraphView::from_bytes(bytes).err().unwrap());
+
+    assert_eq!("not a binary graph file: the magic number is missing", message(b"graph"));
+    let mut bytes = good.clone();
+    bytes[8] = 2;
+    assert_eq!("binary graph version 2 is not supported; expected version 1", message(&bytes));
+    assert_eq!("a binary graph with 3 vertices, 5 arcs and 4 bytes of labels can't be 139 bytes long", message(&good[..139]));
+    // The first target, which is vertex 1
+    let mut bytes = good.clone();
+    bytes[40 + 32] = 7;
+    assert_eq!("arc 0 goes to vertex 7, but there are only 3 vertices", message(&bytes));
+}
@@ -1,4 +1,5 @@
 extern crate graph;
+use std::convert::TryFrom;
 use graph::*;
 
 #[test]
@@ -23,3 +24,18 @@ fn breadth_first_traversal_edge_processing() {
     assert_eq!((1, 0), edges[2]);
     assert_eq!((2, 0), edges[3]);
 }
+
+#[test]
+fn shortest_paths() {
+    let g = graph_builders::from_file("test_data/graph4").unwrap();
+    assert_eq!(Some(vec![2, 0, 4, 6]), g.shortest_path_from_index(2, 6));
+    assert_eq!(Some(vec![5]), g.shortest_path_from_index(5, 5));
+    assert_eq!(None, g.shortest_path_from_index(6, 0));
+
+    let m = matrix_graph::MatrixGraph::try_from(&g).unwrap();
+    assert_eq!(Some(vec![2, 0, 4, 6]), m.shortest_path_from_index(2, 6));
+
+    let g = graph_builders::from_file_with_nodes::<String>("test_data/graph1_labelled").unwrap();
+    let path = g.shortest_path_from_node(String::from("C"), String::from("B")).unwrap();
+    assert_eq!(vec!["C", "A", "B"], path);
+}
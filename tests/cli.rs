@@ -0,0 +1,106 @@
+extern crate graph;
+
+use std::io::prelude::*;
+use std::process::{Command, Output, Stdio};
+
+// Runs the graph binary, with the given text as standard input.
+fn run(args : &[&str], input : &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_graph")).args(args)
+                                                              .stdin(Stdio::piped())
+                                                              .stdout(Stdio::piped())
+                                                              .stderr(Stdio::piped())
+                                                              .spawn()
+                                                              .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output : &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+#[test]
+fn stats() {
+    let output = run(&["stats", "test_data/graph4"], "");
+    assert!(output.status.success());
+    assert_eq!("directed: true\nvertices: 7\nedges: 10\nout-degree distribution:\n  0: 3\n  1: 1\n  2: 1\n  3: 1\n  4: 1\n",
+               stdout(&output));
+}
+
+#[test]
+fn traversals_and_paths() {
+    assert_eq!("C\nA\nB\n", stdout(&run(&["bfs", "C", "test_data/graph1_labelled"], "")));
+    assert_eq!("2\n0\n4\n5\n6\n1\n3\n", stdout(&run(&["dfs", "2", "test_data/graph4"], "")));
+    assert_eq!("2\n0\n4\n6\n", stdout(&run(&["path", "2", "6", "-"], &std::fs::read_to_string("test_data/graph4").unwrap())));
+
+    let output = run(&["path", "6", "0", "test_data/graph4"], "");
+    assert_eq!(Some(1), output.status.code());
+    let output = run(&["bfs", "Z", "test_data/graph1_labelled"], "");
+    assert_eq!(Some(1), output.status.code());
+    assert_eq!("graph: there's no node Z in the graph\n", std::str::from_utf8(&output.stderr).unwrap());
+}
+
+#[test]
+fn convert_through_stdin() {
+    let json = run(&["convert", "--to", "json", "test_data/graph13.net"], "");
+    assert!(json.status.success());
+    let g = graph::graph_builders::node_link::from_node_link_str::<String>(stdout(&json)).unwrap();
+    let native = run(&["convert", "--from", "json", "--to", "native"], stdout(&json));
+    let h = graph::graph_builders::from_str_with_nodes::<String>(stdout(&native)).unwrap();
+    assert_eq!(g.number_of_edges(), h.number_of_edges());
+    assert_eq!(g.node_from_index(0), h.node_from_index(0));
+}
+
+#[test]
+fn edge_list_weights_are_kept() {
+    let csv = "# Roads\nsource,target,weight\na,b,3\nb,c,2.5\n";
+    let dimacs = run(&["convert", "--from", "csv", "--to", "dimacs"], "source,target,weight\na,b,3\nb,c,4\n");
+    assert!(dimacs.status.success());
+    assert_eq!("p sp 3 2\na 1 2 3\na 2 3 4\n", stdout(&dimacs));
+
+    let tsv = run(&["convert", "--from", "csv", "--to", "tsv"], csv);
+    assert_eq!("source\ttarget\tweight\na\tb\t3\nb\tc\t2.5\n", stdout(&tsv));
+    let unweighted = run(&["convert", "--from", "tsv", "--to", "csv"], "source\ttarget\na\tb\n");
+    assert_eq!("source,target\na,b\n", stdout(&unweighted));
+}
+
+#[test]
+fn failed_convert_leaves_the_output_alone() {
+    let dir = std::env::temp_dir().join(format!("rust_graph_cli_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let output = dir.join("out.adj");
+    std::fs::write(&output, "keep me\n").unwrap();
+
+    // An adjacency matrix can't hold parallel edges.
+    let parallel = "number_of_vertices: 2\nparallel_edges: keep\nedges:\n0 1\n0 1\n";
+    let result = run(&["convert", "-", output.to_str().unwrap()], parallel);
+    assert_eq!(Some(1), result.status.code());
+    assert_eq!("keep me\n", std::fs::read_to_string(&output).unwrap());
+
+    let result = run(&["convert", "test_data/graph4", output.to_str().unwrap()], "");
+    assert!(result.status.success());
+    assert!(std::fs::read_to_string(&output).unwrap().starts_with("0 0 0 0 1 0 0\n"));
+    // Only the output is left, with no temporary files.
+    assert_eq!(1, std::fs::read_dir(&dir).unwrap().count());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn validate() {
+    let output = run(&["validate"], "number_of_vertices: 2\ncolour: x\nedges:\n0 5\n");
+    assert_eq!(Some(1), output.status.code());
+    assert_eq!("<stdin>: warning: line 2, column 1 (in header): expected a known key such as number_of_vertices:, found colour:\n\
+                <stdin>: line 4, column 3 (in edges:): expected a vertex index less than 2, found 5\n", stdout(&output));
+
+    let output = run(&["validate", "test_data/graph12.gml"], "");
+    assert!(output.status.success());
+    assert_eq!("test_data/graph12.gml: no problems found\n", stdout(&output));
+}
+
+#[test]
+fn usage_errors() {
+    assert_eq!(Some(2), run(&["frobnicate"], "").status.code());
+    assert_eq!(Some(2), run(&["path", "a"], "").status.code());
+    assert_eq!(Some(2), run(&["stats", "--to", "dot"], "").status.code());
+    assert_eq!(Some(2), run(&["convert", "--from", "yaml"], "").status.code());
+}
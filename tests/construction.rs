@@ -20,6 +20,8 @@ fn make_unconnected() {
     assert_eq!(0, g.index_from_node(1));
     assert_eq!(1, g.index_from_node(2));
     assert_eq!(2, g.index_from_node(10));
+    assert!(g.contains_node(&10));
+    assert!(!g.contains_node(&3));
 
     assert_eq!(0, g.get_degree_from_index(0));
     assert_eq!(0, g.get_degree_from_index(1));
@@ -105,3 +107,79 @@ fn read_undirected_graph_from_file() {
     assert_eq!(1, g.index_from_node(1));
     assert_eq!(2, g.index_from_node(2));
 }
+
+#[test]
+fn has_edge_with_and_without_index() {
+    let mut g = graph_builders::unconnected(vec![0, 1, 2, 3], true);
+    g.add_directed_edge(0, 1);
+    g.add_directed_edge(2, 1);
+
+    assert!(g.has_edge(0, 1));
+    assert!(!g.has_edge(1, 0));
+
+    g.build_edge_index();
+    assert!(g.has_edge_index());
+    assert!(g.has_edge(2, 1));
+    assert!(!g.has_edge(1, 2));
+
+    // Edges added after the index is built are indexed too, and duplicates are still dropped.
+    g.add_directed_edge(3, 0);
+    g.add_directed_edge(3, 0);
+    assert!(g.has_edge(3, 0));
+    assert_eq!(1, g.get_degree_from_index(3));
+
+    g.drop_edge_index();
+    assert!(!g.has_edge_index());
+    assert!(g.has_edge(3, 0));
+}
+
+#[test]
+fn extend_edges_deduplicates() {
+    let mut g = graph_builders::unconnected(vec![0, 1, 2, 3], true);
+    g.add_directed_edge(0, 2);
+    g.extend_edges(vec![(0, 3), (0, 2), (0, 1), (0, 3), (2, 1)]);
+
+    assert_eq!(3, g.get_degree_from_index(0));
+    assert_eq!(1, g.get_degree_from_index(2));
+    assert!(g.has_edge(0, 1));
+    assert!(g.has_edge(0, 3));
+
+    let mut u = graph_builders::unconnected(vec![0, 1, 2], false);
+    u.build_edge_index();
+    u.extend_edges(vec![(0, 1), (1, 0), (1, 2)]);
+
+    assert_eq!(1, u.get_degree_from_index(0));
+    assert_eq!(2, u.get_degree_from_index(1));
+    assert_eq!(1, u.get_degree_from_index(2));
+    assert!(u.has_edge(2, 1));
+}
+
+#[test]
+fn read_graph_from_string() {
+    let text = "number_of_vertices: 3\ndirected: false\nedges:\n0 1\n0 2 // A comment\n";
+    let g = graph_builders::from_str(text).unwrap();
+
+    assert_eq!(3, g.number_of_vertices());
+    assert!(!g.is_directed());
+    assert_eq!(2, g.get_degree_from_index(0));
+
+    let g2 : Graph<usize> = text.parse().unwrap();
+    assert_eq!(graph_builders::make_serialization_string(&g), graph_builders::make_serialization_string(&g2));
+}
+
+#[test]
+fn read_labelled_graph_from_reader() {
+    let text = include_str!("../test_data/graph1_labelled");
+    let g = graph_builders::from_reader_with_nodes::<String, _>(std::io::Cursor::new(text)).unwrap();
+
+    assert_eq!(3, g.number_of_vertices());
+    assert_eq!(2, g.index_from_node(String::from("C")));
+
+    let g2 = graph_builders::from_str_with_nodes::<String>(text).unwrap();
+    assert_eq!(1, g2.index_from_node(String::from("B")));
+}
+
+#[test]
+fn missing_file_is_an_error() {
+    assert!(graph_builders::from_file("test_data/no_such_graph").is_err());
+}
@@ -129,8 +129,23 @@ fn iter_on_fan_in() {
     let expected_edges =
         vec![(0, 1, graph::DFSEdgeType::Tree),
              (1, 2, graph::DFSEdgeType::Tree),
-             (3, 1, graph::DFSEdgeType::Forward),
-             (4, 2, graph::DFSEdgeType::Forward)];  // FIXME - should these really be Cross edges?
+             (3, 1, graph::DFSEdgeType::Cross),
+             (4, 2, graph::DFSEdgeType::Cross)];
 
     assert_eq!(expected_edges, edges);
 }
+
+#[test]
+fn cross_edge_between_trees() {
+    // 2 starts a second tree, and its edge into the first one is a cross edge.
+    let mut g = graph_builders::unconnected(vec![0, 1, 2], true);
+    let e = g.add_directed_edge(0, 1);
+    let f = g.add_directed_edge(2, 1);
+
+    let edge_types = g.depth_first_edge_types();
+    assert_eq!(Some(&graph::DFSEdgeType::Tree), edge_types.get(&e));
+    assert_eq!(Some(&graph::DFSEdgeType::Cross), edge_types.get(&f));
+
+    let dot = graph_builders::dot::make_dot_string_with_style(&g, |_, _| Vec::new(), |_, _, _| Vec::new(), true);
+    assert!(dot.contains("    2 -> 1 [label=\"cross\""));
+}
@@ -0,0 +1,120 @@
+extern crate graph;
+
+use graph::graph_builders::*;
+use graph::graph_builders::dimacs::*;
+use graph::graph_builders::matrix_market::*;
+
+#[test]
+fn read_dimacs_shortest_path() {
+    let g = from_dimacs_sp_file("test_data/graph10.gr").unwrap();
+
+    assert!(g.is_directed());
+    assert_eq!(4, g.number_of_vertices());
+    assert_eq!(5, g.number_of_edges());
+    let e = g.find_edge(0, 1).unwrap();
+    assert_eq!(Some(7), g.edge_attributes().get(e, "weight").and_then(|v| v.as_int()));
+    let weights : Vec<i64> = g.edges_between(1, 3).iter().map(|&e| g.edge_attributes().get(e, "weight").unwrap().as_int().unwrap()).collect();
+    assert_eq!(vec![-1, 5], weights);
+    assert!(!g.has_edge(1, 0));
+}
+
+#[test]
+fn dimacs_max_flow_round_trip() {
+    let text = "c A two-path network\np max 4 4\nn 1 s\nn 4 t\na 1 2 3\na 1 3 2\na 2 4 2\na 3 4 3\n";
+    let problem = from_dimacs_max_flow_str(text).unwrap();
+    assert_eq!(0, problem.source);
+    assert_eq!(3, problem.sink);
+    let e = problem.graph.find_edge(2, 3).unwrap();
+    assert_eq!(Some(3), problem.graph.edge_attributes().get(e, "capacity").and_then(|v| v.as_int()));
+
+    let written = make_dimacs_max_flow_string(&problem.graph, problem.source, problem.sink);
+    assert_eq!("p max 4 4\nn 1 s\nn 4 t\na 1 2 3\na 1 3 2\na 2 4 2\na 3 4 3\n", written);
+}
+
+#[test]
+fn dimacs_lengths_must_be_whole() {
+    let mut g = unconnected((0..2).collect(), true);
+    let e = g.add_directed_edge(0, 1);
+    g.edge_attributes_mut().set(e, "weight", 1.5).unwrap();
+    let mut written = Vec::new();
+    assert_eq!(Some(std::io::ErrorKind::InvalidInput), write_dimacs_sp(&g, &mut written).err().map(|e| e.kind()));
+    assert!(written.is_empty());
+
+    g.edge_attributes_mut().undeclare("weight");
+    g.edge_attributes_mut().set(e, "weight", -4i64).unwrap();
+    let h = from_dimacs_sp_str(&make_dimacs_sp_string(&g)).unwrap();
+    assert_eq!(Some(-4), h.edge_attributes().get(e, "weight").and_then(|v| v.as_int()));
+}
+
+#[test]
+fn whole_float_weights_convert_to_dimacs() {
+    // Pajek and edge list weights are always floats.
+    let g = graph::graph_builders::pajek::from_pajek_str("*Vertices 2\n*Arcs\n1 2 3\n").unwrap();
+    assert_eq!("p sp 2 1\na 1 2 3\n", make_dimacs_sp_string(&g));
+
+    let options = graph::graph_builders::edge_list::EdgeListOptions {
+        weight_column : Some(graph::graph_builders::edge_list::Column::Index(2)), ..Default::default()
+    };
+    let g = graph::graph_builders::edge_list::from_edge_list_str::<String>("source,target,weight\na,b,-2\n", &options).unwrap();
+    let h = from_dimacs_sp_str(&make_dimacs_sp_string(&g)).unwrap();
+    assert_eq!(Some(-2), h.edge_attributes().get(h.find_edge(0, 1).unwrap(), "weight").and_then(|v| v.as_int()));
+}
+
+#[test]
+fn dimacs_errors() {
+    let text = "p sp 3 3\na 0 1 4\na 1 2\nx\na 1 3 2\n";
+    let err = from_dimacs_sp_reader_with_mode(text.as_bytes(), ParseMode::Lenient).err().unwrap();
+    let locations : Vec<(usize, usize)> = err.diagnostics().iter().map(|d| (d.line, d.column)).collect();
+    assert_eq!(vec![(2, 3), (3, 6), (4, 1)], locations);
+
+    let err = from_dimacs_sp_str("p sp 2 2\na 1 2 1\n").err().unwrap();
+    assert_eq!("line 1, column 1 (in DIMACS): expected 2 arcs, as given on the problem line, found 1", format!("{}", err));
+
+    let err = from_dimacs_max_flow_str("p max 2 1\nn 1 s\na 1 2 1\n").err().unwrap();
+    assert_eq!("line 4, column 1 (in DIMACS): expected a sink, given by n <vertex> t, found end of file", format!("{}", err));
+}
+
+#[test]
+fn read_matrix_market() {
+    let g = from_matrix_market_file("test_data/graph11.mtx").unwrap();
+
+    assert!(!g.is_directed());
+    assert_eq!(3, g.number_of_vertices());
+    assert!(g.has_edge(0, 1));
+    assert!(g.has_edge(1, 0));
+    assert!(g.has_edge(2, 2));
+    let e = g.find_edge(0, 2).unwrap();
+    assert_eq!(Some(1.5), g.edge_attributes().get(e, "weight").and_then(|v| v.as_float()));
+
+    assert_eq!("%%MatrixMarket matrix coordinate real symmetric\n3 3 4\n2 1 0.5\n3 1 1.5\n3 2 2\n3 3 4.25\n",
+               make_matrix_market_string(&g));
+}
+
+#[test]
+fn matrix_market_pattern_round_trip() {
+    let mut g = unconnected((0..3).collect(), true);
+    g.add_directed_edge(0, 2);
+    g.add_directed_edge(2, 1);
+    let text = make_matrix_market_string(&g);
+    assert_eq!("%%MatrixMarket matrix coordinate pattern general\n3 3 2\n1 3\n3 2\n", text);
+
+    let h = from_matrix_market_str(&text).unwrap();
+    assert!(h.is_directed());
+    assert!(h.has_edge(0, 2));
+    assert!(h.has_edge(2, 1));
+    assert!(!h.has_edge(1, 2));
+}
+
+#[test]
+fn matrix_market_errors() {
+    let err = from_matrix_market_str("%%MatrixMarket matrix coordinate complex general\n1 1 0\n").err().unwrap();
+    assert_eq!("line 1, column 1 (in Matrix Market): expected real, integer or pattern, found \"complex\"", format!("{}", err));
+
+    let err = from_matrix_market_str("%%MatrixMarket matrix coordinate pattern general\n2 3 0\n").err().unwrap();
+    assert_eq!("line 2, column 3 (in Matrix Market): expected 2 columns, for a square matrix, found \"3\"", format!("{}", err));
+
+    let text = "%%MatrixMarket matrix coordinate integer symmetric\n3 3 3\n1 2 4\n4 1 1\n2 1 x\n";
+    let err = from_matrix_market_reader_with_mode(text.as_bytes(), ParseMode::Lenient).err().unwrap();
+    let locations : Vec<(usize, usize)> = err.diagnostics().iter().map(|d| (d.line, d.column)).collect();
+    assert_eq!(vec![(3, 3), (4, 1), (5, 5)], locations);
+}
@@ -0,0 +1,145 @@
+extern crate graph;
+
+use graph::graph_builders::*;
+use graph::graph_builders::dot::*;
+
+#[test]
+fn write_undirected_graph() {
+    let mut g = unconnected(vec!["A", "B \"quoted\"", "C"], false);
+    g.add_undirected_edge(0, 1);
+    g.add_undirected_edge(2, 1);
+
+    let mut expected = String::from("graph {\n");
+    expected.push_str("    0 [label=\"A\"];\n");
+    expected.push_str("    1 [label=\"B \\\"quoted\\\"\"];\n");
+    expected.push_str("    2 [label=\"C\"];\n");
+    expected.push_str("    0 -- 1;\n");
+    expected.push_str("    1 -- 2;\n");
+    expected.push_str("}\n");
+
+    assert_eq!(expected, make_dot_string(&g));
+}
+
+#[test]
+fn write_styled_graph() {
+    let mut g = unconnected(vec![1, 2, 3], true);
+    g.add_directed_edge(0, 1);
+    let e = g.add_directed_edge(1, 2);
+
+    let dot = make_dot_string_with_style(&g,
+        |index, _| if index == 0 { vec![(String::from("shape"), String::from("box"))] } else { Vec::new() },
+        |edge, _, _| if edge == e { vec![(String::from("color"), String::from("red")), (String::from("label"), String::from("x"))] } else { Vec::new() },
+        false);
+
+    assert!(dot.starts_with("digraph {\n"));
+    assert!(dot.contains("    0 [label=\"1\", shape=\"box\"];\n"));
+    assert!(dot.contains("    0 -> 1;\n"));
+    assert!(dot.contains("    1 -> 2 [color=\"red\", label=\"x\"];\n"));
+}
+
+#[test]
+fn annotate_dfs_edge_types() {
+    let mut g = unconnected(vec!["A", "B", "C"], true);
+    g.add_directed_edge(0, 1);
+    g.add_directed_edge(1, 2);
+    g.add_directed_edge(2, 0);
+    g.add_directed_edge(0, 2);
+
+    let dot = make_dot_string_with_style(&g, |_, _| Vec::new(), |_, _, _| Vec::new(), true);
+
+    assert!(dot.contains("    0 -> 1 [label=\"tree\", style=\"bold\"];\n"));
+    assert!(dot.contains("    1 -> 2 [label=\"tree\", style=\"bold\"];\n"));
+    assert!(dot.contains("    2 -> 0 [label=\"back\", style=\"dashed\"];\n"));
+    assert!(dot.contains("    0 -> 2 [label=\"forward\", style=\"dotted\"];\n"));
+}
+
+#[test]
+fn read_dot_file() {
+    let g = from_dot_file("test_data/graph8.dot").unwrap();
+
+    assert!(g.is_directed());
+    let names : Vec<String> = (0..g.number_of_vertices()).map(|i| g.node_from_index(i)).collect();
+    assert_eq!(vec!["London", "New York", "Paris", "Berlin", "Rome"], names);
+    assert!(g.has_edge(1, 0));
+    assert!(g.has_edge(0, 2));
+    assert!(g.has_edge(2, 3));
+    assert!(g.has_edge(2, 4));
+    assert!(g.has_edge(4, 0));
+    // The graph is strict, so the repeated London -> Paris edge is merged.
+    assert_eq!(5, g.number_of_edges());
+
+    assert_eq!(Some("red"), g.node_attributes().get(0, "colour").and_then(|v| v.as_str()));
+    assert_eq!(Some("box"), g.node_attributes().get(3, "shape").and_then(|v| v.as_str()));
+    let e = g.find_edge(1, 0).unwrap();
+    assert_eq!(Some("2"), g.edge_attributes().get(e, "weight").and_then(|v| v.as_str()));
+    let e = g.find_edge(2, 4).unwrap();
+    assert_eq!(Some("dashed"), g.edge_attributes().get(e, "style").and_then(|v| v.as_str()));
+    let e = g.find_edge(4, 0).unwrap();
+    assert_eq!(None, g.edge_attributes().get(e, "style"));
+}
+
+#[test]
+fn read_dot_without_attributes() {
+    let text = "graph { a -- b -- c [label=\"x\"]; a -- b; \"quoted \\\"id\\\"\" }";
+    let g = from_dot_reader_with_options(text.as_bytes(), ParseMode::Strict, false).unwrap();
+
+    assert!(!g.is_directed());
+    assert_eq!(4, g.number_of_vertices());
+    assert_eq!("quoted \"id\"", g.node_from_index(3));
+    // Not strict, so parallel edges are kept.
+    assert_eq!(3, g.number_of_edges());
+    assert!(g.edge_attributes().is_empty());
+}
+
+#[test]
+fn dot_round_trip() {
+    let mut g = unconnected(vec!["a", "b c", "d"], true);
+    g.add_directed_edge(0, 1);
+    g.add_directed_edge(1, 2);
+
+    let h = from_dot_str(&make_dot_string(&g)).unwrap();
+
+    // The writer uses indices as IDs, with the labels as attributes.
+    assert_eq!(3, h.number_of_vertices());
+    assert!(h.has_edge(0, 1));
+    assert!(h.has_edge(1, 2));
+    assert_eq!(Some("b c"), h.node_attributes().get(1, "label").and_then(|v| v.as_str()));
+}
+
+#[test]
+fn dot_errors_have_locations() {
+    let err = from_dot_str("graph {\n  a -- b\n  b -> c\n}\n").err().unwrap();
+    assert_eq!("line 3, column 5 (in DOT graph): expected -- in an undirected graph, found \"->\"", format!("{}", err));
+
+    let err = from_dot_str("digraph {\n  a -> [x=1]\n}\n").err().unwrap();
+    assert_eq!((2, 8), (err.diagnostics()[0].line, err.diagnostics()[0].column));
+
+    let err = from_dot_str("digraph {\n  \"a\n").err().unwrap();
+    assert_eq!("a closing '\"'", err.diagnostics()[0].expected);
+
+    let text = "graph {\n  a -> b\n  c -> d\n}\n";
+    let err = from_dot_reader_with_options(text.as_bytes(), ParseMode::Lenient, true).err().unwrap();
+    assert_eq!(2, err.diagnostics().len());
+}
+
+#[test]
+fn attributes_that_cant_be_written_are_ignored() {
+    let g = from_dot_str("digraph { a [\"first name\"=x, colour=red]; a -> b [\"my attr\"=1, w=2] }").unwrap();
+    assert_eq!(vec!["colour"], g.node_attributes().names().collect::<Vec<&str>>());
+    assert_eq!(vec!["w"], g.edge_attributes().names().collect::<Vec<&str>>());
+
+    let h = from_str_with_nodes::<String>(&make_serialization_string_with_nodes(&g)).unwrap();
+    assert_eq!(Some("red"), h.node_attributes().get(0, "colour").and_then(|v| v.as_str()));
+}
+
+#[test]
+fn deeply_nested_subgraphs_are_an_error() {
+    let text = format!("graph {{ {}a{} }}", "{ ".repeat(20000), " }".repeat(20000));
+    let err = from_dot_str(&text).err().unwrap();
+    assert_eq!("subgraphs nested no more than 256 deep", err.diagnostics()[0].expected);
+    // At the 257th subgraph's brace
+    assert_eq!(9 + 2 * 256, err.diagnostics()[0].column);
+
+    let text = format!("graph {{ {}a{} }}", "{ ".repeat(256), " }".repeat(256));
+    assert_eq!(1, from_dot_str(&text).unwrap().number_of_vertices());
+}
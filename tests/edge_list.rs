@@ -0,0 +1,99 @@
+extern crate graph;
+
+use graph::graph_builders::*;
+use graph::graph_builders::edge_list::*;
+
+#[test]
+fn read_csv_with_named_columns() {
+    let options = EdgeListOptions { source_column : Column::Name(String::from("from")),
+                                    target_column : Column::Name(String::from("to")),
+                                    weight_column : Some(Column::Name(String::from("cost"))),
+                                    directed      : false,
+                                    ..EdgeListOptions::csv() };
+    let g = from_edge_list_file::<String>("test_data/edges.csv", &options).unwrap();
+
+    assert!(!g.is_directed());
+    assert_eq!(3, g.number_of_vertices());
+    assert_eq!("London", g.node_from_index(0));
+    assert_eq!("New York, NY", g.node_from_index(2));
+    assert!(g.has_edge(1, 0));
+    let e = g.find_edge(1, 2).unwrap();
+    assert_eq!(Some(7.0), g.edge_attributes().get(e, "weight").and_then(|v| v.as_float()));
+    let e = g.find_edge(2, 0).unwrap();
+    assert_eq!(None, g.edge_attributes().get(e, "weight"));
+}
+
+#[test]
+fn read_tsv_without_header() {
+    let options = EdgeListOptions { has_header : false, source_column : Column::Index(1), target_column : Column::Index(0),
+                                    ..EdgeListOptions::tsv() };
+    let g = from_edge_list_str::<usize>("10\t20\n20\t30\n", &options).unwrap();
+
+    assert_eq!(vec![20, 10, 30], (0..3).map(|i| g.node_from_index(i)).collect::<Vec<usize>>());
+    assert!(g.has_edge(0, 1));
+    assert!(g.has_edge(2, 0));
+    assert!(!g.has_edge(1, 0));
+}
+
+#[test]
+fn edge_list_round_trip() {
+    let mut g = unconnected(vec![String::from("a,b"), String::from("\"c\""), String::from(" d")], true);
+    let e = g.add_directed_edge(0, 1);
+    g.add_directed_edge(2, 0);
+    g.edge_attributes_mut().set(e, "weight", 0.25).unwrap();
+
+    let options = EdgeListOptions { weight_column : Some(Column::Index(2)), ..EdgeListOptions::csv() };
+    let text = make_edge_list_string(&g, &options);
+    assert_eq!("source,target,weight\n\"a,b\",\"\"\"c\"\"\",0.25\n\" d\",\"a,b\",\n", text);
+
+    let h = from_edge_list_str::<String>(&text, &options).unwrap();
+    assert_eq!("a,b", h.node_from_index(0));
+    assert_eq!("\"c\"", h.node_from_index(1));
+    assert_eq!(" d", h.node_from_index(2));
+    let e = h.find_edge(0, 1).unwrap();
+    assert_eq!(Some(0.25), h.edge_attributes().get(e, "weight").and_then(|v| v.as_float()));
+}
+
+#[test]
+fn edge_list_errors() {
+    let options = EdgeListOptions { weight_column : Some(Column::Index(2)), ..EdgeListOptions::csv() };
+    let text = "source,target,weight\na,b,1\nc\n\"d,e,2\nf,g,heavy\n";
+    let err = from_edge_list_reader_with_mode::<String, _>(text.as_bytes(), &options, ParseMode::Lenient).err().unwrap();
+    let locations : Vec<(usize, usize)> = err.diagnostics().iter().map(|d| (d.line, d.column)).collect();
+    assert_eq!(vec![(3, 2), (4, 1), (5, 5)], locations);
+
+    let options = EdgeListOptions { source_column : Column::Name(String::from("src")), ..EdgeListOptions::csv() };
+    let err = from_edge_list_str::<String>("source,target\na,b\n", &options).err().unwrap();
+    assert_eq!("line 1, column 1 (in edge list): expected a source column named \"src\" in the header, found no such column",
+               format!("{}", err));
+}
+
+#[test]
+fn edge_list_round_trip_with_other_columns() {
+    let mut g = unconnected(vec![String::from("a"), String::from("%b"), String::from("#c")], true);
+    let e = g.add_directed_edge(0, 1);
+    g.add_directed_edge(2, 0);
+    g.edge_attributes_mut().set(e, "weight", 1.5).unwrap();
+
+    let options = EdgeListOptions { delimiter : ';', source_column : Column::Index(3), target_column : Column::Index(1),
+                                    weight_column : Some(Column::Name(String::from("w"))),
+                                    comment_prefix : Some(String::from("%")), ..EdgeListOptions::csv() };
+    let text = make_edge_list_string(&g, &options);
+    assert_eq!("w;target;;source\n1.5;\"%b\";;a\n;a;;#c\n", text);
+
+    let h = from_edge_list_str::<String>(&text, &options).unwrap();
+    assert_eq!(2, h.number_of_edges());
+    let (a, b, c) = (h.index_from_node(String::from("a")), h.index_from_node(String::from("%b")),
+                     h.index_from_node(String::from("#c")));
+    let e = h.find_edge(a, b).unwrap();
+    assert_eq!(Some(1.5), h.edge_attributes().get(e, "weight").and_then(|v| v.as_float()));
+    assert!(h.find_edge(c, a).is_some());
+}
+
+#[test]
+fn edge_list_columns_must_not_share_an_index() {
+    let g = unconnected(vec![1, 2], true);
+    let options = EdgeListOptions { target_column : Column::Index(0), ..EdgeListOptions::csv() };
+    let err = write_edge_list(&g, Vec::new(), &options).err().unwrap();
+    assert_eq!(std::io::ErrorKind::InvalidInput, err.kind());
+}
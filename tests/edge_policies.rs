@@ -0,0 +1,97 @@
+extern crate graph;
+use graph::*;
+use graph::graph_builders::*;
+
+#[test]
+fn merge_parallel_edges_by_default() {
+    let mut g = unconnected(vec![0, 1, 2], true);
+    let e1 = g.add_directed_edge(0, 1);
+    let e2 = g.add_directed_edge(0, 1);
+
+    assert_eq!(e1, e2);
+    assert_eq!(1, g.number_of_edges());
+    assert_eq!(1, g.get_degree_from_index(0));
+    assert_eq!((0, 1), g.edge_endpoints(e1));
+}
+
+#[test]
+fn keep_parallel_edges() {
+    let config = GraphConfig { parallel_edges : ParallelEdgePolicy::Keep, self_loops : SelfLoopPolicy::Allow };
+    let mut g = unconnected_with_config(vec![0, 1, 2], true, config);
+    let e1 = g.add_directed_edge(0, 1);
+    let e2 = g.add_directed_edge(0, 1);
+    g.add_directed_edge(0, 2);
+
+    assert!(e1 != e2);
+    assert_eq!(3, g.number_of_edges());
+    assert_eq!(3, g.get_degree_from_index(0));
+    assert_eq!(vec![e1, e2], g.edges_between(0, 1));
+    assert_eq!(Some(e1), g.find_edge(0, 1));
+
+    let mut expected_string = String::from("// Graph\n");
+    expected_string.push_str("format_version: 1\n");
+    expected_string.push_str("number_of_vertices: 3\n");
+    expected_string.push_str("directed: true\n");
+    expected_string.push_str("parallel_edges: keep\n");
+    expected_string.push_str("edges:\n");
+    expected_string.push_str("0 1\n");
+    expected_string.push_str("0 1\n");
+    expected_string.push_str("0 2\n");
+    assert_eq!(expected_string, make_serialization_string(&g));
+}
+
+#[test]
+fn reject_parallel_edges_and_self_loops() {
+    let config = GraphConfig { parallel_edges : ParallelEdgePolicy::Reject, self_loops : SelfLoopPolicy::Reject };
+    let mut g = unconnected_with_config(vec![0, 1, 2], false, config);
+    g.add_undirected_edge(0, 1);
+
+    assert_eq!(Err(EdgeError::ParallelEdge(1, 0)), g.try_add_undirected_edge(1, 0));
+    assert_eq!(Err(EdgeError::SelfLoop(2)), g.try_add_undirected_edge(2, 2));
+    assert_eq!(1, g.number_of_edges());
+}
+
+#[test]
+fn undirected_self_loop() {
+    let mut g = unconnected(vec![0, 1], false);
+    g.add_undirected_edge(0, 0);
+    g.add_undirected_edge(0, 1);
+
+    // A self-loop counts twice towards the degree, but is only serialized once.
+    assert_eq!(3, g.get_degree_from_index(0));
+    assert_eq!(1, g.get_degree_from_index(1));
+
+    let mut expected_string = String::from("// Graph\n");
+    expected_string.push_str("format_version: 1\n");
+    expected_string.push_str("number_of_vertices: 2\n");
+    expected_string.push_str("directed: false\n");
+    expected_string.push_str("edges:\n");
+    expected_string.push_str("0 0\n");
+    expected_string.push_str("0 1\n");
+    assert_eq!(expected_string, make_serialization_string(&g));
+}
+
+#[test]
+fn read_multigraph_from_file() {
+    let g = from_file("test_data/graph5").unwrap();
+
+    assert_eq!(ParallelEdgePolicy::Keep, g.config().parallel_edges);
+    assert_eq!(4, g.number_of_edges());
+    assert_eq!(2, g.get_degree_from_index(0));
+    assert_eq!(3, g.get_degree_from_index(1));
+    assert_eq!(3, g.get_degree_from_index(2));
+    assert_eq!(2, g.edges_between(1, 0).len());
+
+    let mut edges = Vec::<(usize, usize, DFSEdgeType)>::new();
+    g.depth_first_iter_from_index(|_| { },
+                                  |_| { },
+                                  |s, d, t, _| edges.push((*s, *d, t)),
+                                  0);
+    // The second edge between 0 and 1 forms a cycle, as does the self-loop.
+    let expected_edges =
+        vec![(0, 1, DFSEdgeType::Tree),
+             (1, 0, DFSEdgeType::Back),
+             (1, 2, DFSEdgeType::Tree),
+             (2, 2, DFSEdgeType::Back)];
+    assert_eq!(expected_edges, edges);
+}
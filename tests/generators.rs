@@ -0,0 +1,92 @@
+extern crate graph;
+use graph::*;
+use graph::generators::*;
+
+fn degrees(g : &Graph<usize>) -> Vec<usize> {
+    (0..g.number_of_vertices()).map(|v| g.get_degree_from_index(v)).collect()
+}
+
+fn is_regular(g : &Graph<usize>, degree : usize) -> bool {
+    degrees(g).iter().all(|&d| d == degree)
+}
+
+#[test]
+fn simple_families() {
+    let g = complete(5, false);
+    assert_eq!((5, 10), (g.number_of_vertices(), g.number_of_edges()));
+    assert!(is_regular(&g, 4));
+    let g = complete(4, true);
+    assert_eq!(12, g.number_of_edges());
+    assert!(g.has_edge(3, 0) && g.has_edge(0, 3));
+
+    let g = path(4, true);
+    assert_eq!(3, g.number_of_edges());
+    assert!(g.has_edge(1, 2) && !g.has_edge(2, 1));
+    assert_eq!(vec![1, 2, 2, 1], degrees(&path(4, false)));
+
+    let g = cycle(5, true);
+    assert!(g.has_edge(4, 0) && is_regular(&g, 1));
+    assert!(is_regular(&cycle(5, false), 2));
+
+    let g = star(4, false);
+    assert_eq!(vec![4, 1, 1, 1, 1], degrees(&g));
+    let g = wheel(5, false);
+    assert_eq!((6, 10), (g.number_of_vertices(), g.number_of_edges()));
+    assert_eq!(vec![5, 3, 3, 3, 3, 3], degrees(&g));
+    assert!(wheel(5, true).has_edge(5, 1));
+
+    let g = complete_bipartite(2, 3, true);
+    assert_eq!(vec![3, 3, 0, 0, 0], degrees(&g));
+    assert!(g.has_edge(1, 4) && !g.has_edge(0, 1));
+}
+
+#[test]
+fn lattices() {
+    let g = grid_2d(3, 4, false);
+    assert_eq!((12, 17), (g.number_of_vertices(), g.number_of_edges()));
+    assert!(g.has_edge(5, 9) && g.has_edge(5, 6) && !g.has_edge(3, 4));
+    assert_eq!(34, grid_2d(3, 4, true).number_of_edges());
+
+    let g = grid_3d(2, 3, 4, false);
+    assert_eq!((24, 46), (g.number_of_vertices(), g.number_of_edges()));
+    // (1, 2, 3) is next to (0, 2, 3), (1, 1, 3) and (1, 2, 2)
+    assert_eq!(3, g.get_degree_from_index(23));
+    assert!(g.has_edge(23, 11) && g.has_edge(23, 19) && g.has_edge(23, 22));
+
+    let g = torus(3, 4, false);
+    assert_eq!(24, g.number_of_edges());
+    assert!(is_regular(&g, 4) && g.has_edge(3, 0) && g.has_edge(8, 0));
+
+    let g = hypercube(3, false);
+    assert_eq!((8, 12), (g.number_of_vertices(), g.number_of_edges()));
+    assert!(is_regular(&g, 3) && g.has_edge(5, 7) && !g.has_edge(0, 3));
+    assert_eq!(1, hypercube(0, false).number_of_vertices());
+}
+
+#[test]
+fn trees_and_petersen() {
+    let g = binary_tree(6, true);
+    assert_eq!(5, g.number_of_edges());
+    assert!(g.has_edge(2, 5) && !g.has_edge(5, 2));
+    assert_eq!(vec![2, 3, 2, 1, 1, 1], degrees(&binary_tree(6, false)));
+
+    let g = petersen(false);
+    assert_eq!((10, 15), (g.number_of_vertices(), g.number_of_edges()));
+    assert!(is_regular(&g, 3));
+    // Every pair of vertices is at most two edges apart, and there are no triangles.
+    for a in 0..10 {
+        for b in 0..10 {
+            assert!(g.shortest_path_from_index(a, b).unwrap().len() <= 3);
+            for c in 0..10 {
+                assert!(!(g.has_edge(a, b) && g.has_edge(b, c) && g.has_edge(c, a)));
+            }
+        }
+    }
+    assert_eq!(30, petersen(true).number_of_edges());
+}
+
+#[test]
+#[should_panic]
+fn cycle_of_two() {
+    cycle(2, false);
+}
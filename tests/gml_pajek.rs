@@ -0,0 +1,134 @@
+extern crate graph;
+
+use graph::graph_builders::*;
+use graph::graph_builders::gml::*;
+use graph::graph_builders::pajek::*;
+
+#[test]
+fn read_gml() {
+    let g = from_gml_file("test_data/graph12.gml").unwrap();
+
+    assert!(g.is_directed());
+    assert_eq!(vec!["Alice", "Bob & co", "30"], (0..3).map(|i| g.node_from_index(i)).collect::<Vec<String>>());
+    assert!(g.has_edge(0, 1));
+    assert!(g.has_edge(2, 0));
+    assert!(!g.has_edge(1, 0));
+    // Given as an integer and a real, so read as floats
+    assert_eq!(Some(31.0), g.node_attributes().get(0, "age").and_then(|v| v.as_float()));
+    assert_eq!(None, g.node_attributes().get(1, "graphics"));
+    let e = g.find_edge(0, 1).unwrap();
+    assert_eq!(Some("friend"), g.edge_attributes().get(e, "kind").and_then(|v| v.as_str()));
+}
+
+#[test]
+fn gml_round_trip() {
+    let mut g = unconnected(vec![String::from("a \"quoted\" label"), String::from("b")], false);
+    let e = g.add_undirected_edge(0, 1);
+    g.edge_attributes_mut().set(e, "weight", 2.0).unwrap();
+    g.node_attributes_mut().set(1, "rank", 3).unwrap();
+
+    let text = make_gml_string(&g);
+    assert_eq!("graph [\n  directed 0\n  node [\n    id 0\n    label \"a &quot;quoted&quot; label\"\n  ]\n  \
+                node [\n    id 1\n    label \"b\"\n    rank 3\n  ]\n  \
+                edge [\n    source 0\n    target 1\n    weight 2.0\n  ]\n]\n", text);
+
+    let h = from_gml_str(&text).unwrap();
+    assert!(!h.is_directed());
+    assert_eq!("a \"quoted\" label", h.node_from_index(0));
+    assert!(h.has_edge(1, 0));
+    let e = h.find_edge(0, 1).unwrap();
+    assert_eq!(Some(2.0), h.edge_attributes().get(e, "weight").and_then(|v| v.as_float()));
+    assert_eq!(Some(3), h.node_attributes().get(1, "rank").and_then(|v| v.as_int()));
+}
+
+#[test]
+fn gml_errors() {
+    let err = from_gml_str("graph [\n  node [ id 1 ]\n").err().unwrap();
+    assert_eq!("line 1, column 1 (in GML): expected a closing ']' for graph, found end of file", format!("{}", err));
+
+    let text = "graph [\n  node [ id 1 ]\n  node [ id 1 ]\n  node [ label \"x\" ]\n  edge [ source 1 target 2 ]\n]\n";
+    let err = from_gml_reader_with_mode(text.as_bytes(), ParseMode::Lenient).err().unwrap();
+    let locations : Vec<(usize, usize)> = err.diagnostics().iter().map(|d| (d.line, d.column)).collect();
+    assert_eq!(vec![(3, 10), (4, 3), (5, 19)], locations);
+}
+
+#[test]
+fn deeply_nested_gml_lists_are_an_error() {
+    let text = format!("graph [ {}]", "x [ ".repeat(200000) + &"] ".repeat(200000));
+    let err = from_gml_str(&text).err().unwrap();
+    assert_eq!("lists nested no more than 256 deep", err.diagnostics()[0].expected);
+    // At the '[' of the 256th x, inside graph
+    assert_eq!(9 + 4 * 255 + 2, err.diagnostics()[0].column);
+
+    let text = format!("graph [ node [ id 1 ] {}]", "x [ ".repeat(255) + &"] ".repeat(255));
+    assert_eq!(1, from_gml_str(&text).unwrap().number_of_vertices());
+}
+
+#[test]
+fn gml_leaves_out_infinite_reals() {
+    let mut g = unconnected(vec![String::from("a"), String::from("b")], false);
+    let e = g.add_undirected_edge(0, 1);
+    g.node_attributes_mut().set(0, "score", f64::NAN).unwrap();
+    g.node_attributes_mut().set(1, "score", 2.5).unwrap();
+    g.edge_attributes_mut().set(e, "weight", f64::INFINITY).unwrap();
+
+    let text = make_gml_string(&g);
+    assert!(!text.contains("NaN") && !text.contains("inf"));
+    let h = from_gml_str(&text).unwrap();
+    assert_eq!(None, h.node_attributes().get(0, "score").and_then(|v| v.as_float()));
+    assert_eq!(Some(2.5), h.node_attributes().get(1, "score").and_then(|v| v.as_float()));
+    let e = h.find_edge(0, 1).unwrap();
+    assert_eq!(None, h.edge_attributes().get(e, "weight"));
+}
+
+#[test]
+fn read_pajek_with_arcs_and_edges() {
+    let g = from_pajek_file("test_data/graph13.net").unwrap();
+
+    assert!(g.is_directed());
+    assert_eq!(vec!["New York", "Boston", "Chicago", "4"], (0..4).map(|i| g.node_from_index(i)).collect::<Vec<String>>());
+    assert!(g.has_edge(0, 1));
+    assert!(!g.has_edge(1, 0));
+    // Edges become reciprocal arcs with one EdgeIndex
+    assert_eq!(g.find_edge(1, 2), g.find_edge(2, 1));
+    assert!(g.has_edge(0, 3));
+    assert!(g.has_edge(2, 3));
+    let e = g.find_edge(0, 1).unwrap();
+    assert_eq!(Some(2.5), g.edge_attributes().get(e, "weight").and_then(|v| v.as_float()));
+
+    assert_eq!("*Vertices 4\n1 \"New York\"\n2 \"Boston\"\n3 \"Chicago\"\n4 \"4\"\n\
+                *Arcs\n1 2 2.5\n*Edges\n1 4 1\n2 3 1\n3 4 1\n", make_pajek_string(&g));
+}
+
+#[test]
+fn pajek_undirected_round_trip() {
+    let mut g = unconnected(vec![String::from("x"), String::from("y z")], false);
+    g.add_undirected_edge(0, 1);
+    g.add_undirected_edge(1, 1);
+    let text = make_pajek_string(&g);
+    assert_eq!("*Vertices 2\n1 \"x\"\n2 \"y z\"\n*Edges\n1 2\n2 2\n", text);
+
+    let h = from_pajek_str(&text).unwrap();
+    assert!(!h.is_directed());
+    assert_eq!("y z", h.node_from_index(1));
+    assert!(h.has_edge(1, 0));
+    assert!(h.has_edge(1, 1));
+}
+
+#[test]
+fn pajek_errors() {
+    let text = "*Vertices 2\n1 \"a\n3 b\n*Arcs\n1 5\n*Matrix\n";
+    let err = from_pajek_reader_with_mode(text.as_bytes(), ParseMode::Lenient).err().unwrap();
+    let locations : Vec<(usize, usize)> = err.diagnostics().iter().map(|d| (d.line, d.column)).collect();
+    assert_eq!(vec![(2, 3), (3, 1), (5, 3), (6, 1)], locations);
+
+    let err = from_pajek_str("*Edges\n1 2\n").err().unwrap();
+    assert_eq!("line 1, column 1 (in Pajek): expected a *Vertices section before any arcs or edges, found \"*Edges\"", format!("{}", err));
+}
+
+#[test]
+fn pajek_repeated_default_label() {
+    // Vertex 1 has no line of its own, so its label 1 is given the *Vertices line.
+    let err = from_pajek_str("% A comment\n*Vertices 2\n2 \"1\"\n").err().unwrap();
+    assert_eq!("line 3, column 1 (in Pajek): expected unique vertex labels, found a repeat of the label on line 2", format!("{}", err));
+}
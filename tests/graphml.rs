@@ -0,0 +1,91 @@
+extern crate graph;
+
+use graph::graph_builders::*;
+use graph::graph_builders::graphml::*;
+
+#[test]
+fn read_graphml_file() {
+    let g = from_graphml_file::<String>("test_data/graph9.graphml").unwrap();
+
+    assert!(!g.is_directed());
+    assert_eq!(3, g.number_of_vertices());
+    assert_eq!("New York", g.node_from_index(2));
+    assert!(g.has_edge(1, 0));
+    assert!(g.has_edge(2, 1));
+
+    assert_eq!(Some("red & blue"), g.node_attributes().get(0, "colour").and_then(|v| v.as_str()));
+    assert_eq!(Some("white"), g.node_attributes().get(1, "colour").and_then(|v| v.as_str()));
+    let e = g.find_edge(0, 1).unwrap();
+    assert_eq!(Some(1.5), g.edge_attributes().get(e, "weight").and_then(|v| v.as_float()));
+    // Only attributes with names are kept.
+    assert_eq!(vec!["colour"], g.node_attributes().names().collect::<Vec<&str>>());
+}
+
+#[test]
+fn graphml_round_trip() {
+    let mut g = unconnected(vec![String::from("a <b>"), String::from("c \"d\""), String::from("e")], true);
+    g.add_directed_edge(0, 1);
+    let e = g.add_undirected_edge(1, 2);
+    g.node_attributes_mut().declare("rank", graph::attributes::AttributeType::Int, Some(7.into())).unwrap();
+    g.node_attributes_mut().set(2, "rank", 3).unwrap();
+    g.edge_attributes_mut().set(e, "visible", true).unwrap();
+
+    let text = make_graphml_string(&g);
+    let h = from_graphml_str::<String>(&text).unwrap();
+
+    assert!(h.is_directed());
+    for i in 0..3 {
+        assert_eq!(g.node_from_index(i), h.node_from_index(i));
+    }
+    assert!(h.has_edge(0, 1));
+    assert!(h.has_edge(1, 2));
+    assert!(h.has_edge(2, 1));
+    assert!(!h.has_edge(1, 0));
+    assert_eq!(Some(7), h.node_attributes().get(0, "rank").and_then(|v| v.as_int()));
+    assert_eq!(Some(3), h.node_attributes().get(2, "rank").and_then(|v| v.as_int()));
+    let e = h.find_edge(2, 1).unwrap();
+    assert_eq!(Some(true), h.edge_attributes().get(e, "visible").and_then(|v| v.as_bool()));
+}
+
+#[test]
+fn graphml_errors_have_locations() {
+    let text = "<graphml>\n  <graph edgedefault=\"undirected\">\n    <node id=\"a\"/>\n    <edge source=\"a\" target=\"b\" directed=\"true\"/>\n  </graph>\n</graphml>\n";
+    let err = from_graphml_reader_with_mode::<String, _>(text.as_bytes(), ParseMode::Lenient).err().unwrap();
+    let locations : Vec<(usize, usize)> = err.diagnostics().iter().map(|d| (d.line, d.column)).collect();
+    assert_eq!(vec![(4, 22), (4, 33)], locations);
+
+    let err = from_graphml_str::<String>("<graphml>\n  <graph>\n  </graphml>\n").err().unwrap();
+    assert_eq!("line 3, column 3 (in GraphML): expected </graph>, found </graphml>", format!("{}", err));
+
+    let err = from_graphml_str::<usize>("<graphml><graph><node id=\"x\"/></graph></graphml>").err().unwrap();
+    assert_eq!("a valid node label", err.diagnostics()[0].expected);
+}
+
+#[test]
+fn repeated_node_after_a_rejected_one() {
+    let text = "<graphml>\n  <graph>\n    <node id=\"x\"/>\n    <node id=\"1\"/>\n    <node id=\"1\"/>\n  </graph>\n</graphml>\n";
+    let err = from_graphml_reader_with_mode::<usize, _>(text.as_bytes(), ParseMode::Lenient).err().unwrap();
+    let found : Vec<(usize, &str)> = err.diagnostics().iter().map(|d| (d.line, d.found.as_str())).collect();
+    assert_eq!(vec![(3, "\"x\""), (5, "a repeat of the node on line 4")], found);
+}
+
+#[test]
+fn keys_sharing_a_name_with_different_types() {
+    let text = "<graphml>\n  <key id=\"d0\" for=\"node\" attr.name=\"x\" attr.type=\"int\"/>\n  \
+                <key id=\"d1\" for=\"node\" attr.name=\"x\" attr.type=\"string\"/>\n  <graph>\n    \
+                <node id=\"a\"><data key=\"d0\">1</data><data key=\"d1\">one</data></node>\n  </graph>\n</graphml>\n";
+    let err = from_graphml_reader_with_mode::<String, _>(text.as_bytes(), ParseMode::Lenient).err().unwrap();
+    let found : Vec<(usize, &str)> = err.diagnostics().iter().map(|d| (d.line, d.expected.as_str())).collect();
+    assert_eq!(vec![(3, "x to have a single type")], found);
+}
+
+#[test]
+fn keys_whose_names_cant_be_written_are_ignored() {
+    let text = "<graphml>\n  <key id=\"d0\" for=\"node\" attr.name=\"first name\"/>\n  <key id=\"d1\" for=\"node\" attr.name=\"age\" attr.type=\"int\"/>\n  \
+                <graph>\n    <node id=\"a\"><data key=\"d0\">Ann</data><data key=\"d1\">30</data></node>\n  </graph>\n</graphml>\n";
+    let g = from_graphml_str::<String>(text).unwrap();
+    assert_eq!(vec!["age"], g.node_attributes().names().collect::<Vec<&str>>());
+
+    let h = from_str_with_nodes::<String>(&make_serialization_string_with_nodes(&g)).unwrap();
+    assert_eq!(Some(30), h.node_attributes().get(0, "age").and_then(|v| v.as_int()));
+}
@@ -0,0 +1,66 @@
+extern crate graph;
+
+use graph::graph_builders::*;
+
+#[test]
+fn read_labelled_edges_from_file() {
+    let g = from_file_with_nodes::<String>("test_data/graph7").unwrap();
+
+    assert_eq!(3, g.number_of_vertices());
+    assert!(!g.is_directed());
+    assert_eq!("London", g.node_from_index(0));
+    assert_eq!("Paris", g.node_from_index(1));
+    assert_eq!("New York", g.node_from_index(2));
+    assert!(g.has_edge(0, 1));
+    assert!(g.has_edge(2, 1));
+    assert!(g.has_edge(0, 2));
+}
+
+#[test]
+fn labels_refer_to_nodes_section() {
+    let text = "edge_format: label\nedges:\nC -> A\nB A\nnodes:\nA\nB\nC\nD\n";
+    let g = from_str_with_nodes::<String>(text).unwrap();
+
+    assert_eq!(4, g.number_of_vertices());
+    assert!(g.has_edge(2, 0));
+    assert!(g.has_edge(1, 0));
+    assert!(!g.has_edge(0, 1));
+
+    // Only the structure is kept when reading without labels.
+    let h = from_str(text).unwrap();
+    assert_eq!(4, h.number_of_vertices());
+    assert!(h.has_edge(2, 0));
+}
+
+#[test]
+fn labelled_edges_round_trip() {
+    let mut g = unconnected(vec![String::from("a b"), String::from("c"), String::from("isolated")], true);
+    g.add_directed_edge(0, 1);
+    g.add_directed_edge(1, 0);
+    let e = g.find_edge(1, 0).unwrap();
+    g.edge_attributes_mut().set(e, "weight", 2.5).unwrap();
+
+    let text = make_serialization_string_with_labelled_edges(&g);
+    assert!(text.contains("\"a b\" -> c\n"));
+    let h = from_str_with_nodes::<String>(&text).unwrap();
+
+    assert_eq!(3, h.number_of_vertices());
+    assert!(h.has_edge(0, 1));
+    assert!(h.has_edge(1, 0));
+    let e = h.find_edge(1, 0).unwrap();
+    assert_eq!(Some(2.5), h.edge_attributes().get(e, "weight").and_then(|v| v.as_float()));
+}
+
+#[test]
+fn labelled_edge_errors() {
+    let text = "number_of_vertices: 2\nedge_format: label\nedges:\nA -> B\nA => B\nA ->\nA C\nnodes:\nA\nB\n";
+    let err = from_reader_with_nodes_and_mode::<String, _>(text.as_bytes(), ParseMode::Lenient).err().unwrap();
+    let locations : Vec<(usize, usize)> = err.diagnostics().iter().map(|d| (d.line, d.column)).collect();
+
+    assert_eq!(vec![(5, 3), (6, 5), (7, 3)], locations);
+    assert_eq!("a label listed in the nodes: section", err.diagnostics()[2].expected);
+
+    let err = from_str_with_nodes::<String>("number_of_vertices: 3\nedge_format: label\nedges:\nA B\n").err().unwrap();
+    assert_eq!("line 1, column 21 (in header): expected number_of_vertices to match the 2 labels found in the edges, found 3",
+               format!("{}", err));
+}
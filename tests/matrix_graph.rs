@@ -0,0 +1,141 @@
+extern crate graph;
+use graph::*;
+use graph::attributes::{AttributeType, AttributeValue};
+use graph::matrix_graph::MatrixGraph;
+use std::convert::TryFrom;
+
+#[test]
+fn edge_existence() {
+    let mut g = MatrixGraph::unweighted(vec!["A", "B", "C"], true);
+    g.add_directed_edge(0, 1);
+    g.add_undirected_edge(1, 2);
+
+    assert!(g.has_edge(0, 1));
+    assert!(!g.has_edge(1, 0));
+    assert!(g.has_edge(1, 2));
+    assert!(g.has_edge(2, 1));
+
+    assert_eq!(1, g.get_degree_from_index(0));
+    assert_eq!(1, g.get_degree_from_index(1));
+    assert_eq!(1, g.get_degree_from_index(2));
+
+    g.remove_directed_edge(0, 1);
+    assert!(!g.has_edge(0, 1));
+    assert_eq!(0, g.get_degree_from_index(0));
+}
+
+#[test]
+fn weighted_edges_and_transpose() {
+    let mut g = MatrixGraph::weighted(vec![0, 1, 2], true);
+    g.set_directed_weight(0, 2, 2.5);
+    g.add_directed_edge(1, 2);
+
+    assert_eq!(Some(2.5), g.get_weight(0, 2));
+    assert_eq!(Some(1.0), g.get_weight(1, 2));
+    assert_eq!(None, g.get_weight(2, 0));
+
+    let t = g.transpose();
+    assert_eq!(Some(2.5), t.get_weight(2, 0));
+    assert_eq!(None, t.get_weight(0, 2));
+    assert_eq!(vec![vec![0.0, 0.0, 0.0], vec![0.0, 0.0, 0.0], vec![2.5, 1.0, 0.0]], t.to_dense_rows());
+}
+
+#[test]
+fn round_trip_through_adjacency_list() {
+    let g = graph_builders::from_file("test_data/graph4").unwrap();
+    let m = MatrixGraph::try_from(&g).unwrap();
+
+    assert_eq!(g.number_of_vertices(), m.number_of_vertices());
+    assert!(m.is_directed());
+    assert!(m.has_edge(4, 6));
+    assert!(!m.has_edge(6, 4));
+
+    // graph4 lists each adjacency list in increasing order, so we get back exactly what we started with.
+    let g2 = Graph::from(&m);
+    assert_eq!(graph_builders::make_serialization_string(&g), graph_builders::make_serialization_string(&g2));
+}
+
+#[test]
+fn traversal_matches_adjacency_list() {
+    let g = graph_builders::from_file("test_data/graph4").unwrap();
+    let m = MatrixGraph::try_from(&g).unwrap();
+
+    let mut list_edges = Vec::<(usize, usize, DFSEdgeType)>::new();
+    let mut matrix_edges = Vec::<(usize, usize, DFSEdgeType)>::new();
+    g.depth_first_iter_from_index(|_| { }, |_| { }, |s, d, t, _| list_edges.push((*s, *d, t)), 2);
+    m.depth_first_iter_from_index(|_| { }, |_| { }, |s, d, t, _| matrix_edges.push((*s, *d, t)), 2);
+    assert_eq!(list_edges, matrix_edges);
+
+    let mut count = 0;
+    m.breadth_first_iter_from_node(|_| { count += 1; }, |_, _| { }, 2);
+    assert_eq!(7, count);
+}
+
+#[test]
+fn weighted_matrix_round_trip() {
+    let mut m = MatrixGraph::weighted(vec![0, 1, 2], true);
+    m.set_directed_weight(0, 2, 2.5);
+    m.set_directed_weight(2, 1, 4.0);
+    m.add_directed_edge(1, 0);
+
+    let g = Graph::from(&m);
+    assert_eq!(Some(AttributeType::Float), g.edge_attributes().attribute_type("weight"));
+    assert_eq!(Some(&AttributeValue::Float(2.5)), g.edge_attributes().get(g.find_edge(0, 2).unwrap(), "weight"));
+    assert_eq!(Some(&AttributeValue::Float(1.0)), g.edge_attributes().get(g.find_edge(1, 0).unwrap(), "weight"));
+
+    let m2 = MatrixGraph::try_from(&g).unwrap();
+    assert!(m2.is_weighted());
+    assert_eq!(m.to_dense_rows(), m2.to_dense_rows());
+}
+
+#[test]
+fn attributed_graph_round_trip() {
+    let mut g = graph_builders::unconnected(vec![0, 1, 2, 3], false);
+    let edges = [g.add_undirected_edge(0, 1), g.add_undirected_edge(0, 3), g.add_undirected_edge(1, 2), g.add_undirected_edge(2, 2)];
+    g.node_attributes_mut().declare("colour", AttributeType::Str, Some(AttributeValue::from("white"))).unwrap();
+    g.node_attributes_mut().set(2, "colour", "dark red").unwrap();
+    g.edge_attributes_mut().declare("weight", AttributeType::Int, Some(AttributeValue::Int(1))).unwrap();
+    g.edge_attributes_mut().set(edges[1], "label", "long").unwrap();
+    for (i, &edge) in edges.iter().enumerate() {
+        g.edge_attributes_mut().set(edge, "weight", 3 * i as i64).unwrap();
+    }
+    g.metadata_mut().name = Some(String::from("attributed"));
+
+    let m = MatrixGraph::try_from(&g).unwrap();
+    assert_eq!(Some(3.0), m.get_weight(3, 0));
+    assert_eq!(Some(&AttributeValue::from("long")), m.edge_attributes().get(m.edge_key(3, 0), "label"));
+    assert_eq!(Some(&AttributeValue::from("dark red")), m.node_attributes().get(2, "colour"));
+
+    let g2 = Graph::from(&m);
+    assert_eq!(graph_builders::make_serialization_string(&g), graph_builders::make_serialization_string(&g2));
+}
+
+#[test]
+fn parallel_edges_cant_be_converted() {
+    let config = GraphConfig { parallel_edges : ParallelEdgePolicy::Keep, self_loops : SelfLoopPolicy::Allow };
+    let mut g = graph_builders::unconnected_with_config(vec![0, 1], true, config);
+    g.add_directed_edge(0, 1);
+    g.add_directed_edge(0, 1);
+    assert_eq!(Some(EdgeError::ParallelEdge(0, 1)), MatrixGraph::try_from(&g).err());
+}
+
+#[test]
+#[should_panic(expected = "dest_index 3 was >= 3")]
+fn shortest_path_checks_indices() {
+    let g = MatrixGraph::unweighted(vec!["A", "B", "C"], true);
+    g.shortest_path_from_index(0, 3);
+}
+
+#[test]
+fn degrees_match_adjacency_list() {
+    for &directed in &[false, true] {
+        let mut g = graph_builders::unconnected(vec![0, 1, 2], directed);
+        g.add_undirected_edge(0, 0);
+        g.add_undirected_edge(0, 1);
+        g.add_directed_edge(2, 2);
+        let m = MatrixGraph::try_from(&g).unwrap();
+        for v in 0..3 {
+            assert_eq!(g.get_degree_from_index(v), m.get_degree_from_index(v));
+        }
+    }
+}
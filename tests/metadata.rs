@@ -0,0 +1,40 @@
+extern crate graph;
+use graph::graph_builders::*;
+
+#[test]
+fn name_and_description_round_trip() {
+    let text = "// Graph\nformat_version: 1\nname: Roads\ndescription: \"Main roads, \\\"as of\\\" 2020\"\nnumber_of_vertices: 2\ndirected: false\nedges:\n0 1\n";
+    let g = from_str(text).unwrap();
+    assert_eq!(Some("Roads"), g.metadata().name.as_deref());
+    assert_eq!(Some("Main roads, \"as of\" 2020"), g.metadata().description.as_deref());
+    assert_eq!(text, make_serialization_string(&g));
+
+    let mut g = unconnected(vec![0, 1], true);
+    assert_eq!(None, g.metadata().name);
+    g.metadata_mut().name = Some(String::from("two words"));
+    let g2 = from_str(&make_serialization_string(&g)).unwrap();
+    assert_eq!(g.metadata(), g2.metadata());
+}
+
+#[test]
+fn newer_format_version_is_a_warning() {
+    let text = "format_version: 3\nnumber_of_vertices: 2\nlayout: circle\nedges:\n0 1\n";
+    let g = from_str(text).unwrap();
+    assert_eq!(1, g.number_of_edges());
+
+    let warnings : Vec<(usize, String, Severity)> = validate_str(text).into_iter().map(|d| (d.line, d.found, d.severity)).collect();
+    assert_eq!(vec![(1, String::from("format_version 3"), Severity::Warning),
+                    (3, String::from("layout:"), Severity::Warning)], warnings);
+    assert_eq!("warning: line 3, column 1 (in header): expected a known key such as number_of_vertices:, found layout:",
+               format!("{}", validate_str(text)[1]));
+}
+
+#[test]
+fn bad_format_version_and_repeated_metadata() {
+    let err = from_str("format_version: one\nnumber_of_vertices: 1\n").err().unwrap();
+    assert_eq!("a format version such as 1", err.diagnostics()[0].expected);
+    assert_eq!(Severity::Error, err.diagnostics()[0].severity);
+
+    let err = from_str("name: a\nname: b\nnumber_of_vertices: 1\n").err().unwrap();
+    assert_eq!((2, "a second name"), (err.diagnostics()[0].line, err.diagnostics()[0].found.as_str()));
+}
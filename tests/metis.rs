@@ -0,0 +1,108 @@
+extern crate graph;
+
+use graph::graph_builders::*;
+use graph::graph_builders::metis::*;
+
+#[test]
+fn read_metis_with_weights() {
+    let g = from_metis_file("test_data/graph14.graph").unwrap();
+
+    assert!(!g.is_directed());
+    assert_eq!(4, g.number_of_vertices());
+    assert_eq!(4, g.number_of_edges());
+    assert_eq!(Some(3), g.node_attributes().get(3, "size").and_then(|v| v.as_int()));
+    assert_eq!(Some(4), g.node_attributes().get(0, "weight0").and_then(|v| v.as_int()));
+    assert_eq!(Some(0), g.node_attributes().get(0, "weight1").and_then(|v| v.as_int()));
+    let e = g.find_edge(3, 0).unwrap();
+    assert_eq!(Some(7), g.edge_attributes().get(e, "weight").and_then(|v| v.as_int()));
+    assert_eq!(g.find_edge(0, 3), g.find_edge(3, 0));
+
+    // Each adjacency list keeps the order of its line
+    let text = "4 4 111 2\n1 4 0 2 2 4 7\n2 1 1 1 2 3 3\n1 0 0 2 3 4 1\n3 2 2 3 1 1 7\n";
+    assert_eq!(text, make_metis_string(&g));
+}
+
+#[test]
+fn metis_round_trip() {
+    let mut g = unconnected((0..4).collect(), false);
+    g.add_undirected_edge(0, 1);
+    g.add_undirected_edge(1, 2);
+    g.add_undirected_edge(2, 0);
+    let text = make_metis_string(&g);
+    assert_eq!("4 3\n2 3\n1 3\n2 1\n\n", text);
+
+    let h = from_metis_str(&text).unwrap();
+    assert_eq!(4, h.number_of_vertices());
+    assert!(h.has_edge(2, 1));
+    assert_eq!(0, h.get_degree_from_index(3));
+}
+
+#[test]
+fn metis_checks_symmetry() {
+    let text = "3 2 1\n2 4 3 1\n1 5\n2 1\n";
+    let err = from_metis_reader_with_options(text.as_bytes(), ParseMode::Lenient, false).err().unwrap();
+    let messages : Vec<String> = err.diagnostics().iter().map(|d| format!("{}", d)).collect();
+    assert_eq!(vec!["line 2, column 1 (in METIS): expected the same weight as on line 3, found 4 and 5",
+                    "line 2, column 5 (in METIS): expected vertex 3 to list vertex 1 too, as the graph is undirected, found no such neighbour",
+                    "line 4, column 1 (in METIS): expected vertex 2 to list vertex 3 too, as the graph is undirected, found no such neighbour"],
+               messages);
+
+    // The same lines are fine as a directed graph
+    let g = from_metis_reader_with_options("3 4 1\n2 4 3 1\n1 5\n2 1\n".as_bytes(), ParseMode::Strict, true).unwrap();
+    assert!(g.is_directed());
+    assert!(g.has_edge(0, 2));
+    assert!(!g.has_edge(2, 0));
+}
+
+#[test]
+fn metis_errors() {
+    let err = from_metis_str("2 1\n2\n1\n1\n").err().unwrap();
+    assert_eq!("line 4, column 1 (in METIS): expected only 2 vertex lines, found \"1\"", format!("{}", err));
+
+    let err = from_metis_str("2 1\n1 2\n1\n").err().unwrap();
+    assert_eq!("line 2, column 1 (in METIS): expected no self-loops in an undirected graph, found vertex 1 in its own list",
+               format!("{}", err));
+
+    let err = from_metis_str("2 2\n2\n1\n").err().unwrap();
+    assert_eq!("line 1, column 1 (in METIS): expected 2 edges, as given in the header, found 1", format!("{}", err));
+}
+
+// Each graph the writer refuses would otherwise have been written in a way that
+// the reader rejects.
+#[test]
+fn metis_writer_only_writes_what_it_can_read() {
+    let mut g = unconnected((0..2).collect(), false);
+    let edge = g.add_undirected_edge(0, 1);
+    g.edge_attributes_mut().set(edge, "weight", 2.5).unwrap();
+    assert_eq!(Some(std::io::ErrorKind::InvalidInput), write_metis(&g, Vec::new()).err().map(|e| e.kind()));
+    g.edge_attributes_mut().undeclare("weight");
+    g.edge_attributes_mut().set(edge, "weight", 3i64).unwrap();
+    let h = from_metis_str(&make_metis_string(&g)).unwrap();
+    assert_eq!(Some(3), h.edge_attributes().get(h.find_edge(1, 0).unwrap(), "weight").and_then(|w| w.as_int()));
+
+    // A whole float, as Pajek and edge list readers give, is written as an int.
+    g.edge_attributes_mut().undeclare("weight");
+    g.edge_attributes_mut().set(edge, "weight", 3.0).unwrap();
+    assert_eq!("2 1 001\n2 3\n1 3\n", make_metis_string(&g));
+
+    let config = graph::GraphConfig { parallel_edges : graph::ParallelEdgePolicy::Keep, self_loops : graph::SelfLoopPolicy::Allow };
+    let mut g = unconnected_with_config((0..2).collect(), false, config);
+    g.add_undirected_edge(0, 1);
+    g.add_undirected_edge(0, 1);
+    assert_eq!(Some(std::io::ErrorKind::InvalidInput), write_metis(&g, Vec::new()).err().map(|e| e.kind()));
+    // A directed multigraph can be read back.
+    let mut g = unconnected_with_config((0..2).collect(), true, config);
+    g.add_directed_edge(0, 1);
+    g.add_directed_edge(0, 1);
+    let h = from_metis_reader_with_options(make_metis_string(&g).as_bytes(), ParseMode::Strict, true).unwrap();
+    assert_eq!(2, h.number_of_edges());
+
+    // The header counts the arcs that are written, not the edges.
+    let mut g = unconnected((0..3).collect(), true);
+    g.add_undirected_edge(0, 1);
+    g.add_directed_edge(1, 2);
+    let text = make_metis_string(&g);
+    assert_eq!("3 3\n2\n1 3\n\n", text);
+    let h = from_metis_reader_with_options(text.as_bytes(), ParseMode::Strict, true).unwrap();
+    assert!(h.has_edge(0, 1) && h.has_edge(1, 0) && h.has_edge(1, 2));
+}
@@ -0,0 +1,44 @@
+extern crate graph;
+
+use graph::graph_builders::*;
+
+#[test]
+fn labels_round_trip() {
+    let labels = vec![String::from("New York"), String::from("foo-bar"), String::from("ünïcode"),
+                      String::from("C:\\path"), String::from("edges:"), String::from("\"quoted\""),
+                      String::from("a // b"), String::from("tab\there"), String::from("")];
+    let mut g = unconnected(labels.clone(), true);
+    g.add_directed_edge(0, 1);
+    g.node_attributes_mut().set(2, "name", "  padded // text ").unwrap();
+
+    let text = make_serialization_string_with_nodes(&g);
+    let h = from_str_with_nodes::<String>(&text).unwrap();
+
+    for (i, label) in labels.iter().enumerate() {
+        assert_eq!(*label, h.node_from_index(i));
+    }
+    assert!(h.has_edge(0, 1));
+    assert_eq!(Some("  padded // text "), h.node_attributes().get(2, "name").and_then(|v| v.as_str()));
+}
+
+#[test]
+fn quoted_labels_and_escapes() {
+    let text = "number_of_vertices: 3 // three\nedges:\n0 1\nnodes:\n\"New York\" // a comment\n\"say \\\"hi\\\"\"\n\"\\u{e9}t\\u{e9}\"\n";
+    let g = from_str_with_nodes::<String>(text).unwrap();
+
+    assert_eq!("New York", g.node_from_index(0));
+    assert_eq!("say \"hi\"", g.node_from_index(1));
+    assert_eq!("été", g.node_from_index(2));
+}
+
+#[test]
+fn bad_quoting_is_reported() {
+    let text = "number_of_vertices: 3\nedges:\nnodes:\n\"unclosed\n\"bad \\q escape\"\n\"joined\"on\n";
+    let err = from_reader_with_nodes_and_mode::<String, _>(text.as_bytes(), ParseMode::Lenient).err().unwrap();
+    let locations : Vec<(usize, usize)> = err.diagnostics().iter().map(|d| (d.line, d.column)).collect();
+
+    // None of the labels could be read, so there are also too few of them.
+    assert_eq!(vec![(4, 1), (4, 1), (5, 6), (6, 9)], locations);
+    assert!(err.diagnostics().iter().any(|d| d.expected == "a closing '\"'"));
+    assert_eq!("\\q", err.diagnostics()[2].found);
+}
@@ -0,0 +1,89 @@
+extern crate graph;
+extern crate serde_json;
+
+use graph::graph_builders::*;
+use graph::graph_builders::node_link::*;
+
+#[test]
+fn read_networkx_output() {
+    let g = from_node_link_file::<usize>("test_data/graph16.json").unwrap();
+
+    assert!(!g.is_directed());
+    assert_eq!(3, g.number_of_vertices());
+    assert_eq!(3, g.number_of_edges());
+    assert_eq!(Some("club"), g.metadata().name.as_deref());
+    assert_eq!(Some("Officer"), g.node_attributes().get(1, "club").and_then(|v| v.as_str()));
+    assert_eq!(Some(3), g.node_attributes().get(2, "size").and_then(|v| v.as_int()));
+    assert_eq!(None, g.node_attributes().attribute_type("pos"));
+    // Given as both an integer and a real
+    let e = g.find_edge(1, 0).unwrap();
+    assert_eq!(Some(4.0), g.edge_attributes().get(e, "weight").and_then(|v| v.as_float()));
+    let e = g.find_edge(0, 2).unwrap();
+    assert_eq!(None, g.edge_attributes().get(e, "weight"));
+}
+
+#[test]
+fn node_link_round_trip() {
+    let mut g = unconnected(vec![String::from("a \"quoted\" name"), String::from("7"), String::from("c")], true);
+    g.metadata_mut().name = Some(String::from("demo"));
+    let e = g.add_directed_edge(0, 1);
+    g.add_directed_edge(2, 2);
+    g.edge_attributes_mut().set(e, "label", "x\ny").unwrap();
+    g.node_attributes_mut().set(2, "visited", true).unwrap();
+
+    let text = make_node_link_string(&g);
+    let expected = "{\n  \"directed\": true,\n  \"multigraph\": false,\n  \"graph\": {\"name\": \"demo\"},\n  \"nodes\": [\n    \
+                    {\"id\": \"a \\\"quoted\\\" name\"},\n    {\"id\": 7},\n    {\"id\": \"c\", \"visited\": true}\n  ],\n  \"links\": [\n    \
+                    {\"source\": \"a \\\"quoted\\\" name\", \"target\": 7, \"label\": \"x\\ny\"},\n    {\"source\": \"c\", \"target\": \"c\"}\n  ]\n}\n";
+    assert_eq!(expected, text);
+    let value : serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(Some(7), value["nodes"][1]["id"].as_i64());
+
+    let h = from_node_link_str::<String>(&text).unwrap();
+    assert_eq!(text, make_node_link_string(&h));
+}
+
+#[test]
+fn d3_style_multigraph() {
+    let text = r#"{"multigraph": true, "nodes": [{"id": "x"}, {"id": "y"}],
+                   "edges": [{"source": "x", "target": "y", "key": 0}, {"source": "x", "target": "y", "key": 1}]}"#;
+    let g = from_node_link_str::<String>(text).unwrap();
+    assert!(!g.is_directed());
+    assert_eq!(2, g.edges_between(0, 1).len());
+}
+
+#[test]
+fn node_link_errors() {
+    let err = from_node_link_str::<usize>("{\"nodes\": [{\"id\": 1},\n {\"id\": 01}]}").err().unwrap();
+    assert_eq!("line 2, column 9 (in node-link JSON): expected a number, found \"01\"", format!("{}", err));
+
+    let text = "{\"nodes\": [{\"id\": 1}, {\"id\": 1}, {\"id\": \"b\"}, {}],\n \"links\": [{\"source\": 1, \"target\": 2}]}";
+    let err = from_node_link_reader_with_mode::<usize, _>(text.as_bytes(), ParseMode::Lenient).err().unwrap();
+    let found : Vec<(usize, usize, &str)> = err.diagnostics().iter().map(|d| (d.line, d.column, d.found.as_str())).collect();
+    assert_eq!(vec![(1, 30, "a second node with id 1"), (1, 41, "\"b\""), (1, 47, "none"), (2, 36, "2")], found);
+}
+
+#[test]
+fn json_numbers_and_escapes() {
+    let text = "{\"nodes\": [{\"id\": \"\\ud83d\\ude00\\u00e9\\/\"}, {\"id\": \"b\"}],\n \"links\": [{\"source\": \"b\", \"target\": \"b\", \"w\": -1.5e-3}]}";
+    let g = from_node_link_str::<String>(text).unwrap();
+    assert_eq!("\u{1f600}\u{e9}/", g.node_from_index(0));
+    let e = g.find_edge(1, 1).unwrap();
+    assert_eq!(Some(-0.0015), g.edge_attributes().get(e, "w").and_then(|v| v.as_float()));
+
+    let deep = format!("{}{}", "[".repeat(1000), "]".repeat(1000));
+    assert!(from_node_link_str::<String>(&deep).is_err());
+}
+
+#[test]
+fn fields_that_cant_be_attribute_names_are_ignored() {
+    let text = "{\"nodes\": [{\"id\": 1, \"my colour\": \"red\", \"\": 2, \"size_2\": 3}, {\"id\": 2}],\n\
+                 \"links\": [{\"source\": 1, \"target\": 2, \"weight-kg\": 4, \"w\": 5}]}";
+    let g = from_node_link_str::<usize>(text).unwrap();
+    assert_eq!(vec!["size_2"], g.node_attributes().names().collect::<Vec<&str>>());
+    assert_eq!(vec!["w"], g.edge_attributes().names().collect::<Vec<&str>>());
+
+    // So the graph can be written in the native format and read back.
+    let h = from_str_with_nodes::<usize>(&make_serialization_string_with_nodes(&g)).unwrap();
+    assert_eq!(Some(3), h.node_attributes().get(0, "size_2").and_then(|v| v.as_int()));
+}
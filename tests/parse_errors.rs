@@ -0,0 +1,107 @@
+extern crate graph;
+use graph::graph_builders::*;
+
+const BAD_GRAPH : &str = "// Graph
+number_of_vertices: 3
+directed: maybe
+edges:
+0 1
+0 x
+1 2 3
+nodes: extra
+";
+
+#[test]
+fn strict_mode_reports_first_error() {
+    let err = from_str(BAD_GRAPH).err().unwrap();
+
+    let expected = Diagnostic { line : 3, column : 11, section : Section::Header,
+                                expected : String::from("true or false"), found : String::from("\"maybe\""),
+                                severity : Severity::Error };
+    assert_eq!(&[expected], err.diagnostics());
+    assert_eq!("line 3, column 11 (in header): expected true or false, found \"maybe\"", format!("{}", err));
+}
+
+#[test]
+fn lenient_mode_reports_every_error() {
+    let err = from_reader_with_mode(BAD_GRAPH.as_bytes(), ParseMode::Lenient).err().unwrap();
+    let locations : Vec<(usize, usize, Section)> = err.diagnostics().iter().map(|d| (d.line, d.column, d.section)).collect();
+
+    assert_eq!(vec![(3, 11, Section::Header),
+                    (6, 3, Section::Edges),
+                    (7, 5, Section::Edges),
+                    (8, 8, Section::Nodes)], locations);
+    // The spurious text itself is reported, not the keyword
+    assert_eq!("\"extra\"", err.diagnostics()[3].found);
+}
+
+#[test]
+fn missing_number_of_vertices() {
+    let err = from_str("directed: true\nedges:\n0 1\n").err().unwrap();
+
+    assert_eq!(1, err.diagnostics().len());
+    assert_eq!(4, err.diagnostics()[0].line);
+    assert_eq!("number_of_vertices:", err.diagnostics()[0].expected);
+}
+
+#[test]
+fn unknown_key_and_bad_attribute_value() {
+    let text = "number_of_vertices: 2\ncolour: red\nnode_attribute: size int\nnode_attributes:\n1 size big\n";
+    let err = from_reader_with_mode(text.as_bytes(), ParseMode::Lenient).err().unwrap();
+
+    assert_eq!(2, err.diagnostics().len());
+    assert_eq!((2, 1), (err.diagnostics()[0].line, err.diagnostics()[0].column));
+    assert_eq!("colour:", err.diagnostics()[0].found);
+    assert_eq!(Severity::Warning, err.diagnostics()[0].severity);
+    assert_eq!((5, 8, Section::NodeAttributes), (err.diagnostics()[1].line, err.diagnostics()[1].column, err.diagnostics()[1].section));
+    assert_eq!("a value of type int", err.diagnostics()[1].expected);
+    assert_eq!(Severity::Error, err.diagnostics()[1].severity);
+}
+
+#[test]
+fn unknown_key_is_only_a_warning() {
+    let g = from_str("number_of_vertices: 2
+colour: red
+edges:
+0 1
+").unwrap();
+    assert_eq!(1, g.number_of_edges());
+}
+
+#[test]
+fn unknown_sections_are_skipped() {
+    // A section that follows the edges, from a newer writer.
+    let text = "number_of_vertices: 3
+edges:
+0 1
+positions:
+0 1.5 2.5
+1 \"unterminated
+nodes:
+a
+b
+c
+";
+    let g = from_str_with_nodes::<String>(text).unwrap();
+    assert_eq!((3, 1), (g.number_of_vertices(), g.number_of_edges()));
+    assert_eq!("c", g.node_from_index(2));
+    let warnings = validate_str(text);
+    assert_eq!(1, warnings.len());
+    assert_eq!((4, Section::Edges, Severity::Warning), (warnings[0].line, warnings[0].section, warnings[0].severity));
+    assert_eq!("positions:", warnings[0].found);
+
+    // A header key whose value is on the lines after it, followed by more of the header.
+    let text = "layout:
+  spring
+  iterations: 50
+number_of_vertices: 2
+directed: false
+edges:
+0 1
+";
+    let g = from_str(text).unwrap();
+    assert!(!g.is_directed());
+    assert_eq!(1, g.number_of_edges());
+    let lines : Vec<usize> = validate_str(&format!("{}colour:\nred\n", text)).iter().map(|d| d.line).collect();
+    assert_eq!(vec![1, 8], lines);
+}
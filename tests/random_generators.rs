@@ -0,0 +1,84 @@
+extern crate graph;
+use graph::*;
+use graph::generators::random::*;
+
+fn edges(g : &Graph<usize>) -> Vec<(usize, usize)> {
+    (0..g.number_of_edges()).map(|e| g.edge_endpoints(EdgeIndex::new(e))).collect()
+}
+
+fn degrees(g : &Graph<usize>) -> Vec<usize> {
+    (0..g.number_of_vertices()).map(|v| g.get_degree_from_index(v)).collect()
+}
+
+#[test]
+fn same_seed_same_graph() {
+    assert_eq!(edges(&gnp_with_seed(30, 0.2, false, 7)), edges(&gnp_with_seed(30, 0.2, false, 7)));
+    assert_ne!(edges(&gnp_with_seed(30, 0.2, false, 7)), edges(&gnp_with_seed(30, 0.2, false, 8)));
+    assert_eq!(edges(&gnm_with_seed(30, 40, true, 7)), edges(&gnm_with_seed(30, 40, true, 7)));
+    assert_eq!(edges(&barabasi_albert_with_seed(30, 2, 7)), edges(&barabasi_albert_with_seed(30, 2, 7)));
+    assert_eq!(edges(&watts_strogatz_with_seed(30, 4, 0.3, 7)), edges(&watts_strogatz_with_seed(30, 4, 0.3, 7)));
+    assert_eq!(edges(&random_regular_with_seed(30, 3, 7)), edges(&random_regular_with_seed(30, 3, 7)));
+    let p = vec![vec![0.5, 0.1], vec![0.1, 0.5]];
+    assert_eq!(edges(&stochastic_block_model_with_seed(&[10, 20], &p, false, 7)),
+               edges(&stochastic_block_model_with_seed(&[10, 20], &p, false, 7)));
+}
+
+#[test]
+fn erdos_renyi() {
+    assert_eq!(0, gnp_with_seed(10, 0.0, true, 1).number_of_edges());
+    assert_eq!(45, gnp_with_seed(10, 1.0, false, 1).number_of_edges());
+    assert_eq!(90, gnp_with_seed(10, 1.0, true, 1).number_of_edges());
+
+    for &(m, directed) in &[(10, false), (40, false), (45, false), (70, true)] {
+        let g = gnm_with_seed(10, m, directed, 3);
+        assert_eq!((10, m), (g.number_of_vertices(), g.number_of_edges()));
+        assert!((0..10).all(|v| !g.has_edge(v, v)));
+    }
+}
+
+#[test]
+fn preferential_attachment_and_small_world() {
+    let g = barabasi_albert_with_seed(50, 3, 11);
+    assert_eq!((50, 47 * 3), (g.number_of_vertices(), g.number_of_edges()));
+    assert!(degrees(&g).iter().all(|&d| d >= 3));
+
+    // With no rewiring it's a ring lattice.
+    let g = watts_strogatz_with_seed(10, 4, 0.0, 11);
+    assert!(degrees(&g).iter().all(|&d| d == 4));
+    assert!(g.has_edge(0, 9) && g.has_edge(0, 8) && !g.has_edge(0, 5));
+    let g = watts_strogatz_with_seed(40, 6, 0.5, 11);
+    assert_eq!(120, g.number_of_edges());
+    assert!((0..40).all(|v| !g.has_edge(v, v)));
+}
+
+#[test]
+fn regular_and_block_model() {
+    // Dense graphs too, which are made as the complement of a sparse one.
+    for &(n, d) in &[(10, 3), (20, 4), (7, 6), (5, 0), (0, 0), (7, 4), (60, 50), (60, 58)] {
+        let g = random_regular_with_seed(n, d, 5);
+        assert_eq!(n * d / 2, g.number_of_edges());
+        assert!(degrees(&g).iter().all(|&degree| degree == d));
+        assert!((0..n).all(|v| !g.has_edge(v, v)));
+    }
+
+    // No edges between the blocks, and every edge within them.
+    let p = vec![vec![1.0, 0.0], vec![0.0, 1.0]];
+    let g = stochastic_block_model_with_seed(&[3, 4], &p, false, 5);
+    assert_eq!(3 + 6, g.number_of_edges());
+    assert!(g.has_edge(4, 6) && !g.has_edge(2, 3));
+    let p = vec![vec![0.0, 1.0], vec![0.0, 0.0]];
+    let g = stochastic_block_model_with_seed(&[2, 2], &p, true, 5);
+    assert_eq!(vec![(0, 2), (0, 3), (1, 2), (1, 3)], edges(&g));
+}
+
+#[test]
+#[should_panic]
+fn odd_total_degree_is_a_panic() {
+    random_regular_with_seed(5, 3, 1);
+}
+
+#[test]
+#[should_panic]
+fn asymmetric_undirected_block_model_is_a_panic() {
+    stochastic_block_model_with_seed(&[2, 2], &[vec![0.5, 0.2], vec![0.1, 0.5]], false, 1);
+}
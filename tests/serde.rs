@@ -0,0 +1,58 @@
+#![cfg(feature = "serde")]
+
+extern crate graph;
+extern crate serde_json;
+
+use graph::*;
+use graph::graph_builders::*;
+
+#[test]
+fn serialize_graph_as_json() {
+    let mut g = unconnected(vec![String::from("a"), String::from("b"), String::from("c")], true);
+    g.add_directed_edge(0, 1);
+    g.add_undirected_edge(1, 2);
+
+    let json = serde_json::to_string(&g).unwrap();
+    assert_eq!("{\"directed\":true,\"nodes\":[\"a\",\"b\",\"c\"],\"edges\":[[0,1],[1,2]],\"undirected_edges\":[1],\
+                \"config\":{\"parallel_edges\":\"Merge\",\"self_loops\":\"Allow\"}}", json);
+
+    let h : Graph<String> = serde_json::from_str(&json).unwrap();
+    assert!(h.is_directed());
+    assert_eq!(2, h.index_from_node(String::from("c")));
+    assert!(h.has_edge(0, 1));
+    assert!(!h.has_edge(1, 0));
+    assert_eq!(h.find_edge(1, 2), h.find_edge(2, 1));
+}
+
+#[test]
+fn deserialize_undirected_graph_without_config() {
+    let g : Graph<usize> = serde_json::from_str("{\"directed\":false,\"nodes\":[10,20,30],\"edges\":[[0,1],[2,1]]}").unwrap();
+    assert!(!g.is_directed());
+    assert_eq!(GraphConfig::default(), g.config());
+    assert_eq!(1, g.index_from_node(20));
+    assert!(g.has_edge(1, 2));
+    assert_eq!(2, g.number_of_edges());
+}
+
+#[test]
+fn deserialize_validates() {
+    let errors = [
+        ("{\"directed\":true,\"nodes\":[1,2,1],\"edges\":[]}", "the nodes must be distinct, but only 2 of the 3 are"),
+        ("{\"directed\":true,\"nodes\":[1,2],\"edges\":[[0,2]]}", "edge 0 joins 0 and 2, but there are only 2 nodes"),
+        ("{\"directed\":true,\"nodes\":[1,2],\"edges\":[[0,1],[0,1]]}", "edge 1 repeats an earlier edge, which the config merges"),
+        ("{\"directed\":true,\"nodes\":[1],\"edges\":[[0,0]],\"config\":{\"parallel_edges\":\"Keep\",\"self_loops\":\"Reject\"}}",
+         "Self-loop on vertex 0 is rejected."),
+    ];
+    for &(json, message) in errors.iter() {
+        let err = serde_json::from_str::<Graph<usize>>(json).err().unwrap();
+        assert!(format!("{}", err).starts_with(message), "{}", err);
+    }
+}
+
+#[test]
+fn serialize_traversal_enums() {
+    assert_eq!("\"Forward\"", serde_json::to_string(&DFSEdgeType::Forward).unwrap());
+    assert_eq!("{\"Processed\":[1,4]}", serde_json::to_string(&DFSTraversalState::Processed(1, 4)).unwrap());
+    let state : BFSTraversalState = serde_json::from_str("\"Discovered\"").unwrap();
+    assert!(state == BFSTraversalState::Discovered);
+}
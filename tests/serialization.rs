@@ -7,6 +7,7 @@ fn serialise_unconnected_graph() {
     let g = unconnected(vec![0, 1, 2, 3], false);
 
     let mut expected_string = String::from("// Graph\n");
+    expected_string.push_str("format_version: 1\n");
     expected_string.push_str("number_of_vertices: 4\n");
     expected_string.push_str("directed: false\n");
     expected_string.push_str("edges:\n");
@@ -22,6 +23,7 @@ fn serialise_undirected_graph() {
     g.add_undirected_edge(0, 1);
 
     let mut expected_string = String::from("// Graph\n");
+    expected_string.push_str("format_version: 1\n");
     expected_string.push_str("number_of_vertices: 4\n");
     expected_string.push_str("directed: false\n");
     expected_string.push_str("edges:\n");
@@ -41,6 +43,7 @@ fn serialise_directed_graph() {
     g.add_directed_edge(0, 1);
 
     let mut expected_string = String::from("// Graph\n");
+    expected_string.push_str("format_version: 1\n");
     expected_string.push_str("number_of_vertices: 4\n");
     expected_string.push_str("directed: true\n");
     expected_string.push_str("edges:\n");
@@ -61,6 +64,7 @@ fn serialise_directed_graph_and_nodes() {
     g.add_directed_edge(0, 1);
 
     let mut expected_string = String::from("// Graph\n");
+    expected_string.push_str("format_version: 1\n");
     expected_string.push_str("number_of_vertices: 4\n");
     expected_string.push_str("directed: true\n");
     expected_string.push_str("edges:\n");
@@ -78,3 +82,52 @@ fn serialise_directed_graph_and_nodes() {
 
     assert_eq!(expected_string, make_serialization_string_with_nodes(&g));
 }
+
+#[test]
+fn write_graph_to_writer() {
+    let mut g = unconnected(vec!["A", "B"], true);
+    g.add_directed_edge(1, 0);
+
+    let mut out = Vec::<u8>::new();
+    write_graph_with_nodes(&g, &mut out).unwrap();
+    assert_eq!(make_serialization_string_with_nodes(&g), String::from_utf8(out).unwrap());
+}
+
+#[test]
+fn write_graph_to_file() {
+    let g = from_file("test_data/graph4").unwrap();
+
+    let path = std::env::temp_dir().join(format!("rust_graph_to_file_{}", std::process::id()));
+    let filename = path.to_str().unwrap();
+    to_file(&g, filename).unwrap();
+    let g2 = from_file(filename).unwrap();
+    std::fs::remove_file(filename).unwrap();
+
+    assert_eq!(make_serialization_string(&g), make_serialization_string(&g2));
+}
+
+#[test]
+fn write_graph_to_file_from_several_threads() {
+    let path = std::env::temp_dir().join(format!("rust_graph_to_file_threads_{}", std::process::id()));
+    let filename = path.to_str().unwrap().to_string();
+    let threads : Vec<_> = (1..9).map(|n| {
+        let filename = filename.clone();
+        std::thread::spawn(move || {
+            let g = unconnected((0..n * 100).collect::<Vec<usize>>(), false);
+            to_file(&g, &filename)
+        })
+    }).collect();
+    for thread in threads {
+        thread.join().unwrap().unwrap();
+    }
+    // Whichever write came last, the file holds all of it.
+    let g = from_file(&filename).unwrap();
+    std::fs::remove_file(&filename).unwrap();
+    assert_eq!(0, g.number_of_vertices() % 100);
+}
+
+#[test]
+fn write_graph_to_missing_directory_fails() {
+    let g = unconnected(vec![0, 1], false);
+    assert!(to_file(&g, "test_data/no_such_directory/graph").is_err());
+}
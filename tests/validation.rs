@@ -0,0 +1,70 @@
+extern crate graph;
+use graph::graph_builders::*;
+
+#[test]
+fn out_of_range_edge_is_an_error() {
+    let err = from_str("number_of_vertices: 2\nedges:\n0 1\n1 2\n").err().unwrap();
+
+    assert_eq!(1, err.diagnostics().len());
+    let d = &err.diagnostics()[0];
+    assert_eq!((4, 3, Section::Edges), (d.line, d.column, d.section));
+    assert_eq!("a vertex index less than 2", d.expected);
+}
+
+#[test]
+fn labels_must_match_number_of_vertices() {
+    let missing = from_str_with_nodes::<String>("number_of_vertices: 3\nedges:\n0 1\nnodes:\nA\nB\n").err().unwrap();
+    assert_eq!(1, missing.diagnostics().len());
+    assert_eq!((7, Section::Nodes), (missing.diagnostics()[0].line, missing.diagnostics()[0].section));
+    assert_eq!("only 2", missing.diagnostics()[0].found);
+
+    let no_section = from_str_with_nodes::<String>("number_of_vertices: 1\n").err().unwrap();
+    assert_eq!((2, Section::Header), (no_section.diagnostics()[0].line, no_section.diagnostics()[0].section));
+
+    let text = "number_of_vertices: 2\nnodes:\nA\nB\nA\n";
+    let extra = from_reader_with_nodes_and_mode::<String, _>(text.as_bytes(), ParseMode::Lenient).err().unwrap();
+    let found : Vec<(usize, &str)> = extra.diagnostics().iter().map(|d| (d.line, d.found.as_str())).collect();
+    assert_eq!(vec![(5, "an extra label"), (5, "a repeat of the label on line 3")], found);
+}
+
+#[test]
+fn validate_reports_duplicate_undirected_edges() {
+    // graph2 lists the undirected edge between 0 and 1 in both directions.
+    let diagnostics = validate_file("test_data/graph2").unwrap();
+
+    assert_eq!(1, diagnostics.len());
+    assert_eq!(6, diagnostics[0].line);
+    assert_eq!("a repeat of the edge on line 5", diagnostics[0].found);
+
+    // But it still loads, with the two merged.
+    assert!(from_file("test_data/graph2").is_ok());
+
+    for filename in &["test_data/graph1", "test_data/graph1_labelled", "test_data/graph3",
+                      "test_data/graph4", "test_data/graph5", "test_data/graph6"] {
+        assert_eq!(Vec::<Diagnostic>::new(), validate_file(filename).unwrap());
+    }
+}
+
+#[test]
+fn validate_reports_every_problem() {
+    let text = "number_of_vertices: 2
+directed: true
+parallel_edges: reject
+self_loops: reject
+edges:
+0 1
+0 1
+1 1
+5 0
+node_attribute: marked bool
+node_attributes:
+7 marked true
+";
+    let diagnostics = validate_str(text);
+    let found : Vec<(usize, usize, &str)> = diagnostics.iter().map(|d| (d.line, d.column, d.found.as_str())).collect();
+
+    assert_eq!(vec![(7, 1, "a repeat of the edge on line 6"),
+                    (8, 1, "a self-loop on vertex 1"),
+                    (9, 1, "5"),
+                    (12, 1, "7")], found);
+}